@@ -0,0 +1,570 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::{get_all, get_opt, get_pages};
+use crate::paging::Paging;
+use crate::{bool_arg, github_delete, github_get, github_put, str_list_arg, time, u64_arg, url_encode};
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+const DEFAULT_CLEANUP_LIMIT: u64 = 100;
+const DEFAULT_FLAKY_RUNS: u64 = 30;
+
+// Seconds between two timestamps; a missing end (still queued or running)
+// counts up to `now`.
+fn elapsed(from: Option<&str>, to: Option<&str>, now: i64) -> i64 {
+    let Some(start) = from.and_then(time::parse) else {
+        return 0;
+    };
+    let end = to.and_then(time::parse).unwrap_or(now);
+    (end - start).max(0)
+}
+
+// Actions permissions live under the org or the repo with the same layout.
+fn policy_base(args: &DataType) -> Option<(String, bool)> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if !owner.is_empty() && !repo.is_empty() {
+        Some((format!("/repos/{owner}/{repo}/actions/permissions"), false))
+    } else if !org.is_empty() {
+        Some((format!("/orgs/{org}/actions/permissions"), true))
+    } else {
+        None
+    }
+}
+
+// The OIDC subject template, also per org or repo.
+fn oidc_path(args: &DataType) -> Option<(String, bool)> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if !owner.is_empty() && !repo.is_empty() {
+        Some((format!("/repos/{owner}/{repo}/actions/oidc/customization/sub"), false))
+    } else if !org.is_empty() {
+        Some((format!("/orgs/{org}/actions/oidc/customization/sub"), true))
+    } else {
+        None
+    }
+}
+
+fn put_section(token: &str, path: &str, body: &Value) -> Value {
+    match github_put(token, path, body) {
+        Ok(data) if data.get("message").is_none() => json!({"ok": true}),
+        Ok(data) => json!({"ok": false, "error": data.get("message")}),
+        Err(e) => json!({"ok": false, "error": e.to_string()}),
+    }
+}
+
+// Shell-style wildcard match (`*` and `?`) for artifact names.
+fn wildcard(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', rest @ ..] => (0..=text.len()).any(|i| wildcard(rest, &text[i..])),
+        [b'?', rest @ ..] => !text.is_empty() && wildcard(rest, &text[1..]),
+        [p, rest @ ..] => text.first() == Some(p) && wildcard(rest, &text[1..]),
+    }
+}
+
+// Per job: (attempt, conclusion) outcomes for each head SHA.
+type JobHistory = BTreeMap<String, Vec<(u64, String)>>;
+
+#[derive(Default)]
+struct JobTotals {
+    attempts: u64,
+    queue_seconds: i64,
+    run_seconds: i64,
+    conclusions: Vec<Value>,
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Lists every attempt of a workflow run with per-job queue time (created to
+/// started) and execution time (started to completed), plus per-job totals
+/// across attempts. Jobs still queued or running count what has elapsed.
+pub(crate) fn get_run_attempts(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let run_id = u64_arg(args, "run_id").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || run_id == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and run_id are required"}),
+        )));
+    }
+    let base = format!("/repos/{owner}/{repo}/actions/runs/{run_id}");
+    let Some(run) = get_opt(token, &base) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("workflow run {run_id} not found")}),
+        )));
+    };
+    let attempt_count = run.get("run_attempt").and_then(|v| v.as_u64()).unwrap_or(1);
+    let now = time::now();
+
+    let mut by_job: BTreeMap<String, JobTotals> = BTreeMap::new();
+    let mut attempts = Vec::new();
+    let (mut total_queue, mut total_run) = (0i64, 0i64);
+    for attempt in 1..=attempt_count {
+        let summary = get_opt(token, &format!("{base}/attempts/{attempt}")).unwrap_or(json!({}));
+        let mut jobs = Vec::new();
+        let (mut queue, mut execution) = (0i64, 0i64);
+        let listing = match get_all(token, &format!("{base}/attempts/{attempt}/jobs")) {
+            Ok(listing) => listing,
+            Err(error) => return Ok(Json(DataType::from_json(error))),
+        };
+        for job in listing.items {
+            let created = job.get("created_at").and_then(|v| v.as_str());
+            let started = job.get("started_at").and_then(|v| v.as_str());
+            let completed = job.get("completed_at").and_then(|v| v.as_str());
+            let queue_seconds = elapsed(created, started, now);
+            let run_seconds = if started.is_some() {
+                elapsed(started, completed, now)
+            } else {
+                0
+            };
+            queue += queue_seconds;
+            execution += run_seconds;
+
+            let name = job.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            let totals = by_job.entry(name.clone()).or_default();
+            totals.attempts += 1;
+            totals.queue_seconds += queue_seconds;
+            totals.run_seconds += run_seconds;
+            totals
+                .conclusions
+                .push(job.get("conclusion").cloned().unwrap_or(Value::Null));
+            jobs.push(json!({
+                "name": name,
+                "status": job.get("status"),
+                "conclusion": job.get("conclusion"),
+                "runner_name": job.get("runner_name"),
+                "queue_seconds": queue_seconds,
+                "run_seconds": run_seconds
+            }));
+        }
+        total_queue += queue;
+        total_run += execution;
+        attempts.push(json!({
+            "attempt": attempt,
+            "status": summary.get("status"),
+            "conclusion": summary.get("conclusion"),
+            "started_at": summary.get("run_started_at"),
+            "triggering_actor": summary.pointer("/triggering_actor/login"),
+            "queue_seconds": queue,
+            "run_seconds": execution,
+            "jobs": jobs
+        }));
+    }
+
+    let mut jobs: Vec<Value> = by_job
+        .into_iter()
+        .map(|(name, t)| {
+            json!({
+                "name": name,
+                "attempts": t.attempts,
+                "queue_seconds": t.queue_seconds,
+                "run_seconds": t.run_seconds,
+                "conclusions": t.conclusions
+            })
+        })
+        .collect();
+    jobs.sort_by_key(|j| std::cmp::Reverse(j["run_seconds"].as_i64().unwrap_or(0)));
+
+    Ok(Json(DataType::from_json(json!({
+        "run_id": run_id,
+        "name": run.get("name"),
+        "head_sha": run.get("head_sha"),
+        "attempt_count": attempt_count,
+        "queue_seconds": total_queue,
+        "run_seconds": total_run,
+        "jobs": jobs,
+        "attempts": attempts
+    }))))
+}
+
+/// Deletes artifacts older than `older_than_days` and/or whose name matches
+/// one of `patterns` (both must hold when both are given), up to `limit`.
+/// Under `dry_run` nothing is deleted and the same report is returned.
+/// Artifacts are listed newest first, so on large repos the oldest ones sit
+/// past the page cap: `truncated` then comes with a `next_page` to pass back
+/// as `page`.
+pub(crate) fn cleanup_artifacts(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let older_than = u64_arg(args, "older_than_days");
+    let patterns = str_list_arg(args, "patterns");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    if older_than.is_none() && patterns.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "older_than_days or patterns is required"}),
+        )));
+    }
+    let dry_run = bool_arg(args, "dry_run");
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_CLEANUP_LIMIT) as usize;
+    let cutoff = older_than.map(|days| time::now() - days as i64 * time::DAY);
+
+    let start = u64_arg(args, "page").unwrap_or(1).max(1);
+    let paging = Paging {
+        page: start,
+        ..Paging::all()
+    };
+    let listing = match get_pages(token, &format!("/repos/{owner}/{repo}/actions/artifacts"), &paging) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let truncated = listing.truncated();
+    let artifacts = listing.items;
+    let scanned = artifacts.len();
+    let matched: Vec<Value> = artifacts
+        .into_iter()
+        // Expired artifacts no longer count against storage.
+        .filter(|a| a.get("expired").and_then(|v| v.as_bool()) != Some(true))
+        .filter(|a| {
+            let created = a.get("created_at").and_then(|v| v.as_str()).and_then(time::parse);
+            cutoff.is_none() || matches!((created, cutoff), (Some(c), Some(cut)) if c < cut)
+        })
+        .filter(|a| {
+            let name = a.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            patterns.is_empty() || patterns.iter().any(|p| wildcard(p.as_bytes(), name.as_bytes()))
+        })
+        .take(limit)
+        .collect();
+
+    let mut reclaimed = 0u64;
+    let mut failed = Vec::new();
+    let mut report = Vec::new();
+    for artifact in &matched {
+        let id = artifact.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        let size = artifact.get("size_in_bytes").and_then(|v| v.as_u64()).unwrap_or(0);
+        if !dry_run {
+            let deleted = github_delete(token, &format!("/repos/{owner}/{repo}/actions/artifacts/{id}"));
+            if !matches!(&deleted, Ok(d) if d.get("message").is_none()) {
+                failed.push(id);
+                continue;
+            }
+        }
+        reclaimed += size;
+        report.push(json!({
+            "id": id,
+            "name": artifact.get("name"),
+            "size_in_bytes": size,
+            "created_at": artifact.get("created_at"),
+            "workflow_run": artifact.pointer("/workflow_run/id")
+        }));
+    }
+
+    // Deleting shifts later artifacts onto earlier pages; resume from the page
+    // the first unscanned one has moved to (re-scanning a few is harmless).
+    let deleted = if dry_run { 0 } else { report.len() };
+    let next_page = truncated.then(|| ((start - 1) * 100 + (scanned - deleted) as u64) / 100 + 1);
+
+    Ok(Json(DataType::from_json(json!({
+        "dry_run": dry_run,
+        "scanned": scanned,
+        "truncated": truncated,
+        "next_page": next_page,
+        "matched": matched.len(),
+        "deleted": deleted,
+        "reclaimed_bytes": reclaimed,
+        "failed": failed,
+        "artifacts": report
+    }))))
+}
+
+/// Actions policy for an org or repo: whether Actions is enabled (and for
+/// which repos, at org level), which actions are allowed, the default
+/// GITHUB_TOKEN permissions, and the fork PR approval policy.
+pub(crate) fn get_actions_policy(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some((base, is_org)) = policy_base(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let Some(permissions) = get_opt(token, &base) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "Actions permissions not found or not accessible"}),
+        )));
+    };
+    // Only meaningful (and only readable) when allowed_actions is "selected".
+    let selected = if permissions.get("allowed_actions").and_then(|v| v.as_str()) == Some("selected") {
+        get_opt(token, &format!("{base}/selected-actions"))
+    } else {
+        None
+    };
+    let workflow = get_opt(token, &format!("{base}/workflow"));
+    let fork_approval = get_opt(token, &format!("{base}/fork-pr-contributor-approval"));
+
+    Ok(Json(DataType::from_json(json!({
+        "scope": if is_org { "org" } else { "repo" },
+        "enabled": permissions.get("enabled"),
+        "enabled_repositories": permissions.get("enabled_repositories"),
+        "allowed_actions": permissions.get("allowed_actions"),
+        "selected_actions": selected,
+        "default_workflow_permissions": workflow.as_ref().and_then(|w| w.get("default_workflow_permissions")),
+        "can_approve_pull_request_reviews": workflow.as_ref().and_then(|w| w.get("can_approve_pull_request_reviews")),
+        "fork_pr_approval_policy": fork_approval.as_ref().and_then(|f| f.get("approval_policy"))
+    }))))
+}
+
+/// Updates the given parts of an org or repo Actions policy; sections with
+/// no arguments are left untouched. Each section reports its own outcome.
+pub(crate) fn set_actions_policy(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some((base, is_org)) = policy_base(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let str_of = |key: &str| args.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let bool_of = |key: &str| args.get(key).and_then(|v| v.to_json().as_bool());
+    let mut results = json!({});
+
+    let mut permissions = json!({});
+    if is_org {
+        if let Some(enabled) = str_of("enabled_repositories") {
+            permissions["enabled_repositories"] = json!(enabled);
+        }
+    } else if let Some(enabled) = bool_of("enabled") {
+        permissions["enabled"] = json!(enabled);
+    }
+    if let Some(allowed) = str_of("allowed_actions") {
+        permissions["allowed_actions"] = json!(allowed);
+    }
+    if permissions.as_object().is_some_and(|p| !p.is_empty()) {
+        // The endpoint requires the enabled flag on every update; keep the
+        // current one rather than guessing, so a policy change never turns
+        // Actions back on.
+        let flag = if is_org { "enabled_repositories" } else { "enabled" };
+        if permissions.get(flag).is_none() {
+            if let Some(current) = get_opt(token, &base).and_then(|c| c.get(flag).cloned()) {
+                permissions[flag] = current;
+            }
+        }
+        results["permissions"] = if permissions.get(flag).is_some() {
+            put_section(token, &base, &permissions)
+        } else {
+            json!({"error": format!("could not read the current `{flag}` setting")})
+        };
+    }
+
+    let patterns = str_list_arg(args, "patterns_allowed");
+    let mut selected = json!({});
+    if let Some(b) = bool_of("github_owned_allowed") {
+        selected["github_owned_allowed"] = json!(b);
+    }
+    if let Some(b) = bool_of("verified_allowed") {
+        selected["verified_allowed"] = json!(b);
+    }
+    if args.get("patterns_allowed").is_some() {
+        selected["patterns_allowed"] = json!(patterns);
+    }
+    if selected.as_object().is_some_and(|s| !s.is_empty()) {
+        results["selected_actions"] = put_section(token, &format!("{base}/selected-actions"), &selected);
+    }
+
+    let mut workflow = json!({});
+    if let Some(default) = str_of("default_workflow_permissions") {
+        workflow["default_workflow_permissions"] = json!(default);
+    }
+    if let Some(b) = bool_of("can_approve_pull_request_reviews") {
+        workflow["can_approve_pull_request_reviews"] = json!(b);
+    }
+    if workflow.as_object().is_some_and(|w| !w.is_empty()) {
+        results["workflow"] = put_section(token, &format!("{base}/workflow"), &workflow);
+    }
+
+    if let Some(policy) = str_of("fork_pr_approval_policy") {
+        results["fork_pr_approval"] = put_section(
+            token,
+            &format!("{base}/fork-pr-contributor-approval"),
+            &json!({"approval_policy": policy}),
+        );
+    }
+
+    if results.as_object().is_some_and(|r| r.is_empty()) {
+        return Ok(Json(DataType::from_json(json!({"error": "no policy settings given"}))));
+    }
+    Ok(Json(DataType::from_json(
+        json!({"scope": if is_org { "org" } else { "repo" }, "results": results}),
+    )))
+}
+
+/// The OIDC token subject claim template: the claim keys joined into `sub`,
+/// and for a repo whether it still uses the default (or inherits the org's).
+pub(crate) fn get_oidc_subject(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some((path, is_org)) = oidc_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let mut data = github_get(token, &path)?;
+    if data.get("include_claim_keys").is_some() || data.get("use_default").is_some() {
+        data["scope"] = json!(if is_org { "org" } else { "repo" });
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Sets the subject claim template to `include_claim_keys` (e.g. repo,
+/// context, job_workflow_ref). A repo can instead go back to the default
+/// with `use_default`. Returns the template as stored afterwards.
+pub(crate) fn set_oidc_subject(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some((path, is_org)) = oidc_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let keys = str_list_arg(args, "include_claim_keys");
+    let use_default = bool_arg(args, "use_default");
+    let body = if use_default && !is_org {
+        json!({"use_default": true})
+    } else if keys.is_empty() {
+        return Ok(Json(DataType::from_json(json!({
+            "error": if is_org { "include_claim_keys is required" } else { "include_claim_keys or use_default is required" }
+        }))));
+    } else if is_org {
+        json!({"include_claim_keys": keys})
+    } else {
+        json!({"use_default": false, "include_claim_keys": keys})
+    };
+    let resp = github_put(token, &path, &body)?;
+    if resp.get("message").is_some() {
+        return Ok(Json(DataType::from_json(resp)));
+    }
+    let mut data = github_get(token, &path)?;
+    data["scope"] = json!(if is_org { "org" } else { "repo" });
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Finds jobs that both passed and failed on the same commit across the
+/// last `runs` completed workflow runs (optionally one `workflow` or
+/// `branch`). A failure followed by a pass in a later attempt of the same
+/// run is counted separately as a rerun pass. Jobs are ranked by the share
+/// of their commits that saw mixed results.
+pub(crate) fn detect_flaky_jobs(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let workflow = args.get("workflow").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    let max_runs = u64_arg(args, "runs").unwrap_or(DEFAULT_FLAKY_RUNS) as usize;
+
+    let mut path = if workflow.is_empty() {
+        format!("/repos/{owner}/{repo}/actions/runs?status=completed")
+    } else {
+        format!("/repos/{owner}/{repo}/actions/workflows/{workflow}/runs?status=completed")
+    };
+    if !branch.is_empty() {
+        path.push_str(&format!("&branch={}", url_encode(branch)));
+    }
+    let Some(listing) = get_opt(token, &format!("{path}&per_page={}", max_runs.clamp(1, 100))) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "could not list workflow runs"}),
+        )));
+    };
+    let runs: Vec<Value> = listing
+        .get("workflow_runs")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().take(max_runs).cloned().collect())
+        .unwrap_or_default();
+
+    let mut jobs: BTreeMap<String, JobHistory> = BTreeMap::new();
+    let mut rerun_passes: BTreeMap<String, u64> = BTreeMap::new();
+    for run in &runs {
+        let Some(run_id) = run.get("id").and_then(|v| v.as_u64()) else {
+            continue;
+        };
+        let sha = run.get("head_sha").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let workflow_name = run.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        // `filter=all` returns the jobs of every attempt of the run.
+        let attempts = match get_all(
+            token,
+            &format!("/repos/{owner}/{repo}/actions/runs/{run_id}/jobs?filter=all"),
+        ) {
+            Ok(listing) => listing.items,
+            Err(error) => return Ok(Json(DataType::from_json(error))),
+        };
+        let mut per_job: BTreeMap<String, Vec<(u64, String)>> = BTreeMap::new();
+        for job in &attempts {
+            let Some(conclusion) = job.get("conclusion").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if conclusion == "skipped" || conclusion == "cancelled" {
+                continue;
+            }
+            let name = format!(
+                "{workflow_name} / {}",
+                job.get("name").and_then(|v| v.as_str()).unwrap_or("")
+            );
+            let attempt = job.get("run_attempt").and_then(|v| v.as_u64()).unwrap_or(1);
+            per_job.entry(name).or_default().push((attempt, conclusion.to_string()));
+        }
+        for (name, mut outcomes) in per_job {
+            outcomes.sort();
+            let rerun_pass = outcomes.windows(2).any(|w| w[0].1 != "success" && w[1].1 == "success");
+            if rerun_pass {
+                *rerun_passes.entry(name.clone()).or_default() += 1;
+            }
+            jobs.entry(name)
+                .or_default()
+                .entry(sha.clone())
+                .or_default()
+                .extend(outcomes);
+        }
+    }
+
+    let mut report: Vec<Value> = jobs
+        .into_iter()
+        .filter_map(|(name, history)| {
+            let shas = history.len();
+            let flaky_shas: Vec<&String> = history
+                .iter()
+                .filter(|(_, outcomes)| {
+                    outcomes.iter().any(|(_, c)| c == "success") && outcomes.iter().any(|(_, c)| c != "success")
+                })
+                .map(|(sha, _)| sha)
+                .collect();
+            let reruns = rerun_passes.get(&name).copied().unwrap_or(0);
+            if flaky_shas.is_empty() && reruns == 0 {
+                return None;
+            }
+            let failures = history.values().flatten().filter(|(_, c)| c != "success").count();
+            Some(json!({
+                "job": name,
+                "flakiness": (flaky_shas.len() as f64 / shas as f64 * 1000.0).round() / 1000.0,
+                "mixed_result_commits": flaky_shas.len(),
+                "commits_observed": shas,
+                "rerun_passes": reruns,
+                "failures": failures,
+                "example_shas": flaky_shas.iter().take(3).collect::<Vec<_>>()
+            }))
+        })
+        .collect();
+    report.sort_by(|a, b| {
+        let key = |v: &Value| {
+            (
+                v["flakiness"].as_f64().unwrap_or(0.0),
+                v["rerun_passes"].as_u64().unwrap_or(0),
+            )
+        };
+        let (ka, kb) = (key(a), key(b));
+        kb.0.total_cmp(&ka.0).then(kb.1.cmp(&ka.1))
+    });
+
+    Ok(Json(DataType::from_json(json!({
+        "runs_analyzed": runs.len(),
+        "flaky_jobs": report
+    }))))
+}
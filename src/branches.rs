@@ -0,0 +1,251 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::confirm::{self, Gate};
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_delete, github_get, github_list, github_post, u64_arg};
+
+const DEFAULT_LIMIT: u64 = 50;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+pub(crate) fn default_branch(token: &str, owner: &str, repo: &str) -> Result<Option<String>, Error> {
+    let meta = github_get(token, &format!("/repos/{owner}/{repo}"))?;
+    Ok(meta.get("default_branch").and_then(|v| v.as_str()).map(str::to_string))
+}
+
+pub(crate) fn branch_tip(token: &str, owner: &str, repo: &str, branch: &str) -> Result<Option<String>, Error> {
+    let data = github_get(token, &format!("/repos/{owner}/{repo}/git/ref/heads/{branch}"))?;
+    Ok(data.pointer("/object/sha").and_then(|s| s.as_str()).map(str::to_string))
+}
+
+pub(crate) fn create_ref(token: &str, owner: &str, repo: &str, branch: &str, sha: &str) -> Result<Value, Error> {
+    github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/git/refs"),
+        &json!({"ref": format!("refs/heads/{branch}"), "sha": sha}),
+    )
+}
+
+// Commit SHA of any branch, tag, or SHA.
+pub(crate) fn resolve_sha(token: &str, owner: &str, repo: &str, git_ref: &str) -> Result<Option<String>, Error> {
+    let commit = github_get(token, &format!("/repos/{owner}/{repo}/commits/{git_ref}"))?;
+    Ok(commit.get("sha").and_then(|s| s.as_str()).map(str::to_string))
+}
+
+fn commit_summary(commit: &Value) -> Value {
+    json!({
+        "sha": commit.get("sha"),
+        "message": commit
+            .pointer("/commit/message")
+            .and_then(|v| v.as_str())
+            .and_then(|m| m.lines().next()),
+        "author": commit.pointer("/author/login").or_else(|| commit.pointer("/commit/author/name")),
+        "date": commit.pointer("/commit/author/date")
+    })
+}
+
+fn commits_of(compare: &Value, limit: usize) -> Vec<Value> {
+    // Compare lists commits oldest first; report the newest.
+    let commits = compare
+        .get("commits")
+        .and_then(|c| c.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    commits.iter().rev().take(limit).map(commit_summary).collect()
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Ahead/behind report for `branch` against `base` (default branch unless
+/// given): diverging commits on both sides and the files the branch changes.
+pub(crate) fn branch_drift(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || branch.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    }
+    let base = match args.get("base").and_then(|v| v.as_str()).filter(|b| !b.is_empty()) {
+        Some(base) => base.to_string(),
+        None => match default_branch(token, owner, repo)? {
+            Some(base) => base,
+            None => {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": "could not resolve default branch"}),
+                )))
+            }
+        },
+    };
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_LIMIT) as usize;
+
+    let ahead = github_get(token, &format!("/repos/{owner}/{repo}/compare/{base}...{branch}"))?;
+    if ahead.get("merge_base_commit").is_none() {
+        return Ok(Json(DataType::from_json(ahead)));
+    }
+    let behind_by = ahead.get("behind_by").and_then(|v| v.as_u64()).unwrap_or(0);
+    let behind_commits = if behind_by > 0 {
+        let behind = github_get(token, &format!("/repos/{owner}/{repo}/compare/{branch}...{base}"))?;
+        commits_of(&behind, limit)
+    } else {
+        Vec::new()
+    };
+
+    let files: Vec<Value> = ahead
+        .get("files")
+        .and_then(|f| f.as_array())
+        .map(|files| {
+            files
+                .iter()
+                .map(|f| {
+                    json!({
+                        "filename": f.get("filename"),
+                        "status": f.get("status"),
+                        "additions": f.get("additions"),
+                        "deletions": f.get("deletions")
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Json(DataType::from_json(json!({
+        "base": base,
+        "branch": branch,
+        "status": ahead.get("status"),
+        "ahead_by": ahead.get("ahead_by"),
+        "behind_by": behind_by,
+        "merge_base_sha": ahead.pointer("/merge_base_commit/sha"),
+        "ahead_commits": commits_of(&ahead, limit),
+        "behind_commits": behind_commits,
+        "files_changed": files.len(),
+        "files": files
+    }))))
+}
+
+pub(crate) fn list_branches(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let mut path = format!("/repos/{owner}/{repo}/branches");
+    if args.get("protected").is_some() {
+        path.push_str(&format!("?protected={}", bool_arg(args, "protected")));
+    }
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_branch(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || branch.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    }
+    let data = github_get(token, &format!("/repos/{owner}/{repo}/branches/{branch}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Creates `branch` at `sha`, or at the tip of `from` (a branch, tag, or
+/// SHA; the default branch when neither is given).
+pub(crate) fn create_branch(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || branch.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    }
+    let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+    let sha = match args.get("sha").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        Some(sha) => sha.to_string(),
+        None => {
+            let from = match args.get("from").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+                Some(from) => Some(from.to_string()),
+                None => default_branch(token, owner, repo)?,
+            };
+            let resolved = match &from {
+                Some(from) => resolve_sha(token, owner, repo, from)?,
+                None => None,
+            };
+            let Some(sha) = resolved else {
+                return Ok(Json(DataType::from_json(json!({
+                    "error": format!("could not resolve {}", from.as_deref().unwrap_or("the default branch"))
+                }))));
+            };
+            sha
+        }
+    };
+    let data = create_ref(token, owner, repo, branch, &sha)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Deletes a branch. The default branch is refused, and a branch that is
+/// not provably merged (commits not on the default branch, or a compare
+/// that failed) goes through the confirmation handshake with those commits
+/// as the preview.
+pub(crate) fn delete_branch(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || branch.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    }
+    let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+    let base = default_branch(token, owner, repo)?;
+    if base.as_deref() == Some(branch) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("{branch} is the default branch and cannot be deleted")}),
+        )));
+    }
+    // Only a compare that reports `ahead_by: 0` proves the branch is merged;
+    // an unknown default branch or a failed compare goes through the gate too.
+    let compare = match &base {
+        Some(base) => github_get(token, &format!("/repos/{owner}/{repo}/compare/{base}...{branch}"))?,
+        None => Value::Null,
+    };
+    let unmerged = compare.get("ahead_by").and_then(|v| v.as_u64());
+    if unmerged != Some(0) {
+        match confirm::gate(&format!("delete_branch:{owner}/{repo}:{branch}"), args) {
+            Gate::Proceed => {}
+            Gate::Invalid(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+            Gate::Pending(confirm_token) => {
+                let message = match unmerged {
+                    Some(_) => "The branch has commits not on the default branch; call again with confirm_token to delete it",
+                    None => "Could not confirm the branch is merged into the default branch; call again with confirm_token to delete it",
+                };
+                return Ok(Json(DataType::from_json(json!({
+                    "confirmation_required": true,
+                    "confirm_token": confirm_token,
+                    "preview": {
+                        "branch": branch,
+                        "unmerged_into": base,
+                        "unmerged_commits": unmerged,
+                        "commits": commits_of(&compare, DEFAULT_LIMIT as usize),
+                        "compare_error": compare.get("message")
+                    },
+                    "message": message
+                }))));
+            }
+        }
+    }
+    let data = github_delete(token, &format!("/repos/{owner}/{repo}/git/refs/heads/{branch}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,125 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::{state, Response};
+
+const INDEX_KEY: &str = "etag/index";
+const MAX_ENTRIES: usize = 64;
+// Plugin vars share a small budget; large payloads are not worth caching.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+// =============================================================================
+// ETag cache for GET requests
+// =============================================================================
+
+pub(crate) fn configure(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+}
+
+pub(crate) struct Entry {
+    pub etag: String,
+    body: Vec<u8>,
+    link: Option<String>,
+}
+
+impl Entry {
+    /// Rebuilds a 200 response from a 304, keeping the fresh headers.
+    pub(crate) fn revalidated(self, mut headers: BTreeMap<String, String>) -> Response {
+        if let Some(link) = self.link {
+            headers.insert("link".to_string(), link);
+        }
+        Response {
+            status: 200,
+            headers,
+            body: self.body,
+        }
+    }
+}
+
+pub(crate) fn lookup(path: &str) -> Option<Entry> {
+    if !ENABLED.with(|e| e.get()) {
+        return None;
+    }
+    let entry = state::load(&entry_key(path))?;
+    Some(Entry {
+        etag: entry.get("etag")?.as_str()?.to_string(),
+        body: entry.get("body")?.as_str()?.as_bytes().to_vec(),
+        link: entry.get("link").and_then(|v| v.as_str()).map(str::to_string),
+    })
+}
+
+pub(crate) fn store(path: &str, resp: &Response) {
+    if !ENABLED.with(|e| e.get()) || resp.status != 200 || resp.body.len() > MAX_BODY_BYTES {
+        return;
+    }
+    let (Some(etag), Ok(body)) = (resp.header("etag"), std::str::from_utf8(&resp.body)) else {
+        return;
+    };
+    let entry = json!({"etag": etag, "body": body, "link": resp.header("link")});
+    if state::save(&entry_key(path), &entry).is_err() {
+        return;
+    }
+    let mut index = load_index();
+    index.retain(|p| p != path);
+    index.push(path.to_string());
+    while index.len() > MAX_ENTRIES {
+        let evicted = index.remove(0);
+        let _ = state::remove(&entry_key(&evicted));
+    }
+    save_index(&index);
+}
+
+/// Drops cached reads of the resource a write touched: the resource itself,
+/// anything below it, and the collections above it (e.g. a PATCH to
+/// `/repos/o/r/issues/5` clears `/repos/o/r/issues?state=open` and
+/// `/repos/o/r`).
+pub(crate) fn invalidate(path: &str) {
+    let mut index = load_index();
+    if index.is_empty() {
+        return;
+    }
+    let written = resource(path);
+    let before = index.len();
+    index.retain(|cached| {
+        let cached_resource = resource(cached);
+        let related = is_within(cached_resource, written) || is_within(written, cached_resource);
+        if related {
+            let _ = state::remove(&entry_key(cached));
+        }
+        !related
+    });
+    if index.len() != before {
+        save_index(&index);
+    }
+}
+
+fn resource(path: &str) -> &str {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    path.trim_end_matches('/')
+}
+
+// Segment-wise prefix check: `/a/b` is within `/a` but not within `/a_`.
+fn is_within(path: &str, prefix: &str) -> bool {
+    path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn entry_key(path: &str) -> String {
+    format!("etag/entry{path}")
+}
+
+fn load_index() -> Vec<String> {
+    match state::load(INDEX_KEY) {
+        Some(Value::Array(paths)) => paths.iter().filter_map(|p| p.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn save_index(index: &[String]) {
+    let _ = state::save(INDEX_KEY, &json!(index));
+}
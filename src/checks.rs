@@ -0,0 +1,212 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_list, github_patch, github_post, u64_arg, url_encode};
+
+// The Checks API accepts at most 50 annotations per request; further batches
+// are appended with follow-up updates.
+const ANNOTATION_BATCH: usize = 50;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn annotations(args: &DataType) -> Vec<Value> {
+    let Some(Value::Array(items)) = args.get("annotations").map(|v| v.to_json()) else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter(|a| a.get("path").is_some() && a.get("message").is_some())
+        .map(|mut a| {
+            if a.get("end_line").is_none() {
+                a["end_line"] = a.get("start_line").cloned().unwrap_or(json!(1));
+            }
+            if a.get("start_line").is_none() {
+                a["start_line"] = a["end_line"].clone();
+            }
+            if a.get("annotation_level").is_none() {
+                a["annotation_level"] = json!("warning");
+            }
+            a
+        })
+        .collect()
+}
+
+// Shared create/update body, minus output annotations.
+fn check_run_body(args: &DataType) -> Map<String, Value> {
+    let mut body = Map::new();
+    for key in [
+        "name",
+        "head_sha",
+        "details_url",
+        "external_id",
+        "status",
+        "conclusion",
+        "started_at",
+        "completed_at",
+    ] {
+        if let Some(v) = args.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()) {
+            body.insert(key.to_string(), json!(v));
+        }
+    }
+    let mut output = Map::new();
+    for key in ["title", "summary", "text"] {
+        if let Some(v) = args.get(key).and_then(|v| v.as_str()) {
+            output.insert(key.to_string(), json!(v));
+        }
+    }
+    if !output.is_empty() {
+        body.insert("output".to_string(), Value::Object(output));
+    }
+    body
+}
+
+// Sends annotations beyond the first batch; GitHub appends them. Stops at
+// the first rejected batch and returns an error naming it (batch 1 is the
+// one sent with the check run itself).
+fn append_annotations(token: &str, path: &str, output: &Value, rest: &[Value]) -> Result<Option<Value>, Error> {
+    for (i, batch) in rest.chunks(ANNOTATION_BATCH).enumerate() {
+        let mut batch_output = json!({
+            "title": output.get("title").cloned().unwrap_or(json!("Annotations")),
+            "summary": output.get("summary").cloned().unwrap_or(json!("")),
+        });
+        batch_output["annotations"] = json!(batch);
+        let data = github_patch(token, path, &json!({"output": batch_output}))?;
+        if data.get("message").is_some() || data.get("success") == Some(&json!(false)) {
+            let message = data.get("message").and_then(|m| m.as_str()).unwrap_or("request failed");
+            let sent = ANNOTATION_BATCH * (i + 1);
+            return Ok(Some(json!({
+                "error": format!("annotation batch {} was rejected: {message}", i + 2),
+                "annotations_sent": sent,
+                "annotations_unsent": rest.len() + ANNOTATION_BATCH - sent,
+                "status": data.get("status"),
+                "errors": data.get("errors")
+            })));
+        }
+    }
+    Ok(None)
+}
+
+fn with_annotations(body: &mut Map<String, Value>, first: &[Value]) -> Result<(), String> {
+    if first.is_empty() {
+        return Ok(());
+    }
+    let Some(output) = body.get_mut("output") else {
+        return Err("title and summary are required when sending annotations".to_string());
+    };
+    if output.get("title").is_none() || output.get("summary").is_none() {
+        return Err("title and summary are required when sending annotations".to_string());
+    }
+    output["annotations"] = json!(first);
+    Ok(())
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Requires GitHub App authentication: the configured token must be an
+/// installation token for an App with `checks:write`.
+pub(crate) fn create_check_run(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let head_sha = args.get("head_sha").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || name.is_empty() || head_sha.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, name, and head_sha are required"}),
+        )));
+    }
+    let all = annotations(args);
+    let (first, rest) = all.split_at(all.len().min(ANNOTATION_BATCH));
+    let mut body = check_run_body(args);
+    if let Err(e) = with_annotations(&mut body, first) {
+        return Ok(Json(DataType::from_json(json!({"error": e}))));
+    }
+    let body = Value::Object(body);
+    let created = github_post(token, &format!("/repos/{owner}/{repo}/check-runs"), &body)?;
+    let Some(id) = created.get("id").and_then(|v| v.as_u64()) else {
+        return Ok(Json(DataType::from_json(created)));
+    };
+    let path = format!("/repos/{owner}/{repo}/check-runs/{id}");
+    if let Some(mut failed) = append_annotations(token, &path, &body["output"], rest)? {
+        failed["check_run_id"] = json!(id);
+        return Ok(Json(DataType::from_json(failed)));
+    }
+    Ok(Json(DataType::from_json(created)))
+}
+
+pub(crate) fn update_check_run(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let id = u64_arg(args, "check_run_id").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || id == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and check_run_id are required"}),
+        )));
+    }
+    let all = annotations(args);
+    let (first, rest) = all.split_at(all.len().min(ANNOTATION_BATCH));
+    let mut body = check_run_body(args);
+    body.remove("head_sha");
+    if let Err(e) = with_annotations(&mut body, first) {
+        return Ok(Json(DataType::from_json(json!({"error": e}))));
+    }
+    let body = Value::Object(body);
+    let path = format!("/repos/{owner}/{repo}/check-runs/{id}");
+    let updated = github_patch(token, &path, &body)?;
+    if updated.get("id").is_some() {
+        if let Some(mut failed) = append_annotations(token, &path, &body["output"], rest)? {
+            failed["check_run_id"] = json!(id);
+            return Ok(Json(DataType::from_json(failed)));
+        }
+    }
+    Ok(Json(DataType::from_json(updated)))
+}
+
+pub(crate) fn list_check_suites(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let git_ref = args.get("ref").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || git_ref.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and ref are required"}),
+        )));
+    }
+    let mut filters = Vec::new();
+    if let Some(app_id) = u64_arg(args, "app_id") {
+        filters.push(format!("app_id={app_id}"));
+    }
+    if let Some(name) = args.get("check_name").and_then(|v| v.as_str()) {
+        filters.push(format!("check_name={}", url_encode(name)));
+    }
+    let mut path = format!("/repos/{owner}/{repo}/commits/{git_ref}/check-suites");
+    if !filters.is_empty() {
+        path.push_str(&format!("?{}", filters.join("&")));
+    }
+    let data = github_list(token, &path, &Paging::from_args(args, 100), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Asks the owning App to re-run a suite; only the App that created it (via
+/// its installation token) may rerequest.
+pub(crate) fn rerequest_check_suite(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let id = u64_arg(args, "check_suite_id").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || id == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and check_suite_id are required"}),
+        )));
+    }
+    let data = github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/check-suites/{id}/rerequest"),
+        &json!({}),
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,352 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::branches::{branch_tip, create_ref};
+use crate::composite::get_all;
+use crate::signing;
+use crate::trailers::{self, Trailer};
+use crate::{bool_arg, github_delete, github_get, github_patch, github_post, state, str_list_arg, u64_arg};
+
+const TEMP_COUNTER: &str = "cherry_pick/next";
+
+// =============================================================================
+// Commit replay via the Git data API
+// =============================================================================
+
+pub(crate) enum Replay {
+    /// SHA of the new commit, parented on the target tip.
+    Applied(String),
+    /// The change doesn't apply cleanly (or can't be replayed at all).
+    Conflict(String),
+}
+
+fn git_commit(token: &str, owner: &str, repo: &str, sha: &str) -> Result<Value, Error> {
+    github_get(token, &format!("/repos/{owner}/{repo}/git/commits/{sha}"))
+}
+
+fn create_commit(token: &str, owner: &str, repo: &str, body: &Value) -> Result<Option<String>, Error> {
+    let mut body = body.clone();
+    signing::sign_commit(&mut body);
+    let created = github_post(token, &format!("/repos/{owner}/{repo}/git/commits"), &body)?;
+    Ok(created.get("sha").and_then(|s| s.as_str()).map(str::to_string))
+}
+
+/// Replays `sha` (or its inverse when `revert`) on top of `onto`, with
+/// `trailers` appended to the message.
+///
+/// There is no server-side cherry-pick, so the three-way merge is borrowed
+/// from the merges endpoint: a throwaway commit with `onto`'s tree is
+/// parented on the merge base we want (the picked commit's parent, or the
+/// commit itself for a revert), and merging the other side into it yields
+/// the replayed tree. The result is committed fresh on top of `onto`.
+pub(crate) fn replay(
+    token: &str,
+    owner: &str,
+    repo: &str,
+    onto: &str,
+    sha: &str,
+    revert: bool,
+    trailers: &[Trailer],
+) -> Result<Replay, Error> {
+    let commit = git_commit(token, owner, repo, sha)?;
+    let parents: Vec<&str> = commit
+        .get("parents")
+        .and_then(|p| p.as_array())
+        .map(|p| p.iter().filter_map(|c| c.get("sha").and_then(|s| s.as_str())).collect())
+        .unwrap_or_default();
+    let [parent] = parents[..] else {
+        return Ok(Replay::Conflict(format!(
+            "{sha} has {} parents; only single-parent commits can be replayed",
+            parents.len()
+        )));
+    };
+    let target = git_commit(token, owner, repo, onto)?;
+    let Some(onto_tree) = target.pointer("/tree/sha").and_then(|s| s.as_str()) else {
+        return Ok(Replay::Conflict(format!("could not read commit {onto}")));
+    };
+
+    let (base, theirs) = if revert {
+        let parent_tree = git_commit(token, owner, repo, parent)?;
+        let inverse = create_commit(
+            token,
+            owner,
+            repo,
+            &json!({"message": "revert", "tree": parent_tree.pointer("/tree/sha"), "parents": [sha]}),
+        )?;
+        let Some(inverse) = inverse else {
+            return Ok(Replay::Conflict(format!("could not build the inverse of {sha}")));
+        };
+        (sha.to_string(), inverse)
+    } else {
+        (parent.to_string(), sha.to_string())
+    };
+    let Some(temp) = create_commit(
+        token,
+        owner,
+        repo,
+        &json!({"message": "replay", "tree": onto_tree, "parents": [base]}),
+    )?
+    else {
+        return Ok(Replay::Conflict(format!("could not stage {onto} for replay")));
+    };
+
+    let temp_branch = format!("replay-tmp-{}", state::next_id(TEMP_COUNTER));
+    github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/git/refs"),
+        &json!({"ref": format!("refs/heads/{temp_branch}"), "sha": temp}),
+    )?;
+    let merged = github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/merges"),
+        &json!({"base": temp_branch, "head": theirs, "commit_message": "replay"}),
+    );
+    let _ = github_delete(token, &format!("/repos/{owner}/{repo}/git/refs/heads/{temp_branch}"));
+    let merged = merged?;
+    let Some(tree) = merged.pointer("/commit/tree/sha").and_then(|s| s.as_str()) else {
+        // 409 "Merge conflict", or 204 when there is nothing left to apply.
+        let reason = merged
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("nothing to apply");
+        return Ok(Replay::Conflict(reason.to_string()));
+    };
+
+    let original = commit.get("message").and_then(|m| m.as_str()).unwrap_or("");
+    let message = if revert {
+        let title = original.lines().next().unwrap_or("");
+        format!("Revert \"{title}\"\n\nThis reverts commit {sha}.")
+    } else {
+        format!("{}\n\n(cherry picked from commit {sha})", original.trim_end())
+    };
+    let message = trailers::apply(&message, trailers);
+    let mut body = json!({"message": message, "tree": tree, "parents": [onto]});
+    if !revert {
+        // Keep the original authorship; the token user becomes the committer.
+        body["author"] = commit.get("author").cloned().unwrap_or(Value::Null);
+    }
+    Ok(match create_commit(token, owner, repo, &body)? {
+        Some(new_sha) => Replay::Applied(new_sha),
+        None => Replay::Conflict("could not create the replayed commit".to_string()),
+    })
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn cherry_pick_commit(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    apply(token, args, false)
+}
+
+pub(crate) fn revert_commit(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    apply(token, args, true)
+}
+
+/// Replays a commit onto `branch`. By default the branch is fast-forwarded
+/// to the new commit; with `open_pr` the result goes on a new branch
+/// (`head`, or a generated name) and a PR against `branch` is opened instead.
+fn apply(token: &str, args: &DataType, revert: bool) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let sha = args.get("sha").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || sha.is_empty() || branch.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, sha, and branch are required"}),
+        )));
+    }
+    let trailers = match trailers::from_args(args) {
+        Ok(trailers) => trailers,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let Some(tip) = branch_tip(token, owner, repo, branch)? else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("branch not found: {branch}")}),
+        )));
+    };
+    let new_sha = match replay(token, owner, repo, &tip, sha, revert, &trailers)? {
+        Replay::Applied(new_sha) => new_sha,
+        Replay::Conflict(reason) => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": format!("{sha} does not apply cleanly onto {branch}: {reason}"), "conflict": true}),
+            )))
+        }
+    };
+
+    if !bool_arg(args, "open_pr") {
+        let data = github_patch(
+            token,
+            &format!("/repos/{owner}/{repo}/git/refs/heads/{branch}"),
+            &json!({"sha": new_sha, "force": false}),
+        )?;
+        // A rejected update (not a fast-forward, protected branch) leaves the
+        // new commit on no branch; say so rather than report success.
+        if data.get("message").is_some() || data.get("success") == Some(&json!(false)) {
+            let reason = data.get("message").and_then(|m| m.as_str()).unwrap_or("request failed");
+            return Ok(Json(DataType::from_json(json!({
+                "error": format!("could not update {branch}: {reason}; commit {new_sha} was created but is on no branch"),
+                "orphaned_commit": new_sha,
+                "status": data.get("status")
+            }))));
+        }
+        return Ok(Json(DataType::from_json(
+            json!({"branch": branch, "commit": new_sha, "ref": data}),
+        )));
+    }
+
+    let action = if revert { "revert" } else { "cherry-pick" };
+    let short = &sha[..sha.len().min(7)];
+    let head = match args.get("head").and_then(|v| v.as_str()).filter(|h| !h.is_empty()) {
+        Some(head) => head.to_string(),
+        None => format!("{action}-{short}-{branch}"),
+    };
+    // An existing `head` (e.g. from re-running the same pick) is left alone;
+    // opening a PR from it would not include the new commit.
+    let created = create_ref(token, owner, repo, &head, &new_sha)?;
+    if created.get("ref").is_none() {
+        let reason = created
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("request failed");
+        return Ok(Json(DataType::from_json(json!({
+            "error": format!("could not create {head}: {reason}; commit {new_sha} was created but is on no branch"),
+            "orphaned_commit": new_sha,
+            "status": created.get("status")
+        }))));
+    }
+    let message = git_commit(token, owner, repo, &new_sha)?;
+    let title = message
+        .get("message")
+        .and_then(|m| m.as_str())
+        .and_then(|m| m.lines().next())
+        .unwrap_or(action)
+        .to_string();
+    let pr = github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/pulls"),
+        &json!({
+            "title": format!("[{branch}] {title}"),
+            "head": head,
+            "base": branch,
+            "body": format!("Automated {action} of {sha} onto `{branch}`.")
+        }),
+    )?;
+    Ok(Json(DataType::from_json(
+        json!({"branch": head, "commit": new_sha, "pull_request": pr}),
+    )))
+}
+
+/// Backports a merged PR to each of `branches`: its commits are replayed in
+/// order onto a fresh `backport-{number}-to-{branch}` branch and a labeled PR
+/// is opened against the target. Branches where a commit doesn't apply are
+/// reported with the offending commit and left untouched.
+pub(crate) fn backport_pr(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    let targets = str_list_arg(args, "branches");
+    if owner.is_empty() || repo.is_empty() || number == 0 || targets.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and branches are required"}),
+        )));
+    }
+    let label = args.get("label").and_then(|v| v.as_str()).unwrap_or("backport");
+    let trailers = match trailers::from_args(args) {
+        Ok(trailers) => trailers,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+
+    let pr = github_get(token, &format!("/repos/{owner}/{repo}/pulls/{number}"))?;
+    if pr.get("merged").and_then(|m| m.as_bool()) != Some(true) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("pull request #{number} is not merged")}),
+        )));
+    }
+    let title = pr.get("title").and_then(|t| t.as_str()).unwrap_or("");
+    // The PR's own commits replay correctly whatever the merge method was.
+    let commits = match get_all(token, &format!("/repos/{owner}/{repo}/pulls/{number}/commits")) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    // Replaying only part of a PR would open a backport that silently drops
+    // its later commits. GitHub also stops listing PR commits at 250.
+    let expected = pr.get("commits").and_then(|c| c.as_u64()).unwrap_or(0);
+    if commits.truncated() || expected > commits.items.len() as u64 {
+        return Ok(Json(DataType::from_json(json!({
+            "error": format!(
+                "pull request #{number} has more commits than could be listed ({}); backport it by hand",
+                commits.items.len()
+            )
+        }))));
+    }
+    let shas: Vec<String> = commits
+        .items
+        .iter()
+        .filter_map(|c| c.get("sha").and_then(|s| s.as_str()).map(str::to_string))
+        .collect();
+    if shas.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("no commits found for pull request #{number}")}),
+        )));
+    }
+
+    let mut results = Vec::new();
+    'targets: for target in &targets {
+        let Some(mut tip) = branch_tip(token, owner, repo, target)? else {
+            results.push(json!({"branch": target, "status": "error", "error": "branch not found"}));
+            continue;
+        };
+        for sha in &shas {
+            match replay(token, owner, repo, &tip, sha, false, &trailers)? {
+                Replay::Applied(new_sha) => tip = new_sha,
+                Replay::Conflict(reason) => {
+                    results.push(json!({"branch": target, "status": "conflict", "commit": sha, "reason": reason}));
+                    continue 'targets;
+                }
+            }
+        }
+
+        let head = format!("backport-{number}-to-{target}");
+        let created = create_ref(token, owner, repo, &head, &tip)?;
+        if created.get("ref").is_none() {
+            results.push(json!({"branch": target, "status": "error", "error": created.get("message")}));
+            continue;
+        }
+        let backport = github_post(
+            token,
+            &format!("/repos/{owner}/{repo}/pulls"),
+            &json!({
+                "title": format!("[Backport {target}] {title}"),
+                "head": head,
+                "base": target,
+                "body": format!("Backport of #{number} to `{target}`.")
+            }),
+        )?;
+        let Some(backport_number) = backport.get("number").and_then(|n| n.as_u64()) else {
+            results.push(json!({"branch": target, "status": "error", "error": backport.get("message")}));
+            continue;
+        };
+        github_post(
+            token,
+            &format!("/repos/{owner}/{repo}/issues/{backport_number}/labels"),
+            &json!({"labels": [label]}),
+        )?;
+        results.push(json!({
+            "branch": target,
+            "status": "opened",
+            "head": head,
+            "number": backport_number,
+            "html_url": backport.get("html_url")
+        }));
+    }
+
+    let conflicts = results.iter().filter(|r| r["status"] == "conflict").count();
+    Ok(Json(DataType::from_json(json!({
+        "pull_request": number,
+        "commits": shas.len(),
+        "conflicts": conflicts,
+        "results": results
+    }))))
+}
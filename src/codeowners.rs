@@ -0,0 +1,100 @@
+use crate::templates;
+
+// GitHub uses the first CODEOWNERS file found in this order.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+pub(crate) struct Rule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+}
+
+pub(crate) struct CodeOwners {
+    pub path: String,
+    pub rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    pub(crate) fn fetch(token: &str, owner: &str, repo: &str, git_ref: Option<&str>) -> Option<Self> {
+        LOCATIONS.iter().find_map(|path| {
+            let text = templates::fetch_text(token, owner, repo, path, git_ref)?;
+            Some(CodeOwners {
+                path: path.to_string(),
+                rules: parse(&text),
+            })
+        })
+    }
+
+    /// Owners of `path`: the last matching rule wins, as on GitHub. A rule
+    /// with no owners explicitly leaves the path unowned.
+    pub(crate) fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| pattern_matches(&r.pattern, path))
+            .map(|r| r.owners.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+fn parse(text: &str) -> Vec<Rule> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next()?.to_string();
+            Some(Rule {
+                pattern,
+                owners: parts.map(str::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+// gitignore-style matching: patterns without an inner slash match at any
+// depth, a trailing slash matches directories only, and a match on a
+// directory covers everything below it.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+
+    // Candidate subjects: every ancestor directory, plus the file itself.
+    // `dir/*` is the exception: it covers direct children only.
+    let mut subjects: Vec<&str> = if pattern.ends_with("/*") {
+        Vec::new()
+    } else {
+        path.match_indices('/').map(|(i, _)| &path[..i]).collect()
+    };
+    if !dir_only {
+        subjects.push(path);
+    }
+    subjects.iter().any(|subject| {
+        if anchored {
+            glob(pattern.as_bytes(), subject.as_bytes())
+        } else {
+            let name = subject.rsplit('/').next().unwrap_or(subject);
+            glob(pattern.as_bytes(), name.as_bytes())
+        }
+    })
+}
+
+// `**` spans slashes, `*` and `?` do not.
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| glob(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob(rest, &text[i..])),
+        [b'?', rest @ ..] => matches!(text, [c, tail @ ..] if *c != b'/' && glob(rest, tail)),
+        [p, rest @ ..] => matches!(text, [c, tail @ ..] if c == p && glob(rest, tail)),
+    }
+}
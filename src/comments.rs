@@ -0,0 +1,129 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::json;
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_list, github_patch, github_post, u64_arg};
+
+const REACTIONS: &[&str] = &["+1", "-1", "laugh", "confused", "heart", "hooray", "rocket", "eyes"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn repo_path(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some(format!("/repos/{owner}/{repo}"))
+}
+
+// Reactable subject: the issue/PR itself (`number`), an issue comment
+// (`comment_id`), or an inline review comment (`review_comment_id`).
+fn reaction_target(base: &str, args: &DataType) -> Option<String> {
+    if let Some(id) = u64_arg(args, "review_comment_id") {
+        return Some(format!("{base}/pulls/comments/{id}/reactions"));
+    }
+    if let Some(id) = u64_arg(args, "comment_id") {
+        return Some(format!("{base}/issues/comments/{id}/reactions"));
+    }
+    u64_arg(args, "number").map(|n| format!("{base}/issues/{n}/reactions"))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Conversation comments on an issue or PR (not inline review comments).
+pub(crate) fn list_comments(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (Some(base), Some(number)) = (repo_path(args), u64_arg(args, "number")) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    };
+    let mut path = format!("{base}/issues/{number}/comments");
+    if let Some(since) = args.get("since").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        path.push_str(&format!("?since={since}"));
+    }
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn create_comment(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let body = args.get("body").and_then(|v| v.as_str()).unwrap_or("");
+    let (Some(base), Some(number)) = (repo_path(args), u64_arg(args, "number")) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and body are required"}),
+        )));
+    };
+    if body.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and body are required"}),
+        )));
+    }
+    let data = github_post(
+        token,
+        &format!("{base}/issues/{number}/comments"),
+        &json!({"body": body}),
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn update_comment(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let body = args.get("body").and_then(|v| v.as_str()).unwrap_or("");
+    let (Some(base), Some(id)) = (repo_path(args), u64_arg(args, "comment_id")) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, comment_id, and body are required"}),
+        )));
+    };
+    if body.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, comment_id, and body are required"}),
+        )));
+    }
+    let data = github_patch(token, &format!("{base}/issues/comments/{id}"), &json!({"body": body}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn delete_comment(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (Some(base), Some(id)) = (repo_path(args), u64_arg(args, "comment_id")) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and comment_id are required"}),
+        )));
+    };
+    let data = github_delete(token, &format!("{base}/issues/comments/{id}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn add_reaction(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let content = args.get("content").and_then(|v| v.as_str()).unwrap_or("");
+    if !REACTIONS.contains(&content) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("content must be one of: {}", REACTIONS.join(", "))}),
+        )));
+    }
+    let Some(path) = repo_path(args).and_then(|base| reaction_target(&base, args)) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and one of number, comment_id, or review_comment_id are required"}),
+        )));
+    };
+    // 200 when the token already reacted this way, 201 when new.
+    let data = github_post(token, &path, &json!({"content": content}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn remove_reaction(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(reaction_id) = u64_arg(args, "reaction_id") else {
+        return Ok(Json(DataType::from_json(json!({"error": "reaction_id is required"}))));
+    };
+    let Some(path) = repo_path(args).and_then(|base| reaction_target(&base, args)) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and one of number, comment_id, or review_comment_id are required"}),
+        )));
+    };
+    let data = github_delete(token, &format!("{path}/{reaction_id}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
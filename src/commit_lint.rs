@@ -0,0 +1,165 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_all;
+use crate::u64_arg;
+
+const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+const DEFAULT_MAX_SUBJECT: u64 = 72;
+
+struct Convention {
+    conventional: bool,
+    types: Vec<String>,
+    max_subject_length: u64,
+    require_issue_ref: bool,
+    skip_merges: bool,
+}
+
+impl Convention {
+    // `commit_convention` from plugin config, with per-call `convention` args
+    // taking precedence field by field.
+    fn resolve(config: &DataType, args: &DataType) -> Convention {
+        let base = config
+            .get("commit_convention")
+            .map(|v| v.to_json())
+            .unwrap_or(Value::Null);
+        let over = args.get("convention").map(|v| v.to_json()).unwrap_or(Value::Null);
+        let field = |key: &str| over.get(key).or_else(|| base.get(key)).cloned();
+        Convention {
+            conventional: field("conventional").and_then(|v| v.as_bool()).unwrap_or(true),
+            types: field("types")
+                .and_then(|v| v.as_array().cloned())
+                .map(|t| t.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_else(|| DEFAULT_TYPES.iter().map(|t| t.to_string()).collect()),
+            max_subject_length: field("max_subject_length")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_MAX_SUBJECT),
+            require_issue_ref: field("require_issue_ref").and_then(|v| v.as_bool()).unwrap_or(false),
+            skip_merges: field("skip_merges").and_then(|v| v.as_bool()).unwrap_or(true),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "conventional": self.conventional,
+            "types": self.types,
+            "max_subject_length": self.max_subject_length,
+            "require_issue_ref": self.require_issue_ref,
+            "skip_merges": self.skip_merges
+        })
+    }
+
+    fn check(&self, message: &str) -> Vec<String> {
+        let subject = message.lines().next().unwrap_or("").trim_end();
+        let mut violations = Vec::new();
+        if subject.is_empty() {
+            violations.push("empty subject line".to_string());
+            return violations;
+        }
+        let len = subject.chars().count() as u64;
+        if len > self.max_subject_length {
+            violations.push(format!("subject is {len} characters (max {})", self.max_subject_length));
+        }
+        if self.conventional {
+            if let Err(reason) = self.check_conventional(subject) {
+                violations.push(reason);
+            }
+        }
+        if self.require_issue_ref && !has_issue_ref(message) {
+            violations.push("no issue reference (e.g. #123 or owner/repo#123)".to_string());
+        }
+        if message.lines().nth(1).is_some_and(|l| !l.trim().is_empty()) {
+            violations.push("subject must be followed by a blank line".to_string());
+        }
+        violations
+    }
+
+    // type(scope)!: description
+    fn check_conventional(&self, subject: &str) -> Result<(), String> {
+        let Some((head, description)) = subject.split_once(": ") else {
+            return Err("not in `type(scope): description` form".to_string());
+        };
+        if description.trim().is_empty() {
+            return Err("empty description after type".to_string());
+        }
+        let head = head.strip_suffix('!').unwrap_or(head);
+        let kind = match head.split_once('(') {
+            Some((kind, scope)) => {
+                if !scope.ends_with(')') || scope.len() < 2 {
+                    return Err(format!("malformed scope in `{head}`"));
+                }
+                kind
+            }
+            None => head,
+        };
+        if !self.types.iter().any(|t| t == kind) {
+            return Err(format!("type `{kind}` is not one of: {}", self.types.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+fn has_issue_ref(message: &str) -> bool {
+    let bytes = message.as_bytes();
+    let digit_at = |i: usize| bytes.get(i).is_some_and(|c| c.is_ascii_digit());
+    (0..bytes.len())
+        .any(|i| (bytes[i] == b'#' && digit_at(i + 1)) || (bytes[i..].starts_with(b"GH-") && digit_at(i + 3)))
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+pub(crate) fn check_pr_commits(token: &str, args: &DataType, config: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let convention = Convention::resolve(config, args);
+
+    // The endpoint caps at 250 commits; `truncated` says when a PR has more.
+    let listing = match get_all(token, &format!("/repos/{owner}/{repo}/pulls/{number}/commits")) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let commits = &listing.items;
+
+    let mut results = Vec::new();
+    let mut failing = 0;
+    for commit in commits {
+        let message = commit.pointer("/commit/message").and_then(|v| v.as_str()).unwrap_or("");
+        let is_merge = commit
+            .get("parents")
+            .and_then(|p| p.as_array())
+            .is_some_and(|p| p.len() > 1);
+        if is_merge && convention.skip_merges {
+            continue;
+        }
+        let violations = convention.check(message);
+        if !violations.is_empty() {
+            failing += 1;
+        }
+        results.push(json!({
+            "sha": commit.get("sha"),
+            "subject": message.lines().next().unwrap_or(""),
+            "author": commit.pointer("/author/login").or_else(|| commit.pointer("/commit/author/name")),
+            "violations": violations
+        }));
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "passed": failing == 0,
+        "checked": results.len(),
+        "failing": failing,
+        "truncated": listing.truncated() || commits.len() >= 250,
+        "convention": convention.to_json(),
+        "commits": results
+    }))))
+}
@@ -0,0 +1,355 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_get, github_get_text, github_list, u64_arg, url_encode};
+
+const MAX_HISTORY: u64 = 300;
+
+// =============================================================================
+// Signature verification
+// =============================================================================
+
+/// Flattens `commit.verification` into `{verified, reason, signer,
+/// verified_at}`. REST doesn't name the signer, but GitHub only verifies
+/// against the committer's keys, so the committer is reported.
+pub(crate) fn verification(commit: &Value) -> Value {
+    let v = commit.pointer("/commit/verification").cloned().unwrap_or(Value::Null);
+    let verified = v.get("verified").and_then(|b| b.as_bool()).unwrap_or(false);
+    json!({
+        "verified": verified,
+        "reason": v.get("reason").cloned().unwrap_or(json!("unsigned")),
+        "signer": if verified {
+            commit.pointer("/committer/login").or_else(|| commit.pointer("/commit/committer/email")).cloned()
+        } else {
+            None
+        },
+        "verified_at": v.get("verified_at")
+    })
+}
+
+fn verified_filter(args: &DataType) -> Option<bool> {
+    match args.get("verified").map(|v| v.to_json())? {
+        Value::Bool(b) => Some(b),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+// Where `path` came from in the commit that created it: the previous name of
+// a rename GitHub detected, or else a file with the same basename that the
+// same commit removed (a move with heavy edits isn't reported as a rename).
+fn renamed_from(token: &str, owner: &str, repo: &str, sha: &str, path: &str) -> Result<Option<String>, Error> {
+    let commit = github_get(token, &format!("/repos/{owner}/{repo}/commits/{sha}"))?;
+    let files = commit
+        .get("files")
+        .and_then(|f| f.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let status = |f: &Value| f.get("status").and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let name = |f: &Value| f.get("filename").and_then(|s| s.as_str()).unwrap_or("").to_string();
+    let Some(entry) = files.iter().find(|f| name(f) == path) else {
+        return Ok(None);
+    };
+    match status(entry).as_str() {
+        "renamed" => Ok(entry
+            .get("previous_filename")
+            .and_then(|p| p.as_str())
+            .map(str::to_string)),
+        "added" => {
+            let basename = path.rsplit('/').next().unwrap_or(path);
+            Ok(files
+                .iter()
+                .filter(|f| status(f) == "removed")
+                .map(name)
+                .find(|removed| removed.rsplit('/').next() == Some(basename)))
+        }
+        _ => Ok(None),
+    }
+}
+
+// GitHub omits `patch` for binary files and for diffs too large to inline;
+// say which, so callers don't read a missing patch as "no change".
+pub(crate) fn annotate_patch(file: &mut Value) {
+    if file.get("patch").is_some() {
+        return;
+    }
+    let changes = file.get("changes").and_then(|c| c.as_u64()).unwrap_or(0);
+    file["patch_omitted"] = json!(if changes == 0 {
+        "binary or no content change"
+    } else {
+        "too large"
+    });
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn list_commits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let filters: Vec<String> = ["sha", "path", "author", "committer", "since", "until"]
+        .iter()
+        .filter_map(|key| {
+            let value = args.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())?;
+            Some(format!("{key}={}", url_encode(value)))
+        })
+        .collect();
+    let mut path = format!("/repos/{owner}/{repo}/commits");
+    if !filters.is_empty() {
+        path.push_str(&format!("?{}", filters.join("&")));
+    }
+    let mut data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    let filter = verified_filter(args);
+    if let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) {
+        for item in items.iter_mut() {
+            item["verification"] = verification(item);
+        }
+        if let Some(wanted) = filter {
+            items.retain(|item| item["verification"]["verified"].as_bool() == Some(wanted));
+        }
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_commit(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let git_ref = args.get("ref").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || git_ref.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and ref are required"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/commits/{git_ref}");
+    if let media @ ("diff" | "patch") = args.get("format").and_then(|v| v.as_str()).unwrap_or("json") {
+        let text = github_get_text(token, &path, media)?;
+        return Ok(Json(DataType::from_json(json!({"format": media, "content": text}))));
+    }
+    let mut data = github_get(token, &path)?;
+    if data.get("sha").is_some() {
+        data["verification"] = verification(&data);
+    }
+    if let Some(files) = data.get_mut("files").and_then(|f| f.as_array_mut()) {
+        for file in files.iter_mut() {
+            annotate_patch(file);
+        }
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// `base...head`: ahead/behind counts, the commits head adds, and per-file
+/// patches (`format` diff or patch returns the raw text instead).
+pub(crate) fn compare_commits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let base = args.get("base").and_then(|v| v.as_str()).unwrap_or("");
+    let head = args.get("head").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || base.is_empty() || head.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, base, and head are required"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/compare/{base}...{head}");
+    if let media @ ("diff" | "patch") = args.get("format").and_then(|v| v.as_str()).unwrap_or("json") {
+        let text = github_get_text(token, &path, media)?;
+        return Ok(Json(DataType::from_json(json!({"format": media, "content": text}))));
+    }
+    let compare = github_get(token, &path)?;
+    if compare.get("commits").is_none() {
+        return Ok(Json(DataType::from_json(compare)));
+    }
+    let commits: Vec<Value> = compare
+        .get("commits")
+        .and_then(|c| c.as_array())
+        .map(|commits| {
+            commits
+                .iter()
+                .map(|c| {
+                    json!({
+                        "sha": c.get("sha"),
+                        "message": c.pointer("/commit/message").and_then(|m| m.as_str()).and_then(|m| m.lines().next()),
+                        "author": c.pointer("/author/login").or_else(|| c.pointer("/commit/author/name")),
+                        "date": c.pointer("/commit/author/date")
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut files = compare
+        .get("files")
+        .and_then(|f| f.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for file in files.iter_mut() {
+        annotate_patch(file);
+    }
+    let total = compare
+        .get("total_commits")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(commits.len() as u64);
+
+    Ok(Json(DataType::from_json(json!({
+        "base": base,
+        "head": head,
+        "status": compare.get("status"),
+        "ahead_by": compare.get("ahead_by"),
+        "behind_by": compare.get("behind_by"),
+        "merge_base_sha": compare.pointer("/merge_base_commit/sha"),
+        "total_commits": total,
+        // Compare returns at most 250 commits and 300 files.
+        "commits_complete": total as usize == commits.len(),
+        "commits": commits,
+        "files_changed": files.len(),
+        "files": files,
+        "html_url": compare.get("html_url")
+    }))))
+}
+
+/// Signed-commit audit of `base...head`: every commit the range introduces
+/// with its verification, plus the unverified ones called out.
+pub(crate) fn check_signed_commits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let base = args.get("base").and_then(|v| v.as_str()).unwrap_or("");
+    let head = args.get("head").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || base.is_empty() || head.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, base, and head are required"}),
+        )));
+    }
+    let compare = github_get(token, &format!("/repos/{owner}/{repo}/compare/{base}...{head}"))?;
+    let Some(commits) = compare.get("commits").and_then(|c| c.as_array()) else {
+        return Ok(Json(DataType::from_json(compare)));
+    };
+
+    let results: Vec<Value> = commits
+        .iter()
+        .map(|c| {
+            json!({
+                "sha": c.get("sha"),
+                "message": c.pointer("/commit/message").and_then(|m| m.as_str()).and_then(|m| m.lines().next()),
+                "author": c.pointer("/author/login").or_else(|| c.pointer("/commit/author/name")),
+                "verification": verification(c)
+            })
+        })
+        .collect();
+    let unverified: Vec<Value> = results
+        .iter()
+        .filter(|r| r["verification"]["verified"] != true)
+        .cloned()
+        .collect();
+    let total = compare
+        .get("total_commits")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(results.len() as u64);
+
+    Ok(Json(DataType::from_json(json!({
+        "base": base,
+        "head": head,
+        "total_commits": total,
+        // Compare returns at most 250 commits.
+        "complete": total as usize == results.len(),
+        "verified": results.len() - unverified.len(),
+        "unverified": unverified.len(),
+        "all_signed": unverified.is_empty() && total as usize == results.len(),
+        "unverified_commits": unverified,
+        "commits": results
+    }))))
+}
+
+/// Commits that touched `path`, newest first. With `follow_renames` (the
+/// default), when the oldest commit under a name is the one that created it
+/// by a rename, the walk continues under the previous name from its parent.
+pub(crate) fn get_file_history(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || path.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and path are required"}),
+        )));
+    }
+    let limit = u64_arg(args, "limit").unwrap_or(30).clamp(1, MAX_HISTORY);
+    let follow = args.get("follow_renames").is_none() || bool_arg(args, "follow_renames");
+    let mut from = args.get("ref").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    let mut current = path.to_string();
+    let mut history: Vec<Value> = Vec::new();
+    let mut renames: Vec<Value> = Vec::new();
+    let mut truncated = false;
+    loop {
+        let wanted = limit - history.len() as u64;
+        let mut list = format!("/repos/{owner}/{repo}/commits?path={}", url_encode(&current));
+        if !from.is_empty() {
+            list.push_str(&format!("&sha={}", url_encode(&from)));
+        }
+        let paging = Paging {
+            page: 1,
+            per_page: wanted.min(100),
+            max_pages: wanted.div_ceil(100),
+        };
+        let data = github_list(token, &list, &paging, &Fields::default())?;
+        let Some(items) = data.get("items").and_then(|i| i.as_array()) else {
+            if history.is_empty() {
+                return Ok(Json(DataType::from_json(data)));
+            }
+            break;
+        };
+        for c in items.iter().take(wanted as usize) {
+            history.push(json!({
+                "sha": c.get("sha"),
+                "path": current,
+                "date": c.pointer("/commit/author/date"),
+                "author": c.pointer("/author/login").or_else(|| c.pointer("/commit/author/name")),
+                "subject": c.pointer("/commit/message").and_then(|m| m.as_str()).and_then(|m| m.lines().next()),
+                "message": c.pointer("/commit/message"),
+                "html_url": c.get("html_url")
+            }));
+        }
+        if history.len() as u64 >= limit {
+            truncated =
+                data.pointer("/page_info/next_page").and_then(Value::as_u64).is_some() || items.len() as u64 > wanted;
+            break;
+        }
+        let oldest = items.last();
+        let (Some(sha), Some(parent)) = (
+            oldest.and_then(|c| c.get("sha")).and_then(|s| s.as_str()),
+            oldest
+                .and_then(|c| c.pointer("/parents/0/sha"))
+                .and_then(|s| s.as_str()),
+        ) else {
+            break;
+        };
+        if !follow {
+            break;
+        }
+        let Some(previous) = renamed_from(token, owner, repo, sha, &current)? else {
+            break;
+        };
+        // A rename cycle (a -> b -> a) would otherwise walk forever.
+        if renames.iter().any(|r| r["from"] == previous.as_str()) {
+            break;
+        }
+        renames.push(json!({"sha": sha, "from": previous, "to": current}));
+        current = previous;
+        from = parent.to_string();
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "path": path,
+        "count": history.len(),
+        "truncated": truncated,
+        "last_changed": history.first(),
+        "renames": renames,
+        "commits": history
+    }))))
+}
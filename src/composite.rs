@@ -0,0 +1,738 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::graphql::github_graphql;
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{errors, github_get, github_list, github_request, json_body, media_type, time, u64_arg};
+
+const DEFAULT_STALE_DAYS: u64 = 30;
+const MAX_GRAPH_DEPTH: u64 = 3;
+const MAX_GRAPH_NODES: u64 = 50;
+
+// Everything pr_overview reports in one round trip; over REST this is the
+// PR, its reviews, requested reviewers, review threads, the head commit's
+// statuses and check runs, and the timeline for linked issues.
+const PR_OVERVIEW_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      number title url state isDraft merged mergeable mergeStateStatus reviewDecision
+      createdAt updatedAt author { login }
+      baseRefName headRefName headRefOid additions deletions changedFiles
+      labels(first: 50) { nodes { name } }
+      assignees(first: 20) { nodes { login } }
+      reviewRequests(first: 20) { nodes { requestedReviewer { ... on User { login } ... on Team { slug } } } }
+      latestReviews(first: 50) { nodes { author { login } state submittedAt } }
+      reviewThreads(first: 100) { totalCount nodes { isResolved } }
+      closingIssuesReferences(first: 20) { nodes { number title state url } }
+      commits(last: 1) {
+        nodes { commit { statusCheckRollup { state contexts(first: 100) { nodes {
+          __typename
+          ... on CheckRun { name status conclusion detailsUrl }
+          ... on StatusContext { context state targetUrl }
+        } } } } }
+      }
+    }
+  }
+}";
+
+// =============================================================================
+// Shared helpers
+// =============================================================================
+
+// Composite tools degrade to partial results: a failed or 404 sub-request
+// yields None instead of aborting the whole bundle.
+pub(crate) fn get_opt(token: &str, path: &str) -> Option<Value> {
+    let value = github_get(token, path).ok()?;
+    let is_error = value.get("message").is_some() && value.get("documentation_url").is_some();
+    (!is_error).then_some(value)
+}
+
+fn pick(value: &Value, keys: &[&str]) -> Value {
+    let mut out = json!({});
+    for key in keys {
+        if let Some(v) = value.get(*key) {
+            out[*key] = v.clone();
+        }
+    }
+    out
+}
+
+/// Items of a list endpoint, walked with `github_list`. `next_page` is set
+/// when pages remain past the walk (or a later page failed), so callers can
+/// report the truncation or resume from it.
+pub(crate) struct Listing {
+    pub items: Vec<Value>,
+    pub total: Option<u64>,
+    pub next_page: Option<u64>,
+}
+
+impl Listing {
+    pub(crate) fn truncated(&self) -> bool {
+        self.next_page.is_some()
+    }
+}
+
+// Err is the error body of the first page (a GitHub error, or the uniform
+// envelope for transport failures), ready to return or report as is.
+pub(crate) fn get_pages(token: &str, path: &str, paging: &Paging) -> Result<Listing, Value> {
+    let mut data = match github_list(token, path, paging, &Fields::default()) {
+        Ok(data) => data,
+        Err(e) => return Err(errors::failure(&format!("{e:#}"))),
+    };
+    let Some(Value::Array(items)) = data.get_mut("items").map(Value::take) else {
+        return Err(data);
+    };
+    Ok(Listing {
+        items,
+        total: data.pointer("/page_info/total_hint").and_then(|v| v.as_u64()),
+        next_page: data.pointer("/page_info/next_page").and_then(|v| v.as_u64()),
+    })
+}
+
+/// Every page from the first, up to the `max_pages` config cap.
+pub(crate) fn get_all(token: &str, path: &str) -> Result<Listing, Value> {
+    get_pages(token, path, &Paging::all())
+}
+
+pub(crate) fn search_count(token: &str, query: &str) -> Option<u64> {
+    let data = get_opt(token, &format!("/search/issues?q={query}&per_page=1"))?;
+    data.get("total_count").and_then(|v| v.as_u64())
+}
+
+/// Like `get_opt`, but only a 404 is `Ok(None)`; any other failure
+/// (permissions, SSO, rate limit) is kept as `Err` so callers can tell "not
+/// there" from "couldn't look".
+pub(crate) fn get_found(token: &str, path: &str) -> Result<Option<Value>, Value> {
+    let value = github_get(token, path).map_err(|e| errors::failure(&format!("{e:#}")))?;
+    let is_error = value.get("message").is_some() && value.get("documentation_url").is_some();
+    match (is_error, errors::is_not_found(&value)) {
+        (false, _) => Ok(Some(value)),
+        (true, true) => Ok(None),
+        (true, false) => Err(value),
+    }
+}
+
+/// Folds legacy commit statuses and every check run for `sha` into one
+/// summary with an overall `state` of success, failure, pending, or none.
+/// When a lookup fails (anything but 404) or the check runs are cut off and
+/// nothing has failed, the state is `unknown`, with `errors` and `truncated`
+/// saying why.
+pub(crate) fn ci_status(token: &str, owner: &str, repo: &str, sha: &str) -> Value {
+    let mut lookup_errors = Vec::new();
+    let statuses: Vec<Value> = match get_found(token, &format!("/repos/{owner}/{repo}/commits/{sha}/status")) {
+        Ok(combined) => combined
+            .as_ref()
+            .and_then(|c| c.get("statuses"))
+            .and_then(|s| s.as_array())
+            .cloned()
+            .unwrap_or_default(),
+        Err(error) => {
+            lookup_errors.push(error);
+            Vec::new()
+        }
+    };
+    let (runs, truncated) = match get_all(token, &format!("/repos/{owner}/{repo}/commits/{sha}/check-runs")) {
+        Ok(listing) => {
+            let truncated = listing.truncated();
+            (listing.items, truncated)
+        }
+        Err(error) => {
+            if !errors::is_not_found(&error) {
+                lookup_errors.push(error);
+            }
+            (Vec::new(), false)
+        }
+    };
+
+    let (mut success, mut failure, mut pending) = (0u64, 0u64, 0u64);
+    for status in &statuses {
+        match status.get("state").and_then(|v| v.as_str()).unwrap_or("") {
+            "success" => success += 1,
+            "pending" => pending += 1,
+            _ => failure += 1,
+        }
+    }
+    for run in &runs {
+        if run.get("status").and_then(|v| v.as_str()) != Some("completed") {
+            pending += 1;
+            continue;
+        }
+        match run.get("conclusion").and_then(|v| v.as_str()).unwrap_or("") {
+            "success" | "neutral" | "skipped" => success += 1,
+            _ => failure += 1,
+        }
+    }
+
+    let state = if failure > 0 {
+        "failure"
+    } else if !lookup_errors.is_empty() || truncated {
+        "unknown"
+    } else if pending > 0 {
+        "pending"
+    } else if success > 0 {
+        "success"
+    } else {
+        "none"
+    };
+    json!({
+        "sha": sha,
+        "state": state,
+        "success": success,
+        "failure": failure,
+        "pending": pending,
+        "truncated": truncated,
+        "errors": lookup_errors,
+        "statuses": statuses.iter().map(|s| pick(s, &["context", "state", "description", "target_url"])).collect::<Vec<_>>(),
+        "check_runs": runs.iter().map(|r| pick(r, &["name", "status", "conclusion", "html_url"])).collect::<Vec<_>>()
+    })
+}
+
+// Rates a metric: `ok` at or under `good`, `warn` at or under `fair`, else `bad`.
+fn rate(value: Option<f64>, good: f64, fair: f64) -> Value {
+    match value {
+        None => Value::Null,
+        Some(v) if v <= good => json!("ok"),
+        Some(v) if v <= fair => json!("warn"),
+        Some(_) => json!("bad"),
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+fn age_days(ts: Option<&Value>, now: i64) -> Option<f64> {
+    let secs = time::parse(ts?.as_str()?)?;
+    Some(((now - secs) as f64 / time::DAY as f64 * 10.0).round() / 10.0)
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn repo_overview(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let meta = github_get(token, &format!("/repos/{owner}/{repo}"))?;
+    if meta.get("full_name").is_none() {
+        return Ok(Json(DataType::from_json(meta)));
+    }
+    let default_branch = meta.get("default_branch").and_then(|v| v.as_str()).unwrap_or("main");
+
+    let branch = get_opt(token, &format!("/repos/{owner}/{repo}/branches/{default_branch}"));
+    let head_sha = branch
+        .as_ref()
+        .and_then(|b| b.pointer("/commit/sha"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let head = branch.as_ref().map(|b| {
+        json!({
+            "name": default_branch,
+            "protected": b.get("protected"),
+            "sha": head_sha,
+            "last_commit_message": b.pointer("/commit/commit/message"),
+            "last_commit_date": b.pointer("/commit/commit/committer/date")
+        })
+    });
+
+    let tree = head_sha
+        .as_ref()
+        .and_then(|sha| get_opt(token, &format!("/repos/{owner}/{repo}/git/trees/{sha}")));
+    let top_level: Vec<Value> = tree
+        .as_ref()
+        .and_then(|t| t.get("tree"))
+        .and_then(|t| t.as_array())
+        .map(|entries| entries.iter().map(|e| pick(e, &["path", "type", "size"])).collect())
+        .unwrap_or_default();
+
+    let languages = get_opt(token, &format!("/repos/{owner}/{repo}/languages"));
+    let latest_release = get_opt(token, &format!("/repos/{owner}/{repo}/releases/latest"))
+        .map(|r| pick(&r, &["tag_name", "name", "published_at", "html_url", "prerelease"]));
+
+    let open_prs = search_count(token, &format!("repo:{owner}/{repo}+type:pr+state:open"));
+    // The repo's open_issues_count includes open PRs.
+    let open_issues = meta
+        .get("open_issues_count")
+        .and_then(|v| v.as_u64())
+        .map(|total| total.saturating_sub(open_prs.unwrap_or(0)));
+
+    let ci = head_sha.as_deref().map(|sha| ci_status(token, owner, repo, sha));
+
+    Ok(Json(DataType::from_json(json!({
+        "repository": pick(&meta, &[
+            "full_name", "description", "html_url", "homepage", "visibility", "private", "fork", "archived",
+            "default_branch", "language", "topics", "license", "stargazers_count", "forks_count",
+            "watchers_count", "created_at", "pushed_at", "updated_at"
+        ]),
+        "default_branch": head,
+        "top_level_tree": top_level,
+        "languages": languages,
+        "latest_release": latest_release,
+        "open_issues": open_issues,
+        "open_prs": open_prs,
+        "ci_status": ci
+    }))))
+}
+
+pub(crate) fn issue_context(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let issue = github_get(token, &format!("/repos/{owner}/{repo}/issues/{number}"))?;
+    if issue.get("number").is_none() {
+        return Ok(Json(DataType::from_json(issue)));
+    }
+
+    let comments = match get_all(token, &format!("/repos/{owner}/{repo}/issues/{number}/comments")) {
+        Ok(comments) => comments,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let timeline = match get_all(token, &format!("/repos/{owner}/{repo}/issues/{number}/timeline")) {
+        Ok(timeline) => timeline,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let truncated = comments.truncated() || timeline.truncated();
+    let comments: Vec<Value> = comments
+        .items
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.get("id"),
+                "user": c.pointer("/user/login"),
+                "author_association": c.get("author_association"),
+                "created_at": c.get("created_at"),
+                "updated_at": c.get("updated_at"),
+                "body": c.get("body")
+            })
+        })
+        .collect();
+
+    let mut cross_references = Vec::new();
+    let mut linked_prs = Vec::new();
+    for event in timeline.items {
+        if event.get("event").and_then(|v| v.as_str()) != Some("cross-referenced") {
+            continue;
+        }
+        let Some(source) = event.pointer("/source/issue") else {
+            continue;
+        };
+        let reference = json!({
+            "repository": source.pointer("/repository/full_name"),
+            "number": source.get("number"),
+            "title": source.get("title"),
+            "state": source.get("state"),
+            "html_url": source.get("html_url"),
+            "is_pr": source.get("pull_request").is_some(),
+            "referenced_at": event.get("created_at")
+        });
+        if source.get("pull_request").is_some() {
+            let mut pr = reference.clone();
+            pr["merged_at"] = source
+                .pointer("/pull_request/merged_at")
+                .cloned()
+                .unwrap_or(Value::Null);
+            linked_prs.push(pr);
+        }
+        cross_references.push(reference);
+    }
+
+    let labels: Vec<Value> = issue
+        .get("labels")
+        .and_then(|l| l.as_array())
+        .map(|l| l.iter().filter_map(|label| label.get("name").cloned()).collect())
+        .unwrap_or_default();
+    let assignees: Vec<Value> = issue
+        .get("assignees")
+        .and_then(|a| a.as_array())
+        .map(|a| a.iter().filter_map(|user| user.get("login").cloned()).collect())
+        .unwrap_or_default();
+
+    Ok(Json(DataType::from_json(json!({
+        "issue": pick(&issue, &[
+            "number", "title", "state", "state_reason", "html_url", "body", "created_at", "updated_at",
+            "closed_at", "comments", "author_association", "milestone", "locked"
+        ]),
+        "author": issue.pointer("/user/login"),
+        "is_pr": issue.get("pull_request").is_some(),
+        "labels": labels,
+        "assignees": assignees,
+        "comments": comments,
+        "cross_references": cross_references,
+        "linked_prs": linked_prs,
+        "truncated": truncated
+    }))))
+}
+
+// GraphQL connection `{nodes: [...]}` as a plain list.
+fn nodes(value: Option<&Value>) -> Vec<Value> {
+    value
+        .and_then(|v| v.get("nodes"))
+        .and_then(|n| n.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// A pull request with its review state, CI rollup, and linked issues from a
+/// single GraphQL query.
+pub(crate) fn pr_overview(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let resp = github_graphql(
+        token,
+        PR_OVERVIEW_QUERY,
+        &json!({"owner": owner, "repo": repo, "number": number}),
+    )?;
+    let Some(pr) = resp.pointer("/data/repository/pullRequest").filter(|p| !p.is_null()) else {
+        return Ok(Json(DataType::from_json(json!({
+            "error": format!("pull request {owner}/{repo}#{number} not found"),
+            "errors": resp.get("errors").or(resp.get("message"))
+        }))));
+    };
+
+    let reviews: Vec<Value> = nodes(pr.get("latestReviews"))
+        .iter()
+        .map(|r| json!({"user": r.pointer("/author/login"), "state": r.get("state"), "submitted_at": r.get("submittedAt")}))
+        .collect();
+    let requested: Vec<Value> = nodes(pr.get("reviewRequests"))
+        .iter()
+        .filter_map(|r| {
+            let reviewer = r.get("requestedReviewer")?;
+            reviewer.get("login").or(reviewer.get("slug")).cloned()
+        })
+        .collect();
+    let threads = nodes(pr.get("reviewThreads"));
+    let unresolved = threads.iter().filter(|t| t["isResolved"] == false).count();
+    let rollup = pr.pointer("/commits/nodes/0/commit/statusCheckRollup");
+    let checks: Vec<Value> = nodes(rollup.and_then(|r| r.get("contexts")))
+        .iter()
+        .map(|c| {
+            if c["__typename"] == "CheckRun" {
+                json!({"name": c.get("name"), "status": c.get("status"), "conclusion": c.get("conclusion"), "url": c.get("detailsUrl")})
+            } else {
+                json!({"name": c.get("context"), "state": c.get("state"), "url": c.get("targetUrl")})
+            }
+        })
+        .collect();
+    let names = |conn: Option<&Value>, key: &str| -> Vec<Value> {
+        nodes(conn).iter().filter_map(|n| n.get(key).cloned()).collect()
+    };
+
+    Ok(Json(DataType::from_json(json!({
+        "pull_request": {
+            "number": pr.get("number"),
+            "title": pr.get("title"),
+            "url": pr.get("url"),
+            "state": pr.get("state"),
+            "draft": pr.get("isDraft"),
+            "merged": pr.get("merged"),
+            "mergeable": pr.get("mergeable"),
+            "merge_state": pr.get("mergeStateStatus"),
+            "author": pr.pointer("/author/login"),
+            "base": pr.get("baseRefName"),
+            "head": pr.get("headRefName"),
+            "head_sha": pr.get("headRefOid"),
+            "additions": pr.get("additions"),
+            "deletions": pr.get("deletions"),
+            "changed_files": pr.get("changedFiles"),
+            "created_at": pr.get("createdAt"),
+            "updated_at": pr.get("updatedAt")
+        },
+        "labels": names(pr.get("labels"), "name"),
+        "assignees": names(pr.get("assignees"), "login"),
+        "review_decision": pr.get("reviewDecision"),
+        "reviews": reviews,
+        "requested_reviewers": requested,
+        "review_threads": {"total": pr.pointer("/reviewThreads/totalCount"), "unresolved": unresolved},
+        "ci": {"state": rollup.and_then(|r| r.get("state")), "checks": checks},
+        "linked_issues": nodes(pr.get("closingIssuesReferences")),
+        "errors": resp.get("errors")
+    }))))
+}
+
+// Graph node key and summary for an issue or PR payload (timeline sources
+// and the issues endpoint share the shape).
+fn graph_node(issue: &Value) -> Option<(String, Value)> {
+    let number = issue.get("number")?.as_u64()?;
+    let repo = issue
+        .pointer("/repository/full_name")
+        .and_then(|r| r.as_str())
+        .or_else(|| issue.get("repository_url")?.as_str()?.split("/repos/").nth(1))?;
+    let key = format!("{repo}#{number}");
+    let node = json!({
+        "id": key,
+        "repository": repo,
+        "number": number,
+        "title": issue.get("title"),
+        "state": issue.get("state"),
+        "is_pr": issue.get("pull_request").is_some(),
+        "merged_at": issue.pointer("/pull_request/merged_at"),
+        "html_url": issue.get("html_url")
+    });
+    Some((key, node))
+}
+
+/// Issues and PRs related to one issue or PR through timeline
+/// cross-references, walked breadth-first up to `depth` hops (default 2,
+/// max 3) and `max_nodes` (default 25, max 50). An edge `from -> to` means
+/// `from` mentioned `to`.
+pub(crate) fn reference_graph(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let depth = u64_arg(args, "depth").unwrap_or(2).clamp(1, MAX_GRAPH_DEPTH);
+    let max_nodes = u64_arg(args, "max_nodes").unwrap_or(25).clamp(1, MAX_GRAPH_NODES) as usize;
+
+    let root = github_get(token, &format!("/repos/{owner}/{repo}/issues/{number}"))?;
+    let Some((root_key, root_node)) = graph_node(&root) else {
+        return Ok(Json(DataType::from_json(root)));
+    };
+    let mut nodes: Vec<Value> = vec![root_node];
+    let mut seen: Vec<String> = vec![root_key.clone()];
+    let mut edges: Vec<Value> = Vec::new();
+    let mut frontier = vec![root_key.clone()];
+    let mut truncated = false;
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for key in &frontier {
+            let Some((repo_name, number)) = key.split_once('#') else {
+                continue;
+            };
+            // An unreadable or cut-off timeline leaves the graph incomplete.
+            let timeline = get_all(token, &format!("/repos/{repo_name}/issues/{number}/timeline"));
+            truncated |= timeline.as_ref().map(Listing::truncated).unwrap_or(true);
+            for event in timeline.map(|t| t.items).unwrap_or_default() {
+                if event.get("event").and_then(|v| v.as_str()) != Some("cross-referenced") {
+                    continue;
+                }
+                let Some((source_key, source_node)) = event.pointer("/source/issue").and_then(graph_node) else {
+                    continue;
+                };
+                if !seen.contains(&source_key) && nodes.len() >= max_nodes {
+                    truncated = true;
+                    continue;
+                }
+                // Repeated mentions collapse into the first edge.
+                if !edges
+                    .iter()
+                    .any(|e| e["from"] == source_key.as_str() && e["to"] == key.as_str())
+                {
+                    edges.push(json!({"from": source_key, "to": key, "at": event.get("created_at")}));
+                }
+                if seen.contains(&source_key) {
+                    continue;
+                }
+                seen.push(source_key.clone());
+                nodes.push(source_node);
+                next.push(source_key);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "root": root_key,
+        "depth": depth,
+        "truncated": truncated,
+        "nodes": nodes,
+        "edges": edges
+    }))))
+}
+
+/// CI summary for any branch, tag, or SHA: the ref is resolved to a commit
+/// first so callers see exactly which SHA the verdict applies to.
+pub(crate) fn get_combined_status(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let git_ref = args.get("ref").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || git_ref.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and ref are required"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/commits/{git_ref}");
+    let resp = github_request(token, "GET", &path, None, media_type("sha"))?;
+    if resp.status == 404 {
+        return Ok(Json(DataType::from_json(json!({
+            "error": format!("ref not found: {git_ref}"),
+            "status": 404
+        }))));
+    }
+    if resp.status >= 400 {
+        return Ok(Json(DataType::from_json(json_body(&resp, &Fields::default())?)));
+    }
+    let sha = String::from_utf8_lossy(&resp.body).trim().to_string();
+    let mut status = ci_status(token, owner, repo, &sha);
+    let verdict = match status["state"].as_str() {
+        Some("success") => "pass",
+        Some("failure") => "fail",
+        Some("pending") => "pending",
+        Some("unknown") => "unknown",
+        _ => "none",
+    };
+    status["ref"] = json!(git_ref);
+    status["verdict"] = json!(verdict);
+    Ok(Json(DataType::from_json(status)))
+}
+
+/// Weekly maintenance scorecard: default-branch CI, open PR count and median
+/// age, stale issues (no update in `stale_days`), open Dependabot alerts, and
+/// days since the last release. Each metric is rated ok/warn/bad; metrics
+/// the token cannot read are null and left out of the score.
+pub(crate) fn repo_health(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let stale_days = u64_arg(args, "stale_days").unwrap_or(DEFAULT_STALE_DAYS);
+    let meta = github_get(token, &format!("/repos/{owner}/{repo}"))?;
+    if meta.get("full_name").is_none() {
+        return Ok(Json(DataType::from_json(meta)));
+    }
+    let default_branch = meta.get("default_branch").and_then(|v| v.as_str()).unwrap_or("main");
+    let now = time::now();
+
+    let ci = ci_status(token, owner, repo, default_branch);
+    let ci_state = ci["state"].as_str().unwrap_or("none").to_string();
+    let ci_rating = match ci_state.as_str() {
+        "success" => json!("ok"),
+        "pending" => json!("warn"),
+        "failure" => json!("bad"),
+        _ => Value::Null,
+    };
+
+    let prs = get_all(token, &format!("/repos/{owner}/{repo}/pulls?state=open"));
+    let pr_median = prs.as_ref().ok().and_then(|prs| {
+        median(
+            prs.items
+                .iter()
+                .filter_map(|pr| age_days(pr.get("created_at"), now))
+                .collect(),
+        )
+    });
+
+    let cutoff = time::format_date(now - stale_days as i64 * time::DAY);
+    let stale = search_count(
+        token,
+        &format!("repo:{owner}/{repo}+is:issue+is:open+updated:<{cutoff}"),
+    );
+
+    let alerts = get_opt(
+        token,
+        &format!("/repos/{owner}/{repo}/dependabot/alerts?state=open&per_page=100"),
+    );
+    let alert_summary = alerts.as_ref().and_then(|a| a.as_array()).map(|alerts| {
+        let mut by_severity = serde_json::Map::new();
+        for alert in alerts {
+            let severity = alert
+                .pointer("/security_advisory/severity")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            let count = by_severity.entry(severity).or_insert(json!(0));
+            *count = json!(count.as_u64().unwrap_or(0) + 1);
+        }
+        let severe = ["critical", "high"].iter().any(|s| by_severity.contains_key(*s));
+        (alerts.len(), by_severity, severe)
+    });
+    let alert_rating = match &alert_summary {
+        None => Value::Null,
+        Some((0, _, _)) => json!("ok"),
+        Some((_, _, true)) => json!("bad"),
+        Some(_) => json!("warn"),
+    };
+
+    let release = get_opt(token, &format!("/repos/{owner}/{repo}/releases/latest"));
+    let release_age = release.as_ref().and_then(|r| age_days(r.get("published_at"), now));
+
+    let ratings = [
+        ci_rating.clone(),
+        rate(pr_median, 7.0, 30.0),
+        rate(stale.map(|n| n as f64), 0.0, 10.0),
+        alert_rating.clone(),
+        rate(release_age, 90.0, 365.0),
+    ];
+    let rated: Vec<&str> = ratings.iter().filter_map(|r| r.as_str()).collect();
+    let points: f64 = rated
+        .iter()
+        .map(|r| match *r {
+            "ok" => 1.0,
+            "warn" => 0.5,
+            _ => 0.0,
+        })
+        .sum();
+    let score = (!rated.is_empty()).then(|| (points / rated.len() as f64 * 100.0).round());
+
+    Ok(Json(DataType::from_json(json!({
+        "repository": format!("{owner}/{repo}"),
+        "generated_at": time::format(now),
+        "score": score,
+        "ci": {"branch": default_branch, "state": ci_state, "rating": ci_rating},
+        // A truncated listing holds the newest PRs only, so the count is a
+        // lower bound and the median understates the age.
+        "pull_requests": match &prs {
+            Ok(prs) => json!({
+                "open": prs.items.len(),
+                "open_lower_bound": prs.truncated(),
+                "median_age_days": pr_median,
+                "rating": ratings[1]
+            }),
+            Err(error) => json!({"open": null, "error": error, "rating": null}),
+        },
+        "stale_issues": {"days": stale_days, "count": stale, "rating": ratings[2]},
+        // Alerts page by cursor, so only the first 100 are read; a full page
+        // means the counts are lower bounds.
+        "dependabot_alerts": alert_summary.map(|(open, by_severity, _)| {
+            json!({
+                "open": open,
+                "open_lower_bound": open >= 100,
+                "by_severity": by_severity,
+                "rating": alert_rating
+            })
+        }),
+        "last_release": release.map(|r| {
+            json!({
+                "tag": r.get("tag_name"),
+                "published_at": r.get("published_at"),
+                "age_days": release_age,
+                "rating": ratings[4]
+            })
+        })
+    }))))
+}
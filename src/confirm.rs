@@ -0,0 +1,47 @@
+use magi_pdk::DataType;
+use serde_json::json;
+
+use crate::{state, time};
+
+const TOKEN_COUNTER: &str = "confirm/next";
+const TOKEN_TTL_SECS: i64 = 600;
+
+// =============================================================================
+// Confirmation handshake for destructive tools
+// =============================================================================
+
+pub(crate) enum Gate {
+    /// The caller presented a valid token for this exact action.
+    Proceed,
+    /// First call: show the preview and hand back this token.
+    Pending(String),
+    Invalid(String),
+}
+
+/// Two-step confirmation. A call without `confirm_token` issues a short-lived
+/// token bound to `action` (which should encode every argument that matters);
+/// repeating the call with that token lets it through once.
+pub(crate) fn gate(action: &str, args: &DataType) -> Gate {
+    let presented = args.get("confirm_token").and_then(|v| v.as_str()).unwrap_or("");
+    if presented.is_empty() {
+        let token = format!("cf-{}", state::next_id(TOKEN_COUNTER));
+        let record = json!({"action": action, "expires_at": time::now() + TOKEN_TTL_SECS});
+        if let Err(e) = state::save(&format!("confirm/{token}"), &record) {
+            return Gate::Invalid(format!("could not store confirmation token: {e}"));
+        }
+        return Gate::Pending(token);
+    }
+
+    let key = format!("confirm/{presented}");
+    let Some(record) = state::load(&key) else {
+        return Gate::Invalid("unknown or already used confirm_token".to_string());
+    };
+    let _ = state::remove(&key);
+    if record.get("action").and_then(|v| v.as_str()) != Some(action) {
+        return Gate::Invalid("confirm_token was issued for a different action or arguments".to_string());
+    }
+    if record.get("expires_at").and_then(|v| v.as_i64()).unwrap_or(0) < time::now() {
+        return Gate::Invalid("confirm_token expired; call again without it for a new one".to_string());
+    }
+    Gate::Proceed
+}
@@ -0,0 +1,302 @@
+use base64::Engine;
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::stream::Fields;
+use crate::trailers;
+use crate::{github_get, github_put, github_request, json_body, media_type, url_encode, JSON_MEDIA_TYPE};
+
+// =============================================================================
+// File content decoding
+// =============================================================================
+
+/// Decodes the base64 `content` of a contents API file payload (GitHub wraps
+/// it at 60 columns).
+pub(crate) fn decode(file: &Value) -> Option<Vec<u8>> {
+    let encoded: String = file
+        .get("content")?
+        .as_str()?
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD.decode(encoded).ok()
+}
+
+/// NUL bytes or invalid UTF-8 mark content as binary, as git does.
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+pub(crate) fn guess_mime(path: &str, bytes: &[u8]) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\0asm", "application/wasm"),
+        (b"\x7fELF", "application/x-elf"),
+    ];
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return mime;
+    }
+    let ext = path
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" | "jar" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+// The contents API only inlines files up to 1 MB; between 1 and 100 MB it
+// answers with `encoding: "none"` and an empty `content`.
+fn is_inlined(file: &Value) -> bool {
+    let size = file.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+    let content = file.get("content").and_then(|v| v.as_str()).unwrap_or("");
+    file.get("encoding").and_then(|v| v.as_str()) != Some("none") && (size == 0 || !content.is_empty())
+}
+
+/// The bytes of a contents API file payload and where they came from:
+/// inline base64, the Git Blobs API for large files, or the `raw` media type
+/// of `contents_path` as a last resort.
+pub(crate) fn file_bytes(
+    token: &str,
+    owner: &str,
+    repo: &str,
+    contents_path: &str,
+    file: &Value,
+) -> Result<Option<(Vec<u8>, &'static str)>, Error> {
+    if is_inlined(file) {
+        return Ok(decode(file).map(|bytes| (bytes, "contents")));
+    }
+    if let Some(sha) = file.get("sha").and_then(|v| v.as_str()) {
+        let blob = github_get(token, &format!("/repos/{owner}/{repo}/git/blobs/{sha}"))?;
+        if let Some(bytes) = decode(&blob) {
+            return Ok(Some((bytes, "blob")));
+        }
+    }
+    let resp = github_request(token, "GET", contents_path, None, media_type("raw"))?;
+    Ok((resp.status < 400).then_some((resp.body, "raw")))
+}
+
+/// Clean envelope for a file: decoded text, or for binary files a
+/// descriptor (plus base64 `content` when `as_base64`).
+pub(crate) fn envelope(file: &Value, bytes: &[u8], source: &str, as_base64: bool) -> Value {
+    let path = file.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let mut out = json!({
+        "name": file.get("name"),
+        "path": path,
+        "sha": file.get("sha"),
+        "size": file.get("size").and_then(|v| v.as_u64()).unwrap_or(bytes.len() as u64),
+        "html_url": file.get("html_url"),
+        "source": source
+    });
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !is_binary(bytes) => {
+            out["binary"] = json!(false);
+            out["encoding"] = json!("utf-8");
+            out["content"] = json!(text);
+        }
+        _ => {
+            out["binary"] = json!(true);
+            out["mime_type"] = json!(guess_mime(path, bytes));
+            out["download_url"] = file.get("download_url").cloned().unwrap_or(Value::Null);
+            if as_base64 {
+                out["encoding"] = json!("base64");
+                out["content"] = json!(base64::engine::general_purpose::STANDARD.encode(bytes));
+            } else {
+                out["note"] = json!("binary content omitted; pass as_base64 to receive the encoded bytes");
+            }
+        }
+    }
+    out
+}
+
+// =============================================================================
+// File writes
+// =============================================================================
+
+struct FileTarget<'a> {
+    owner: &'a str,
+    repo: &'a str,
+    path: &'a str,
+    branch: &'a str,
+}
+
+impl FileTarget<'_> {
+    fn from_args(args: &DataType) -> Option<FileTarget<'_>> {
+        let target = FileTarget {
+            owner: args.get("owner").and_then(|v| v.as_str()).unwrap_or(""),
+            repo: args.get("repo").and_then(|v| v.as_str()).unwrap_or(""),
+            path: args
+                .get("path")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .trim_matches('/'),
+            branch: args.get("branch").and_then(|v| v.as_str()).unwrap_or(""),
+        };
+        (!target.owner.is_empty() && !target.repo.is_empty() && !target.path.is_empty()).then_some(target)
+    }
+
+    // Path segments are encoded individually so the slashes stay.
+    fn url(&self) -> String {
+        let segments: Vec<String> = self.path.split('/').map(url_encode).collect();
+        format!("/repos/{}/{}/contents/{}", self.owner, self.repo, segments.join("/"))
+    }
+
+    // Blob SHA of the file on the branch, or `None` when it doesn't exist yet.
+    fn current_sha(&self, token: &str) -> Result<Option<String>, Error> {
+        let mut url = self.url();
+        if !self.branch.is_empty() {
+            url.push_str(&format!("?ref={}", url_encode(self.branch)));
+        }
+        let file = github_get(token, &url)?;
+        if file.get("type").and_then(|t| t.as_str()) != Some("file") {
+            return Ok(None);
+        }
+        Ok(file.get("sha").and_then(|s| s.as_str()).map(str::to_string))
+    }
+}
+
+// The commit fields shared by writes and deletes: message (with trailers),
+// branch, and optional `committer` / `author` identities.
+fn commit_fields(target: &FileTarget, args: &DataType) -> Result<Value, String> {
+    let message = args.get("message").and_then(|v| v.as_str()).unwrap_or("");
+    if message.is_empty() {
+        return Err("message is required".to_string());
+    }
+    let mut body = json!({"message": trailers::apply(message, &trailers::from_args(args)?)});
+    if !target.branch.is_empty() {
+        body["branch"] = json!(target.branch);
+    }
+    for role in ["committer", "author"] {
+        let Some(identity) = args.get(role).map(|v| v.to_json()) else {
+            continue;
+        };
+        let name = identity.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let email = identity.get("email").and_then(|v| v.as_str()).unwrap_or("");
+        if name.is_empty() || email.is_empty() {
+            return Err(format!("{role} needs name and email"));
+        }
+        body[role] = json!({"name": name, "email": email});
+    }
+    Ok(body)
+}
+
+// `{commit, content}` responses reduced to what a caller acts on next.
+fn write_result(data: Value, created: Option<bool>) -> Value {
+    let Some(commit) = data.get("commit") else {
+        return data;
+    };
+    let mut out = json!({
+        "commit": {
+            "sha": commit.get("sha"),
+            "message": commit.get("message"),
+            "html_url": commit.get("html_url")
+        },
+        "content": data.get("content").filter(|c| !c.is_null()).map(|c| json!({
+            "path": c.get("path"),
+            "sha": c.get("sha"),
+            "size": c.get("size"),
+            "html_url": c.get("html_url")
+        }))
+    });
+    if let Some(created) = created {
+        out["created"] = json!(created);
+    }
+    out
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Commits `content` (text) or `content_base64` to `path`. The existing blob
+/// SHA is looked up when `sha` isn't given; passing it makes the write fail
+/// if the file changed since it was read.
+pub(crate) fn create_or_update_file(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(target) = FileTarget::from_args(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, path, message, and content are required"}),
+        )));
+    };
+    let encoded = match (
+        args.get("content").and_then(|v| v.as_str()),
+        args.get("content_base64").and_then(|v| v.as_str()),
+    ) {
+        (Some(text), None) => base64::engine::general_purpose::STANDARD.encode(text),
+        (None, Some(b64)) => {
+            let cleaned: String = b64.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+            if base64::engine::general_purpose::STANDARD.decode(&cleaned).is_err() {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": "content_base64 is not valid base64"}),
+                )));
+            }
+            cleaned
+        }
+        _ => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "exactly one of content or content_base64 is required"}),
+            )))
+        }
+    };
+    let mut body = match commit_fields(&target, args) {
+        Ok(body) => body,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    body["content"] = json!(encoded);
+    let sha = match args.get("sha").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        Some(sha) => Some(sha.to_string()),
+        None => target.current_sha(token)?,
+    };
+    if let Some(sha) = &sha {
+        body["sha"] = json!(sha);
+    }
+    let data = github_put(token, &target.url(), &body)?;
+    Ok(Json(DataType::from_json(write_result(data, Some(sha.is_none())))))
+}
+
+pub(crate) fn delete_file(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(target) = FileTarget::from_args(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, path, and message are required"}),
+        )));
+    };
+    let mut body = match commit_fields(&target, args) {
+        Ok(body) => body,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let sha = match args.get("sha").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        Some(sha) => sha.to_string(),
+        None => match target.current_sha(token)? {
+            Some(sha) => sha,
+            None => {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": format!("{} does not exist or is not a file", target.path)}),
+                )))
+            }
+        },
+    };
+    body["sha"] = json!(sha);
+    let resp = github_request(token, "DELETE", &target.url(), Some(&body), JSON_MEDIA_TYPE)?;
+    let data = json_body(&resp, &Fields::default())?;
+    Ok(Json(DataType::from_json(write_result(data, None))))
+}
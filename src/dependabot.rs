@@ -0,0 +1,176 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::bool_arg;
+use crate::composite::{ci_status, get_all, get_opt};
+
+const DEPENDABOT_LOGIN: &str = "dependabot[bot]";
+const SEVERITIES: &[&str] = &["low", "moderate", "high", "critical"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// "Bump serde from 1.0.188 to 1.0.190 in /api" -> ("1.0.188", "1.0.190").
+fn versions(title: &str) -> Option<(&str, &str)> {
+    let (_, rest) = title.split_once(" from ")?;
+    let (from, rest) = rest.split_once(" to ")?;
+    let to = rest.split_whitespace().next()?;
+    Some((from.trim(), to))
+}
+
+// Which semver component changed; grouped updates and non-semver versions
+// are "unknown".
+fn bump_kind(from: &str, to: &str) -> &'static str {
+    let parts = |v: &str| -> Vec<String> {
+        v.trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (parts(from), parts(to));
+    for (i, kind) in ["major", "minor", "patch"].iter().enumerate() {
+        match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) if x == y => continue,
+            (Some(_), Some(_)) => return *kind,
+            _ => break,
+        }
+    }
+    "unknown"
+}
+
+fn severity_rank(severity: &str) -> usize {
+    SEVERITIES.iter().position(|s| *s == severity).map_or(0, |i| i + 1)
+}
+
+fn highest(severities: &[String]) -> Option<&String> {
+    severities.iter().max_by_key(|s| severity_rank(s))
+}
+
+// Vulnerabilities on the versions a PR adds and on those it removes, from
+// the dependency review comparison of base...head.
+fn vulnerability_delta(
+    token: &str,
+    owner: &str,
+    repo: &str,
+    base: &str,
+    head: &str,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let changes = get_opt(
+        token,
+        &format!("/repos/{owner}/{repo}/dependency-graph/compare/{base}...{head}"),
+    )?;
+    let (mut introduced, mut fixed) = (Vec::new(), Vec::new());
+    for change in changes.as_array()? {
+        let severities = change
+            .get("vulnerabilities")
+            .and_then(|v| v.as_array())
+            .map(|v| {
+                v.iter()
+                    .filter_map(|x| x.get("severity").and_then(|s| s.as_str()).map(str::to_string))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        match change.get("change_type").and_then(|c| c.as_str()) {
+            Some("added") => introduced.extend(severities),
+            Some("removed") => fixed.extend(severities),
+            _ => {}
+        }
+    }
+    Some((introduced, fixed))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Open Dependabot PRs ranked for batch merging: security fixes first, then
+/// by bump size. A PR is `merge_eligible` when CI passed, it introduces no
+/// known vulnerability, and it isn't a major bump (unless `allow_major`).
+pub(crate) fn triage_dependabot_prs(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let allow_major = bool_arg(args, "allow_major");
+
+    let pulls = match get_all(token, &format!("/repos/{owner}/{repo}/pulls?state=open")) {
+        Ok(listing) => listing.items,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let mut ranked: Vec<(usize, Value)> = Vec::new();
+    for pr in pulls
+        .iter()
+        .filter(|p| p.pointer("/user/login").and_then(|l| l.as_str()) == Some(DEPENDABOT_LOGIN))
+    {
+        let title = pr.get("title").and_then(|t| t.as_str()).unwrap_or("");
+        let head = pr.pointer("/head/sha").and_then(|s| s.as_str()).unwrap_or("");
+        let base = pr.pointer("/base/sha").and_then(|s| s.as_str()).unwrap_or("");
+        let (from, to) = versions(title).unzip();
+        let bump = match (from, to) {
+            (Some(from), Some(to)) => bump_kind(from, to),
+            _ => "unknown",
+        };
+        let ci = ci_status(token, owner, repo, head);
+        let ci_state = ci.get("state").and_then(|s| s.as_str()).unwrap_or("none").to_string();
+        let delta = vulnerability_delta(token, owner, repo, base, head);
+        let (introduced, fixed) = delta.clone().unwrap_or_default();
+
+        let mut blockers = Vec::new();
+        if ci_state != "success" {
+            blockers.push(format!("CI is {ci_state}"));
+        }
+        if let Some(severity) = highest(&introduced) {
+            blockers.push(format!("introduces a {severity} vulnerability"));
+        }
+        if delta.is_none() {
+            blockers.push("dependency review unavailable".to_string());
+        }
+        if bump == "major" && !allow_major {
+            blockers.push("major version bump".to_string());
+        }
+        if pr.get("draft").and_then(|d| d.as_bool()) == Some(true) {
+            blockers.push("draft".to_string());
+        }
+
+        // Lower sorts first: security fixes, then patch < minor < unknown < major.
+        let bump_rank = ["patch", "minor", "unknown", "major"]
+            .iter()
+            .position(|b| *b == bump)
+            .unwrap_or(2);
+        let mut rank = bump_rank;
+        if fixed.is_empty() {
+            rank += 10;
+        }
+        if !blockers.is_empty() {
+            rank += 100;
+        }
+        ranked.push((
+            rank,
+            json!({
+                "number": pr.get("number"),
+                "title": title,
+                "from": from,
+                "to": to,
+                "bump": bump,
+                "ci": ci_state,
+                "fixes_severity": highest(&fixed),
+                "introduces_severity": highest(&introduced),
+                "merge_eligible": blockers.is_empty(),
+                "blockers": blockers,
+                "html_url": pr.get("html_url")
+            }),
+        ));
+    }
+    ranked.sort_by_key(|(rank, _)| *rank);
+    let eligible = ranked.iter().filter(|(_, pr)| pr["merge_eligible"] == true).count();
+    Ok(Json(DataType::from_json(json!({
+        "open_dependabot_prs": ranked.len(),
+        "merge_eligible": eligible,
+        "pull_requests": ranked.into_iter().map(|(_, pr)| pr).collect::<Vec<_>>()
+    }))))
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_opt;
+use crate::stream::Fields;
+use crate::{github_request, json_body, u64_arg, url_encode};
+
+// The dependency graph GraphQL fields still sit behind this preview.
+const DEPENDENCY_GRAPH_MEDIA_TYPE: &str = "application/vnd.github.hawkgirl-preview+json";
+const MAX_MANIFEST_PAGES: u64 = 5;
+const DEFAULT_DEPENDENT_LIMIT: u64 = 100;
+
+const MANIFESTS_QUERY: &str = "query($owner: String!, $repo: String!, $after: String) {
+  repository(owner: $owner, name: $repo) {
+    dependencyGraphManifests(first: 20, after: $after) {
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        filename parseable dependenciesCount
+        dependencies(first: 100) {
+          nodes { packageName packageManager requirements hasDependencies repository { nameWithOwner } }
+        }
+      }
+    }
+  }
+}";
+
+// Manifest files to search per ecosystem when looking for dependents.
+const ECOSYSTEM_MANIFESTS: &[(&str, &[&str])] = &[
+    ("npm", &["package.json"]),
+    ("pip", &["requirements.txt", "pyproject.toml", "setup.py"]),
+    ("cargo", &["Cargo.toml"]),
+    ("go", &["go.mod"]),
+    ("maven", &["pom.xml"]),
+    ("gradle", &["build.gradle", "build.gradle.kts"]),
+    ("rubygems", &["Gemfile"]),
+    ("composer", &["composer.json"]),
+    ("nuget", &["packages.config"]),
+];
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Declared dependencies per manifest from the dependency graph. Manifests
+/// with more than 100 dependencies report `truncated`.
+pub(crate) fn list_dependencies(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let ecosystem = args
+        .get("ecosystem")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_ascii_uppercase();
+
+    let mut manifests = Vec::new();
+    let mut after = Value::Null;
+    for _ in 0..MAX_MANIFEST_PAGES {
+        let body = json!({"query": MANIFESTS_QUERY, "variables": {"owner": owner, "repo": repo, "after": after}});
+        let resp = github_request(token, "POST", "/graphql", Some(&body), DEPENDENCY_GRAPH_MEDIA_TYPE)?;
+        let data = json_body(&resp, &Fields::default())?;
+        let Some(connection) = data.pointer("/data/repository/dependencyGraphManifests") else {
+            return Ok(Json(DataType::from_json(json!({
+                "error": "dependency graph unavailable for this repository",
+                "details": data.get("errors").or(data.get("message"))
+            }))));
+        };
+        for manifest in connection.get("nodes").and_then(|n| n.as_array()).into_iter().flatten() {
+            let deps: Vec<Value> = manifest
+                .pointer("/dependencies/nodes")
+                .and_then(|n| n.as_array())
+                .into_iter()
+                .flatten()
+                .filter(|d| {
+                    let manager = d.get("packageManager").and_then(|v| v.as_str());
+                    ecosystem.is_empty() || manager == Some(ecosystem.as_str())
+                })
+                .map(|d| {
+                    json!({
+                        "package": d.get("packageName"),
+                        "ecosystem": d.get("packageManager"),
+                        "requirements": d.get("requirements"),
+                        "repository": d.pointer("/repository/nameWithOwner")
+                    })
+                })
+                .collect();
+            let declared = manifest.get("dependenciesCount").and_then(|v| v.as_u64()).unwrap_or(0);
+            let listed = manifest
+                .pointer("/dependencies/nodes")
+                .and_then(|n| n.as_array())
+                .map_or(0, Vec::len);
+            if deps.is_empty() && !ecosystem.is_empty() {
+                continue;
+            }
+            manifests.push(json!({
+                "filename": manifest.get("filename"),
+                "parseable": manifest.get("parseable"),
+                "dependency_count": declared,
+                "truncated": declared as usize > listed,
+                "dependencies": deps
+            }));
+        }
+        match connection.pointer("/pageInfo/endCursor") {
+            Some(cursor) if connection.pointer("/pageInfo/hasNextPage") == Some(&json!(true)) => after = cursor.clone(),
+            _ => break,
+        }
+    }
+
+    let total: usize = manifests
+        .iter()
+        .filter_map(|m| m["dependencies"].as_array())
+        .map(Vec::len)
+        .sum();
+    Ok(Json(DataType::from_json(
+        json!({"dependency_count": total, "manifests": manifests}),
+    )))
+}
+
+/// Repositories whose manifests mention `package`. GitHub's "Used by" graph
+/// has no API, so this searches code in the ecosystem's manifest files
+/// (optionally within `org`); matches are candidates to confirm, not proof.
+pub(crate) fn find_dependents(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let package = args.get("package").and_then(|v| v.as_str()).unwrap_or("");
+    let ecosystem = args
+        .get("ecosystem")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if package.is_empty() || ecosystem.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "package and ecosystem are required"}),
+        )));
+    }
+    let Some((_, files)) = ECOSYSTEM_MANIFESTS.iter().find(|(name, _)| *name == ecosystem) else {
+        let known: Vec<&str> = ECOSYSTEM_MANIFESTS.iter().map(|(name, _)| *name).collect();
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("ecosystem must be one of: {}", known.join(", "))}),
+        )));
+    };
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_DEPENDENT_LIMIT) as usize;
+
+    let mut dependents: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in *files {
+        let mut query = format!("\"{package}\" filename:{file}");
+        if !org.is_empty() {
+            query.push_str(&format!(" org:{org}"));
+        }
+        let path = format!("/search/code?q={}&per_page=100", url_encode(&query));
+        let Some(results) = get_opt(token, &path) else {
+            continue;
+        };
+        for item in results.get("items").and_then(|i| i.as_array()).into_iter().flatten() {
+            let (Some(repo), Some(manifest)) = (
+                item.pointer("/repository/full_name").and_then(|v| v.as_str()),
+                item.get("path").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+            dependents
+                .entry(repo.to_string())
+                .or_default()
+                .push(manifest.to_string());
+        }
+    }
+    let total = dependents.len();
+    let repos: Vec<Value> = dependents
+        .into_iter()
+        .take(limit)
+        .map(|(repo, manifests)| json!({"repository": repo, "manifests": manifests}))
+        .collect();
+
+    Ok(Json(DataType::from_json(json!({
+        "package": package,
+        "ecosystem": ecosystem,
+        "count": total,
+        "dependents": repos,
+        "method": "code_search"
+    }))))
+}
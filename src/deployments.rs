@@ -0,0 +1,73 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::{github_get, u64_arg, url_encode};
+
+const DEFAULT_LIMIT: u64 = 20;
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+/// Deployment history for one environment, newest first, each with its
+/// latest status. `current` is the newest deployment whose latest status is
+/// `success`, i.e. what the environment is running now.
+pub(crate) fn deployment_history(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let environment = args.get("environment").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || environment.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and environment are required"}),
+        )));
+    }
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_LIMIT).clamp(1, 100);
+    let path = format!(
+        "/repos/{owner}/{repo}/deployments?environment={}&per_page={limit}",
+        url_encode(environment)
+    );
+    let data = github_get(token, &path)?;
+    let Value::Array(deployments) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+
+    let mut history = Vec::new();
+    let mut current = Value::Null;
+    for deployment in &deployments {
+        let id = deployment.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        // Statuses come newest first.
+        let latest = github_get(
+            token,
+            &format!("/repos/{owner}/{repo}/deployments/{id}/statuses?per_page=1"),
+        )?
+        .as_array()
+        .and_then(|s| s.first().cloned())
+        .unwrap_or(Value::Null);
+        let entry = json!({
+            "id": id,
+            "sha": deployment.get("sha"),
+            "ref": deployment.get("ref"),
+            "task": deployment.get("task"),
+            "description": deployment.get("description"),
+            "creator": deployment.pointer("/creator/login"),
+            "created_at": deployment.get("created_at"),
+            "state": latest.get("state").cloned().unwrap_or(json!("unknown")),
+            "status_at": latest.get("created_at"),
+            "status_description": latest.get("description"),
+            "status_creator": latest.pointer("/creator/login"),
+            "environment_url": latest.get("environment_url"),
+            "log_url": latest.get("log_url")
+        });
+        if current.is_null() && entry["state"] == "success" {
+            current = entry.clone();
+        }
+        history.push(entry);
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "environment": environment,
+        "current": current,
+        "deployments": history
+    }))))
+}
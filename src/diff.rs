@@ -0,0 +1,552 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{bool_arg, contents, github_get, url_encode};
+
+// =============================================================================
+// Unified diff model
+// =============================================================================
+
+#[derive(Serialize, Default)]
+pub(crate) struct FileDiff {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub status: &'static str,
+    pub binary: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Hunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub section: String,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Line {
+    pub kind: &'static str,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub content: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_newline: bool,
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+/// Parses `git diff`/unified diff text into files, hunks, and lines. Also
+/// accepts bare hunks such as the `patch` field of the PR files API.
+pub(crate) fn parse(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // Lines still expected in the current hunk, old side and new side.
+    let (mut old_left, mut new_left) = (0u32, 0u32);
+    let (mut old_no, mut new_no) = (0u32, 0u32);
+
+    for raw in text.lines() {
+        if old_left > 0 || new_left > 0 {
+            let Some(hunk) = files.last_mut().and_then(|f| f.hunks.last_mut()) else {
+                break;
+            };
+            let line = match raw.chars().next() {
+                Some('+') => Some(("add", &raw[1..])),
+                Some('-') => Some(("del", &raw[1..])),
+                Some(' ') => Some(("context", &raw[1..])),
+                Some('\\') => {
+                    if let Some(last) = hunk.lines.last_mut() {
+                        last.no_newline = true;
+                    }
+                    continue;
+                }
+                // Some tools strip the leading space from empty context lines.
+                None => Some(("context", "")),
+                // The hunk's counts were off and it ended early; the line is
+                // read again below as the header it most likely is.
+                _ => None,
+            };
+            if let Some((kind, content)) = line {
+                let (old_line, new_line) = match kind {
+                    "add" => {
+                        new_left = new_left.saturating_sub(1);
+                        new_no += 1;
+                        (None, Some(new_no - 1))
+                    }
+                    "del" => {
+                        old_left = old_left.saturating_sub(1);
+                        old_no += 1;
+                        (Some(old_no - 1), None)
+                    }
+                    _ => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                        old_no += 1;
+                        new_no += 1;
+                        (Some(old_no - 1), Some(new_no - 1))
+                    }
+                };
+                hunk.lines.push(Line {
+                    kind,
+                    old_line,
+                    new_line,
+                    content: content.to_string(),
+                    no_newline: false,
+                });
+                continue;
+            }
+            (old_left, new_left) = (0, 0);
+        }
+
+        if let Some(rest) = raw.strip_prefix("diff --git ") {
+            let (old, new) = match rest.rfind(" b/") {
+                Some(i) => (&rest[..i], &rest[i + 1..]),
+                None => (rest, rest),
+            };
+            files.push(FileDiff {
+                old_path: Some(strip_prefix(old).to_string()),
+                new_path: Some(strip_prefix(new).to_string()),
+                status: "modified",
+                ..FileDiff::default()
+            });
+        } else if let Some(path) = raw.strip_prefix("--- ") {
+            // Plain unified diffs have no `diff --git` line to start a file.
+            if !matches!(files.last(), Some(f) if f.hunks.is_empty()) {
+                files.push(FileDiff {
+                    status: "modified",
+                    ..FileDiff::default()
+                });
+            }
+            let file = files.last_mut().unwrap();
+            match diff_path(path) {
+                Some(p) => file.old_path = Some(p),
+                None => file.status = "added",
+            }
+        } else if let Some(path) = raw.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                match diff_path(path) {
+                    Some(p) => file.new_path = Some(p),
+                    None => file.status = "deleted",
+                }
+            }
+        } else if let Some(header) = raw.strip_prefix("@@ ") {
+            let Some((ranges, section)) = header.split_once(" @@") else {
+                continue;
+            };
+            let mut parts = ranges.split_whitespace();
+            let (old_start, old_lines) = parse_range(parts.next().unwrap_or("").trim_start_matches('-'));
+            let (new_start, new_lines) = parse_range(parts.next().unwrap_or("").trim_start_matches('+'));
+            if files.is_empty() {
+                files.push(FileDiff {
+                    status: "modified",
+                    ..FileDiff::default()
+                });
+            }
+            let file = files.last_mut().unwrap();
+            file.hunks.push(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section: section.trim().to_string(),
+                lines: Vec::new(),
+            });
+            (old_left, new_left) = (old_lines, new_lines);
+            (old_no, new_no) = (old_start, new_start);
+        } else if raw.starts_with('\\') {
+            // A marker after the hunk's last line, once its counts ran out.
+            let last = files
+                .last_mut()
+                .and_then(|f| f.hunks.last_mut())
+                .and_then(|h| h.lines.last_mut());
+            if let Some(last) = last {
+                last.no_newline = true;
+            }
+        } else if let Some(file) = files.last_mut() {
+            if raw.starts_with("new file mode") {
+                file.status = "added";
+            } else if raw.starts_with("deleted file mode") {
+                file.status = "deleted";
+            } else if let Some(p) = raw.strip_prefix("rename from ") {
+                file.status = "renamed";
+                file.old_path = Some(p.to_string());
+            } else if let Some(p) = raw.strip_prefix("rename to ") {
+                file.status = "renamed";
+                file.new_path = Some(p.to_string());
+            } else if raw.starts_with("Binary files ") || raw == "GIT binary patch" {
+                file.binary = true;
+            }
+        }
+    }
+
+    for file in &mut files {
+        for line in file.hunks.iter().flat_map(|h| &h.lines) {
+            match line.kind {
+                "add" => file.additions += 1,
+                "del" => file.deletions += 1,
+                _ => {}
+            }
+        }
+    }
+    files
+}
+
+fn strip_prefix(path: &str) -> &str {
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+}
+
+// None for /dev/null (file added or deleted).
+fn diff_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+    (path != "/dev/null").then(|| strip_prefix(path).to_string())
+}
+
+// "12,5" -> (12, 5); a missing count means 1 line.
+fn parse_range(range: &str) -> (u32, u32) {
+    match range.split_once(',') {
+        Some((start, len)) => (start.parse().unwrap_or(0), len.parse().unwrap_or(0)),
+        None => (range.parse().unwrap_or(0), 1),
+    }
+}
+
+// =============================================================================
+// Diff generation
+// =============================================================================
+
+const CONTEXT_LINES: usize = 3;
+// Past this many cells the LCS table is too big for the plugin's memory; the
+// changed middle is then reported as one replacement.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+// Line-level edit script: (' ' | '-' | '+', line).
+fn edit_script<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut ops: Vec<(char, &str)> = old[..prefix].iter().map(|l| (' ', *l)).collect();
+    if (a.len() + 1) * (b.len() + 1) > MAX_LCS_CELLS {
+        ops.extend(a.iter().map(|l| ('-', *l)));
+        ops.extend(b.iter().map(|l| ('+', *l)));
+    } else {
+        // lcs[i][j] = LCS length of a[i..] and b[j..].
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                ops.push((' ', a[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push(('-', a[i]));
+                i += 1;
+            } else {
+                ops.push(('+', b[j]));
+                j += 1;
+            }
+        }
+        ops.extend(a[i..].iter().map(|l| ('-', *l)));
+        ops.extend(b[j..].iter().map(|l| ('+', *l)));
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|l| (' ', *l)));
+    ops
+}
+
+/// Renders a `git diff`-style unified diff of two texts; empty when they are
+/// identical. `None` on either side stands for a missing file.
+pub(crate) fn unified(path: &str, old: Option<&str>, new: Option<&str>) -> String {
+    let old_lines: Vec<&str> = old.map(|t| t.lines().collect()).unwrap_or_default();
+    let new_lines: Vec<&str> = new.map(|t| t.lines().collect()).unwrap_or_default();
+    let ops = edit_script(&old_lines, &new_lines);
+    if ops.iter().all(|(tag, _)| *tag == ' ') {
+        return String::new();
+    }
+
+    let mut out = format!("diff --git a/{path} b/{path}\n");
+    match (old, new) {
+        (None, _) => out.push_str("new file mode 100644\n"),
+        (_, None) => out.push_str("deleted file mode 100644\n"),
+        _ => {}
+    }
+    let old_name = if old.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_name = if new.is_some() {
+        format!("b/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    out.push_str(&format!("--- {old_name}\n+++ {new_name}\n"));
+
+    // Line numbers reached before each op, old and new side.
+    let mut before = Vec::with_capacity(ops.len() + 1);
+    let (mut o, mut n) = (0usize, 0usize);
+    for (tag, _) in &ops {
+        before.push((o, n));
+        match tag {
+            '-' => o += 1,
+            '+' => n += 1,
+            _ => {
+                o += 1;
+                n += 1;
+            }
+        }
+    }
+    before.push((o, n));
+
+    let mut i = 0;
+    while i < ops.len() {
+        if ops[i].0 == ' ' {
+            i += 1;
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT_LINES);
+        // Extend through further changes separated by at most 2x context.
+        let mut end = i;
+        let mut j = i;
+        while j < ops.len() {
+            if ops[j].0 != ' ' {
+                j += 1;
+                end = j;
+                continue;
+            }
+            let run = j;
+            while j < ops.len() && ops[j].0 == ' ' {
+                j += 1;
+            }
+            if j == ops.len() || j - run > 2 * CONTEXT_LINES {
+                break;
+            }
+        }
+        let stop = (end + CONTEXT_LINES).min(ops.len());
+        let (old_from, new_from) = before[start];
+        let (old_to, new_to) = before[stop];
+        let range = |from: usize, to: usize| {
+            let count = to - from;
+            // An empty side is numbered by the line it follows.
+            let first = if count == 0 { from } else { from + 1 };
+            format!("{first},{count}")
+        };
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_from, old_to),
+            range(new_from, new_to)
+        ));
+        for (tag, line) in &ops[start..stop] {
+            out.push_str(&format!("{tag}{line}\n"));
+        }
+        i = stop;
+    }
+    out
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+pub(crate) fn parse_diff(args: &DataType) -> FnResult<Json<DataType>> {
+    let text = args.get("diff").and_then(|v| v.as_str()).unwrap_or("");
+    if text.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "diff is required"}))));
+    }
+    let files = parse(text);
+    let additions: u32 = files.iter().map(|f| f.additions).sum();
+    let deletions: u32 = files.iter().map(|f| f.deletions).sum();
+    Ok(Json(DataType::from_json(json!({
+        "files_changed": files.len(),
+        "additions": additions,
+        "deletions": deletions,
+        "files": files
+    }))))
+}
+
+/// Unified diff of `content` against `path` on `ref` (default branch unless
+/// given) without writing anything, to show what a file write would change.
+pub(crate) fn preview_change(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim_matches('/');
+    let content = args.get("content").and_then(|v| v.as_str());
+    let delete = bool_arg(args, "delete");
+    if owner.is_empty() || repo.is_empty() || path.is_empty() || (content.is_none() && !delete) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, path, and content (or delete) are required"}),
+        )));
+    }
+    let git_ref = args.get("ref").and_then(|v| v.as_str()).filter(|r| !r.is_empty());
+    let mut url = format!("/repos/{owner}/{repo}/contents/{path}");
+    if let Some(r) = git_ref {
+        url.push_str(&format!("?ref={}", url_encode(r)));
+    }
+    let file = github_get(token, &url)?;
+    let current = if file.get("message").and_then(|m| m.as_str()) == Some("Not Found") {
+        None
+    } else if file.get("type").and_then(|t| t.as_str()) != Some("file") {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("{path} is not a file"), "response": file}),
+        )));
+    } else {
+        match contents::decode(&file).map(String::from_utf8) {
+            Some(Ok(text)) => Some(text),
+            _ => {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": format!("{path} is binary or too large to diff")}),
+                )))
+            }
+        }
+    };
+    let proposed = if delete { None } else { content };
+
+    let diff = unified(path, current.as_deref(), proposed);
+    let (additions, deletions) = diff
+        .lines()
+        .filter(|l| !l.starts_with("+++") && !l.starts_with("---"))
+        .fold((0, 0), |(a, d), l| match l.as_bytes().first() {
+            Some(b'+') => (a + 1, d),
+            Some(b'-') => (a, d + 1),
+            _ => (a, d),
+        });
+    Ok(Json(DataType::from_json(json!({
+        "path": path,
+        "ref": git_ref,
+        "new_file": current.is_none(),
+        "deleted": delete && current.is_some(),
+        "unchanged": diff.is_empty(),
+        "additions": additions,
+        "deletions": deletions,
+        "sha": file.get("sha"),
+        "diff": diff
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn miscounted_hunk_keeps_the_next_file() {
+        let text = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+-old
++new
+diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -1 +1 @@
+-x
++y
+";
+        let files = parse(text);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].hunks[0].lines.len(), 2);
+        assert_eq!(files[1].new_path.as_deref(), Some("b.txt"));
+        assert_eq!(files[1].hunks.len(), 1);
+        assert_eq!((files[1].additions, files[1].deletions), (1, 1));
+    }
+
+    #[test]
+    fn miscounted_hunk_keeps_the_next_hunk() {
+        let text = "\
+@@ -1,4 +1,4 @@
+-a
++b
+@@ -10 +10 @@
+-c
++d
+";
+        let files = parse(text);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[1].lines[0].old_line, Some(10));
+    }
+
+    #[test]
+    fn no_newline_marker_applies_to_the_line_before_it() {
+        let text = "\
+--- a/f
++++ b/f
+@@ -1 +1 @@
+-a
+\\ No newline at end of file
++b
+\\ No newline at end of file
+";
+        let files = parse(text);
+        let lines = &files[0].hunks[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].no_newline);
+        assert!(lines[1].no_newline);
+    }
+
+    #[test]
+    fn rename_without_changes() {
+        let text = "\
+diff --git a/old.txt b/new.txt
+similarity index 100%
+rename from old.txt
+rename to new.txt
+";
+        let files = parse(text);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, "renamed");
+        assert_eq!(files[0].old_path.as_deref(), Some("old.txt"));
+        assert_eq!(files[0].new_path.as_deref(), Some("new.txt"));
+        assert!(files[0].hunks.is_empty());
+    }
+
+    #[test]
+    fn binary_file() {
+        let text = "\
+diff --git a/img.png b/img.png
+new file mode 100644
+Binary files /dev/null and b/img.png differ
+";
+        let files = parse(text);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].binary);
+        assert_eq!(files[0].status, "added");
+    }
+
+    #[test]
+    fn unified_output_parses_back() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "one\n2\nthree\nfour\nfive\n";
+        let files = parse(&unified("f.txt", Some(old), Some(new)));
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+        assert!(unified("f.txt", Some(old), Some(old)).is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+
+use magi_pdk::DataType;
+
+pub(crate) const DEFAULT_API_BASE: &str = "https://api.github.com";
+
+thread_local! {
+    static API_BASE: RefCell<String> = RefCell::new(DEFAULT_API_BASE.to_string());
+}
+
+// =============================================================================
+// API base URL (github.com or GitHub Enterprise Server)
+// =============================================================================
+
+/// Normalizes `api_base_url`. GHES serves REST under `/api/v3`, so a bare
+/// host such as `https://ghe.example.com` gets that prefix added.
+pub(crate) fn normalize(raw: &str) -> Result<String, String> {
+    let base = raw.trim().trim_end_matches('/');
+    let Some(rest) = base.strip_prefix("https://").or_else(|| base.strip_prefix("http://")) else {
+        return Err(format!("api_base_url must start with https:// or http://: {raw}"));
+    };
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if host.is_empty() {
+        return Err(format!("api_base_url has no host: {raw}"));
+    }
+    if host == "api.github.com" || !path.is_empty() {
+        return Ok(base.to_string());
+    }
+    Ok(format!("{base}/api/v3"))
+}
+
+pub(crate) fn configure(config: &DataType) {
+    let base = config
+        .get("api_base_url")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .and_then(|s| normalize(s).ok())
+        .unwrap_or_else(|| DEFAULT_API_BASE.to_string());
+    API_BASE.with(|b| *b.borrow_mut() = base);
+}
+
+/// Absolute URL for an API path. GraphQL lives at `/api/graphql` on GHES,
+/// beside rather than under the REST prefix.
+pub(crate) fn url(path: &str) -> String {
+    if path.starts_with("https://") || path.starts_with("http://") {
+        return path.to_string();
+    }
+    API_BASE.with(|b| {
+        let base = b.borrow();
+        match base.strip_suffix("/api/v3") {
+            Some(host) if path == "/graphql" => format!("{host}/api/graphql"),
+            _ => format!("{base}{path}"),
+        }
+    })
+}
+
+/// The API path of a URL on the configured host (mock fixtures are keyed by path).
+pub(crate) fn strip(url: &str) -> &str {
+    let base = API_BASE.with(|b| b.borrow().clone());
+    url.strip_prefix(base.as_str()).unwrap_or(url)
+}
@@ -0,0 +1,93 @@
+use serde_json::{json, Map, Value};
+
+// =============================================================================
+// Uniform error envelope: {error: {code, message, details}}
+// =============================================================================
+
+fn code_for_status(status: u64, message: &str) -> &'static str {
+    match status {
+        400 => "bad_request",
+        401 => "unauthorized",
+        429 => "rate_limited",
+        403 if message.to_ascii_lowercase().contains("rate limit") => "rate_limited",
+        403 => "forbidden",
+        404 => "not_found",
+        409 => "conflict",
+        410 => "gone",
+        422 => "validation_failed",
+        500..=599 => "upstream_error",
+        _ => "http_error",
+    }
+}
+
+/// Whether a response body is GitHub's answer for a missing resource, by the
+/// numeric status `json_body` tags it with.
+pub(crate) fn is_not_found(body: &Value) -> bool {
+    body.get("status").and_then(|s| s.as_u64()) == Some(404)
+}
+
+fn envelope(code: &str, message: &str, details: Map<String, Value>) -> Value {
+    json!({"error": {"code": code, "message": message, "details": details}})
+}
+
+// GitHub error bodies carry `message` and `documentation_url`; `json_body`
+// adds the HTTP `status`. Bodiless failures only have `success: false`.
+fn github_failure(obj: &Map<String, Value>) -> Option<Value> {
+    let status = obj.get("status").and_then(|s| s.as_u64()).unwrap_or(0);
+    let message = obj.get("message").and_then(|m| m.as_str());
+    let is_error_body = message.is_some() && obj.contains_key("documentation_url");
+    let is_empty_failure = obj.get("success") == Some(&json!(false)) && status >= 400;
+    if !is_error_body && !is_empty_failure {
+        return None;
+    }
+    let message = message
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("GitHub API returned {status}"));
+    let mut details = Map::new();
+    if status > 0 {
+        details.insert("status".into(), json!(status));
+    }
+    for key in ["documentation_url", "errors", "retry_after", "rate_limit_reset"] {
+        if let Some(value) = obj.get(key) {
+            details.insert(key.into(), value.clone());
+        }
+    }
+    Some(envelope(code_for_status(status, &message), &message, details))
+}
+
+/// Rewrites a tool result that reports failure into the uniform envelope:
+/// tool-level `{"error": "..."}` (other fields become details) and GitHub
+/// error bodies passed through as data. Anything else is returned as is.
+pub(crate) fn normalize(output: Value) -> Value {
+    let Value::Object(mut obj) = output else {
+        return output;
+    };
+    if matches!(obj.get("error"), Some(Value::Object(e)) if e.contains_key("code")) {
+        return Value::Object(obj);
+    }
+    if let Some(error) = obj.remove("error") {
+        let message = match &error {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let code = if message.starts_with("unknown tool") {
+            "unknown_tool"
+        } else {
+            "invalid_request"
+        };
+        return envelope(code, &message, obj);
+    }
+    match github_failure(&obj) {
+        Some(envelope) => envelope,
+        None => Value::Object(obj),
+    }
+}
+
+/// Envelope for a tool that failed outright (transport or parse errors).
+pub(crate) fn failure(message: &str) -> Value {
+    envelope("request_failed", message, Map::new())
+}
+
+pub(crate) fn is_error(output: &Value) -> bool {
+    output.pointer("/error/code").is_some()
+}
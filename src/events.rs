@@ -0,0 +1,125 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_request_with, json_body, paging, state, str_list_arg, JSON_MEDIA_TYPE};
+
+const DEFAULT_PER_PAGE: u64 = 30;
+// GitHub serves at most 300 events (10 pages of 30 or 3 of 100) per feed.
+const MAX_PAGES: u64 = 10;
+
+// Feeds: a repo, an org, or a user's own (or received) activity.
+fn feed_path(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let user = args.get("user").and_then(|v| v.as_str()).unwrap_or("");
+    if !owner.is_empty() && !repo.is_empty() {
+        Some(format!("/repos/{owner}/{repo}/events"))
+    } else if !org.is_empty() {
+        Some(format!("/orgs/{org}/events"))
+    } else if !user.is_empty() && bool_arg(args, "received") {
+        Some(format!("/users/{user}/received_events"))
+    } else if !user.is_empty() {
+        Some(format!("/users/{user}/events"))
+    } else {
+        None
+    }
+}
+
+fn event_id(event: &Value) -> u64 {
+    event
+        .get("id")
+        .and_then(|v| v.as_str())
+        .and_then(|id| id.parse().ok())
+        .unwrap_or(0)
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Reads an event feed a page at a time (`page`, `per_page`, `max_pages`),
+/// keeping only `types` when given. With `only_new`, the feed's ETag and
+/// newest event id are kept in plugin state: an unchanged feed answers with
+/// a free 304, and events already returned are dropped.
+pub(crate) fn list_events(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = feed_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, org, or user is required"}),
+        )));
+    };
+    let types = str_list_arg(args, "types");
+    let paging = Paging::from_args(args, DEFAULT_PER_PAGE);
+    let first_page = paging.page;
+    let only_new = bool_arg(args, "only_new");
+
+    let key = format!("events{path}");
+    let previous = if only_new { state::load(&key) } else { None };
+    let etag = previous.as_ref().and_then(|p| p.get("etag")).and_then(|v| v.as_str());
+    let last_seen = previous
+        .as_ref()
+        .and_then(|p| p.get("last_id"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    let mut events = Vec::new();
+    let mut page_info = json!({"next_page": null});
+    let mut new_etag = None;
+    let mut poll_interval = None;
+    let mut page = first_page;
+    for _ in 0..paging.max_pages.min(MAX_PAGES) {
+        let extra = match etag {
+            Some(tag) if page == first_page => vec![("If-None-Match", tag.to_string())],
+            _ => Vec::new(),
+        };
+        let resp = github_request_with(token, "GET", &paging.url(&path, page), None, JSON_MEDIA_TYPE, &extra)?;
+        if page == first_page {
+            poll_interval = resp.header("x-poll-interval").and_then(|v| v.parse::<u64>().ok());
+            if resp.status == 304 {
+                return Ok(Json(DataType::from_json(json!({
+                    "changed": false,
+                    "items": [],
+                    "poll_interval": poll_interval
+                }))));
+            }
+            new_etag = resp.header("etag").map(str::to_string);
+        }
+        let mut data = paging::envelope(json_body(&resp, &Fields::default())?, resp.header("link"));
+        let Some(Value::Array(batch)) = data.get_mut("items").map(Value::take) else {
+            return Ok(Json(DataType::from_json(data)));
+        };
+        page_info = data.get("page_info").cloned().unwrap_or(page_info);
+        // Events are newest first, so everything past the last seen id is old.
+        let reached_seen = only_new && batch.iter().any(|e| event_id(e) <= last_seen);
+        events.extend(batch.into_iter().filter(|e| !only_new || event_id(e) > last_seen));
+        match page_info.get("next_page").and_then(|v| v.as_u64()) {
+            Some(next) if !reached_seen => page = next,
+            _ => break,
+        }
+    }
+
+    if only_new {
+        let newest = events.iter().map(event_id).max().unwrap_or(last_seen).max(last_seen);
+        state::save(&key, &json!({"etag": new_etag, "last_id": newest}))?;
+    }
+    let total = events.len();
+    let items: Vec<Value> = events
+        .into_iter()
+        .filter(|e| {
+            let kind = e.get("type").and_then(|v| v.as_str()).unwrap_or("");
+            types.is_empty() || types.iter().any(|t| t == kind)
+        })
+        .collect();
+    let filtered_out = total - items.len();
+
+    Ok(Json(DataType::from_json(json!({
+        "changed": true,
+        "items": items,
+        "filtered_out": filtered_out,
+        "page_info": page_info,
+        "poll_interval": poll_interval
+    }))))
+}
@@ -0,0 +1,263 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::{bool_arg, github_post};
+
+// Vetted queries shipped with the plugin; config `graphql_queries` entries
+// with the same name take precedence.
+const BUNDLED: &[(&str, &str, &str)] = &[
+    (
+        "pr_review_status",
+        "Review decision, latest reviews, and status check rollup for a pull request",
+        "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      title state isDraft mergeable reviewDecision
+      latestReviews(first: 20) { nodes { author { login } state submittedAt } }
+      reviewRequests(first: 20) { nodes { requestedReviewer { ... on User { login } ... on Team { slug } } } }
+      commits(last: 1) { nodes { commit { oid statusCheckRollup { state } } } }
+    }
+  }
+}",
+    ),
+    (
+        "recent_discussions",
+        "Most recently updated discussions in a repository with category and answer state",
+        "query($owner: String!, $repo: String!, $first: Int = 20) {
+  repository(owner: $owner, name: $repo) {
+    discussions(first: $first, orderBy: {field: UPDATED_AT, direction: DESC}) {
+      nodes { number title url updatedAt isAnswered category { name } author { login } comments { totalCount } }
+    }
+  }
+}",
+    ),
+    (
+        "user_contributions",
+        "A user's contribution totals over the last year",
+        "query($login: String!) {
+  user(login: $login) {
+    contributionsCollection {
+      totalCommitContributions totalIssueContributions totalPullRequestContributions
+      totalPullRequestReviewContributions restrictedContributionsCount
+    }
+  }
+}",
+    ),
+    (
+        "linked_issues",
+        "Issues a pull request will close when merged",
+        "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      closingIssuesReferences(first: 50) { nodes { number title state url } }
+    }
+  }
+}",
+    ),
+];
+
+struct NamedQuery {
+    description: String,
+    text: String,
+}
+
+fn lookup(config: &DataType, name: &str) -> Option<NamedQuery> {
+    let configured = config.get("graphql_queries").map(|q| q.to_json());
+    if let Some(entry) = configured.as_ref().and_then(|q| q.get(name)) {
+        let text = entry.get("query").or(Some(entry)).and_then(|q| q.as_str())?;
+        let description = entry.get("description").and_then(|d| d.as_str()).unwrap_or("");
+        return Some(NamedQuery {
+            description: description.to_string(),
+            text: text.to_string(),
+        });
+    }
+    BUNDLED
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, description, text)| NamedQuery {
+            description: description.to_string(),
+            text: text.to_string(),
+        })
+}
+
+/// Declared variables of the query's operation header as
+/// `(name, type, required)`; a default value makes a variable optional.
+fn declared_variables(query: &str) -> Vec<(String, String, bool)> {
+    let header = query.split('{').next().unwrap_or("");
+    let Some((_, params)) = header.split_once('(') else {
+        return Vec::new();
+    };
+    let params = params.rsplit_once(')').map(|(p, _)| p).unwrap_or(params);
+    params
+        .split('$')
+        .skip(1)
+        .filter_map(|decl| {
+            let (name, rest) = decl.split_once(':')?;
+            let (ty, default) = match rest.split_once('=') {
+                Some((ty, _)) => (ty, true),
+                None => (rest, false),
+            };
+            let ty = ty.trim().trim_end_matches(',').trim();
+            Some((name.trim().to_string(), ty.to_string(), ty.ends_with('!') && !default))
+        })
+        .collect()
+}
+
+/// POSTs a query to the GraphQL endpoint (`/api/graphql` on GHES) and
+/// returns the whole response: `data`, plus `errors` on partial failure.
+pub(crate) fn github_graphql(token: &str, query: &str, variables: &Value) -> Result<Value, Error> {
+    github_post(token, "/graphql", &json!({"query": query, "variables": variables}))
+}
+
+/// Operation types defined in a document, in order (`query` for the
+/// shorthand `{ ... }` form; fragments show up as `fragment`).
+fn operation_types(document: &str) -> Vec<String> {
+    let mut types = Vec::new();
+    // First keyword of the top-level definition being read.
+    let mut header: Option<String> = None;
+    let (mut depth, mut parens) = (0usize, 0usize);
+    let mut word = String::new();
+    let mut chars = document.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if depth == 0 && parens == 0 && header.is_none() && !word.is_empty() {
+            header = Some(word.clone());
+        }
+        word.clear();
+        match c {
+            '#' => while chars.next_if(|&n| n != '\n').is_some() {},
+            '"' => {
+                while let Some(n) = chars.next() {
+                    match n {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' => parens += 1,
+            ')' => parens = parens.saturating_sub(1),
+            '{' if parens == 0 => {
+                if depth == 0 {
+                    types.push(header.clone().unwrap_or_else(|| "query".to_string()));
+                }
+                depth += 1;
+            }
+            '}' if parens == 0 => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    header = None;
+                }
+            }
+            _ => {}
+        }
+    }
+    types
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn list_named_queries(config: &DataType) -> FnResult<Json<DataType>> {
+    let mut names: Vec<String> = BUNDLED.iter().map(|(n, _, _)| n.to_string()).collect();
+    if let Some(Value::Object(configured)) = config.get("graphql_queries").map(|q| q.to_json()) {
+        let extra: Vec<String> = configured.keys().filter(|k| !names.contains(k)).cloned().collect();
+        names.extend(extra);
+    }
+    let queries: Vec<Value> = names
+        .iter()
+        .filter_map(|name| {
+            let query = lookup(config, name)?;
+            let variables: Vec<Value> = declared_variables(&query.text)
+                .into_iter()
+                .map(|(n, ty, required)| json!({"name": n, "type": ty, "required": required}))
+                .collect();
+            Some(json!({"name": name, "description": query.description, "variables": variables}))
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"queries": queries}))))
+}
+
+/// Runs a registered query by name. Only variables the query declares are
+/// accepted, and required ones must be present, so callers can parameterize
+/// but never change the query text.
+pub(crate) fn run_named_query(token: &str, args: &DataType, config: &DataType) -> FnResult<Json<DataType>> {
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    if name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "name is required"}))));
+    }
+    let Some(query) = lookup(config, name) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("unknown query: {name}")}),
+        )));
+    };
+    let variables = match args.get("variables").map(|v| v.to_json()) {
+        Some(Value::Object(vars)) => vars,
+        _ => Map::new(),
+    };
+    let declared = declared_variables(&query.text);
+    let unknown: Vec<&String> = variables
+        .keys()
+        .filter(|k| !declared.iter().any(|(n, _, _)| n == *k))
+        .collect();
+    let missing: Vec<&str> = declared
+        .iter()
+        .filter(|(n, _, required)| *required && matches!(variables.get(n), None | Some(Value::Null)))
+        .map(|(n, _, _)| n.as_str())
+        .collect();
+    if !unknown.is_empty() || !missing.is_empty() {
+        return Ok(Json(DataType::from_json(json!({
+            "error": "variables do not match the query",
+            "unknown": unknown,
+            "missing": missing
+        }))));
+    }
+
+    let resp = github_graphql(token, &query.text, &Value::Object(variables))?;
+    Ok(Json(DataType::from_json(json!({
+        "name": name,
+        "data": resp.get("data"),
+        "errors": resp.get("errors").or(resp.get("message"))
+    }))))
+}
+
+/// Runs caller-supplied GraphQL. Read-only unless `allow_mutation`, so
+/// writes stay behind the dedicated tools and their confirmation gates.
+pub(crate) fn graphql(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+    if query.trim().is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "query is required"}))));
+    }
+    let types = operation_types(query);
+    if types.iter().any(|t| t == "subscription") {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "subscriptions are not supported over HTTP"}),
+        )));
+    }
+    if types.iter().any(|t| t == "mutation") && !bool_arg(args, "allow_mutation") {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "query contains a mutation; pass allow_mutation to run it"}),
+        )));
+    }
+    let variables = match args.get("variables").map(|v| v.to_json()) {
+        Some(Value::Object(vars)) => Value::Object(vars),
+        None | Some(Value::Null) => json!({}),
+        Some(_) => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "variables must be an object"}),
+            )))
+        }
+    };
+    let resp = github_graphql(token, query, &variables)?;
+    Ok(Json(DataType::from_json(json!({
+        "data": resp.get("data"),
+        "errors": resp.get("errors").or(resp.get("message"))
+    }))))
+}
@@ -0,0 +1,190 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_all;
+use crate::{github_delete, github_patch, github_post, str_list_arg, u64_arg, url_encode};
+
+const STATES: &[&str] = &["open", "closed"];
+const STATE_REASONS: &[&str] = &["completed", "not_planned", "duplicate", "reopened"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn issue_path(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number")?;
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some(format!("/repos/{owner}/{repo}/issues/{number}"))
+}
+
+fn state_reason_error(reason: &str) -> Option<Value> {
+    (!STATE_REASONS.contains(&reason))
+        .then(|| json!({"error": format!("state_reason must be one of: {}", STATE_REASONS.join(", "))}))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Edits an issue (or a PR's issue fields): `title`, `body`, `state`, and
+/// `state_reason`. Only the given fields change.
+pub(crate) fn update_issue(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = issue_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    };
+    let mut body = json!({});
+    for key in ["title", "body"] {
+        if let Some(value) = args.get(key).and_then(|v| v.as_str()) {
+            body[key] = json!(value);
+        }
+    }
+    if let Some(state) = args.get("state").and_then(|v| v.as_str()) {
+        if !STATES.contains(&state) {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "state must be open or closed"}),
+            )));
+        }
+        body["state"] = json!(state);
+    }
+    if let Some(reason) = args.get("state_reason").and_then(|v| v.as_str()) {
+        if let Some(error) = state_reason_error(reason) {
+            return Ok(Json(DataType::from_json(error)));
+        }
+        body["state_reason"] = json!(reason);
+    }
+    if body.as_object().is_some_and(|b| b.is_empty()) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "nothing to update; give title, body, state, or state_reason"}),
+        )));
+    }
+    let data = github_patch(token, &path, &body)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Closes an issue as `state_reason` (completed by default), optionally
+/// leaving a `comment` first so the closing explains itself.
+pub(crate) fn close_issue(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = issue_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    };
+    let reason = args.get("state_reason").and_then(|v| v.as_str()).unwrap_or("completed");
+    if reason == "reopened" {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "reopened is not a closing reason"}),
+        )));
+    }
+    if let Some(error) = state_reason_error(reason) {
+        return Ok(Json(DataType::from_json(error)));
+    }
+    if let Some(comment) = args.get("comment").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        let posted = github_post(token, &format!("{path}/comments"), &json!({"body": comment}))?;
+        if posted.get("id").is_none() {
+            return Ok(Json(DataType::from_json(posted)));
+        }
+    }
+    let data = github_patch(token, &path, &json!({"state": "closed", "state_reason": reason}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn add_labels(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let labels = str_list_arg(args, "labels");
+    let Some(path) = issue_path(args).filter(|_| !labels.is_empty()) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and labels are required"}),
+        )));
+    };
+    // Labels that don't exist yet are created by GitHub with a default color.
+    let data = github_post(token, &format!("{path}/labels"), &json!({"labels": labels}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn remove_label(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let label = args.get("label").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(path) = issue_path(args).filter(|_| !label.is_empty()) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and label are required"}),
+        )));
+    };
+    let data = github_delete(token, &format!("{path}/labels/{}", url_encode(label)))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Adds up to 10 assignees. Users without push access are silently
+/// skipped by GitHub, so the result lists who actually ended up assigned.
+pub(crate) fn add_assignees(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let assignees = str_list_arg(args, "assignees");
+    let Some(path) = issue_path(args).filter(|_| !assignees.is_empty()) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, number, and assignees are required"}),
+        )));
+    };
+    let data = github_post(token, &format!("{path}/assignees"), &json!({"assignees": assignees}))?;
+    let Some(assigned) = data.get("assignees").and_then(|a| a.as_array()) else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let assigned: Vec<&str> = assigned
+        .iter()
+        .filter_map(|a| a.get("login").and_then(|l| l.as_str()))
+        .collect();
+    let skipped: Vec<&String> = assignees
+        .iter()
+        .filter(|a| !assigned.iter().any(|l| l.eq_ignore_ascii_case(a)))
+        .collect();
+    Ok(Json(DataType::from_json(json!({
+        "number": data.get("number"),
+        "assignees": assigned,
+        "skipped": skipped,
+        "html_url": data.get("html_url")
+    }))))
+}
+
+/// Sets the milestone by `milestone` number or `title` (open milestones),
+/// or clears it when `milestone` is null or 0.
+pub(crate) fn set_milestone(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = issue_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    };
+    let title = args.get("title").and_then(|v| v.as_str()).filter(|t| !t.is_empty());
+    let milestone = match (u64_arg(args, "milestone"), title) {
+        (Some(0), _) => Value::Null,
+        (Some(number), _) => json!(number),
+        (None, Some(title)) => {
+            let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+            let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+            let milestones = match get_all(token, &format!("/repos/{owner}/{repo}/milestones?state=open")) {
+                Ok(listing) => listing.items,
+                Err(error) => return Ok(Json(DataType::from_json(error))),
+            };
+            let found = milestones
+                .into_iter()
+                .find(|m| m.get("title").and_then(|t| t.as_str()) == Some(title));
+            match found.and_then(|m| m.get("number").cloned()) {
+                Some(number) => number,
+                None => {
+                    return Ok(Json(DataType::from_json(
+                        json!({"error": format!("no open milestone titled {title}")}),
+                    )))
+                }
+            }
+        }
+        (None, None) if matches!(args.get("milestone").map(|v| v.to_json()), Some(Value::Null)) => Value::Null,
+        (None, None) => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "milestone (number, or null to clear) or title is required"}),
+            )))
+        }
+    };
+    let data = github_patch(token, &path, &json!({"milestone": milestone}))?;
+    Ok(Json(DataType::from_json(data)))
+}
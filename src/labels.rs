@@ -0,0 +1,169 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_all;
+use crate::{bool_arg, github_delete, github_patch, github_post, repos_arg, url_encode};
+
+struct Label {
+    name: String,
+    color: String,
+    description: String,
+    aliases: Vec<String>,
+}
+
+fn parse_labels(value: &Value) -> Result<Vec<Label>, String> {
+    let Some(items) = value.as_array() else {
+        return Err("labels must be an array of {name, color, description, aliases}".into());
+    };
+    items
+        .iter()
+        .map(|item| {
+            let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            if name.is_empty() {
+                return Err("every label needs a name".to_string());
+            }
+            let color = item.get("color").and_then(|v| v.as_str()).unwrap_or("ededed");
+            Ok(Label {
+                name: name.to_string(),
+                color: color.trim_start_matches('#').to_ascii_lowercase(),
+                description: item
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                aliases: item
+                    .get("aliases")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|s| s.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn name_of(label: &Value) -> &str {
+    label.get("name").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn outcome(result: Result<Value, Error>) -> Option<String> {
+    match result {
+        Ok(data) => data.get("message").and_then(|m| m.as_str()).map(str::to_string),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Makes each repo's labels match a canonical set. Existing labels are
+/// matched by name case-insensitively, then by `aliases` (which renames them
+/// and keeps their issue assignments); the rest are created. Labels not in
+/// the set are deleted only with `delete_extra`. `dry_run` reports the plan.
+pub(crate) fn sync_labels(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let repos = repos_arg(args);
+    if repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "repos (or owner and repo) are required"}),
+        )));
+    }
+    let canonical = match parse_labels(&args.get("labels").map(|v| v.to_json()).unwrap_or(Value::Null)) {
+        Ok(labels) => labels,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let dry_run = bool_arg(args, "dry_run");
+    let delete_extra = bool_arg(args, "delete_extra");
+
+    let mut reports = Vec::new();
+    for (owner, repo) in &repos {
+        let base = format!("/repos/{owner}/{repo}/labels");
+        // Without the full current set every canonical label would look
+        // missing, so an unreadable or cut-off list skips the repo.
+        let mut existing = match get_all(token, &base) {
+            Ok(listing) if !listing.truncated() => listing.items,
+            Ok(_) => {
+                reports.push(json!({"repo": format!("{owner}/{repo}"), "error": "too many labels to read them all"}));
+                continue;
+            }
+            Err(error) => {
+                reports.push(json!({"repo": format!("{owner}/{repo}"), "error": error}));
+                continue;
+            }
+        };
+        let mut changes = Vec::new();
+
+        for label in &canonical {
+            let found = existing
+                .iter()
+                .position(|l| name_of(l).eq_ignore_ascii_case(&label.name))
+                .or_else(|| {
+                    existing
+                        .iter()
+                        .position(|l| label.aliases.iter().any(|a| a.eq_ignore_ascii_case(name_of(l))))
+                });
+            let Some(index) = found else {
+                let error = (!dry_run)
+                    .then(|| {
+                        let body = json!({"name": label.name, "color": label.color, "description": label.description});
+                        outcome(github_post(token, &base, &body))
+                    })
+                    .flatten();
+                changes.push(json!({"action": "create", "label": label.name, "error": error}));
+                continue;
+            };
+            let current = existing.remove(index);
+            let current_name = name_of(&current).to_string();
+            let color = current
+                .get("color")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            let description = current.get("description").and_then(|v| v.as_str()).unwrap_or("");
+            let renamed = current_name != label.name;
+            if !renamed && color == label.color && description == label.description {
+                continue;
+            }
+            let error = (!dry_run)
+                .then(|| {
+                    let body = json!({"new_name": label.name, "color": label.color, "description": label.description});
+                    outcome(github_patch(
+                        token,
+                        &format!("{base}/{}", url_encode(&current_name)),
+                        &body,
+                    ))
+                })
+                .flatten();
+            changes.push(json!({
+                "action": if renamed { "rename" } else { "update" },
+                "label": label.name,
+                "from": current_name,
+                "color": if color != label.color { json!([color, label.color]) } else { Value::Null },
+                "description": if description != label.description {
+                    json!([description, label.description])
+                } else {
+                    Value::Null
+                },
+                "error": error
+            }));
+        }
+
+        // Whatever was not matched above is outside the canonical set.
+        for extra in &existing {
+            let name = name_of(extra);
+            if !delete_extra {
+                changes.push(json!({"action": "keep_extra", "label": name}));
+                continue;
+            }
+            let error = (!dry_run)
+                .then(|| outcome(github_delete(token, &format!("{base}/{}", url_encode(name)))))
+                .flatten();
+            changes.push(json!({"action": "delete", "label": name, "error": error}));
+        }
+
+        let failed = changes.iter().filter(|c| !c["error"].is_null()).count();
+        reports.push(json!({"repo": format!("{owner}/{repo}"), "changes": changes, "failed": failed}));
+    }
+
+    Ok(Json(DataType::from_json(json!({"dry_run": dry_run, "repos": reports}))))
+}
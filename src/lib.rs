@@ -1,6 +1,72 @@
+use std::collections::BTreeMap;
+
 use extism_pdk::*;
 use magi_pdk::DataType;
 use serde_json::json;
+use paging::Paging;
+use stream::Fields;
+
+mod actions;
+mod branches;
+mod cache;
+mod checks;
+mod cherry_pick;
+mod codeowners;
+mod commit_lint;
+mod comments;
+mod commits;
+mod composite;
+mod confirm;
+mod contents;
+mod dependabot;
+mod dependencies;
+mod deployments;
+mod diff;
+mod endpoint;
+mod errors;
+mod events;
+mod graphql;
+mod issues;
+mod labels;
+mod limits;
+mod logging;
+mod migrate;
+mod misc;
+mod mock;
+mod moderation;
+mod notifications;
+mod orgs;
+mod paging;
+mod pat;
+mod permissions;
+mod protection;
+mod pulls;
+mod ratelimit;
+mod redact;
+mod refs;
+mod releases;
+mod repo_admin;
+mod reviewers;
+mod search;
+mod secrets;
+mod security;
+mod signing;
+mod sso;
+mod stale;
+mod state;
+mod stats;
+mod stream;
+mod sync;
+mod templates;
+mod time;
+mod traffic;
+mod trailers;
+mod tree;
+mod usage;
+mod users;
+mod wait;
+mod webhooks;
+mod workflow_lint;
 
 // =============================================================================
 // Plugin exports
@@ -14,14 +80,164 @@ pub fn describe() -> FnResult<Json<DataType>> {
         "description": "GitHub API integration for repos, issues, PRs, and code search",
         "label": "mcp",
         "tools": [
-            {"name": "list_repos", "description": "List repositories for a user or org"},
+            {"name": "list_repos", "description": "List repositories for a user or org (`page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
             {"name": "get_repo", "description": "Get repository details"},
-            {"name": "list_issues", "description": "List issues for a repository"},
+            {"name": "list_issues", "description": "List issues for a repository (`page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
             {"name": "create_issue", "description": "Create a new issue"},
-            {"name": "list_prs", "description": "List pull requests for a repository"},
-            {"name": "get_pr", "description": "Get pull request details"},
-            {"name": "get_file", "description": "Get file contents from a repository"},
-            {"name": "search_code", "description": "Search code across repositories"}
+            {"name": "update_issue", "description": "Update an issue's title, body, state (open/closed), or state_reason (completed, not_planned, duplicate, reopened)"},
+            {"name": "close_issue", "description": "Close an issue with a `state_reason` (default completed) and optional closing `comment`"},
+            {"name": "add_labels", "description": "Add `labels` to an issue or PR"},
+            {"name": "remove_label", "description": "Remove one `label` from an issue or PR"},
+            {"name": "add_assignees", "description": "Assign users to an issue or PR, reporting any GitHub skipped for lack of access"},
+            {"name": "set_milestone", "description": "Set an issue's milestone by `milestone` number or open milestone `title`; null or 0 clears it"},
+            {"name": "list_prs", "description": "List pull requests for a repository (`page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
+            {"name": "get_pr", "description": "Get pull request details (`format`: json, diff, or patch)"},
+            {"name": "get_file", "description": "Get a file as decoded text with `sha`, `size`, and `html_url`; files over 1 MB come from the Git Blobs API (`format`: json, raw, or html; binary files are summarized unless `as_base64`)"},
+            {"name": "find_definition", "description": "Find where a `symbol` is defined in a repo (`owner`/`repo`) or `org`, optionally by `language`, with `context_lines` around each site"},
+            {"name": "create_or_update_file", "description": "Commit a file to a branch: `content` (text) or `content_base64`, `message`, `branch`, optional `sha` (looked up when omitted), `committer`/`author` {name, email}, and commit trailers (`trailers`, `co_authors`, `signed_off_by`, `closes`)"},
+            {"name": "delete_file", "description": "Delete a file in a commit on `branch` with `message`; optional `sha`, `committer`/`author`, and commit trailers"},
+            {"name": "search_code", "description": "Search code across repositories (`page`, `per_page`, `max_pages`, `fields`; `sort_by`/`order`, `max_per_repo`, `dedupe_by_repo`; returns items and page_info)"},
+            {"name": "search_issues", "description": "Search issues and PRs with GitHub query syntax (`is:pr`, `label:\"good first issue\"`, ...); `sort` (comments, reactions, interactions, created, updated) and `order`, plus the search_code paging and refinement args"},
+            {"name": "search_repos", "description": "Search repositories with GitHub query syntax; `sort` (stars, forks, help-wanted-issues, updated) and `order`, plus the search_code paging and refinement args"},
+            {"name": "search_users", "description": "Search users and orgs with GitHub query syntax; `sort` (followers, repositories, joined) and `order`, plus the search_code paging and refinement args"},
+            {"name": "render_markdown", "description": "Render Markdown to HTML (GFM with repository context for links and mentions)"},
+            {"name": "list_gitignore_templates", "description": "List available .gitignore template names"},
+            {"name": "get_gitignore_template", "description": "Get a .gitignore template by name"},
+            {"name": "list_licenses", "description": "List common license templates"},
+            {"name": "get_license", "description": "Get a license template (full text and metadata) by key"},
+            {"name": "get_meta", "description": "Get GitHub meta info: hook/actions IP ranges and SSH key fingerprints"},
+            {"name": "list_emojis", "description": "List emoji shortcodes, optionally fuzzy-filtered by `query` (`limit`, default 50)"},
+            {"name": "parse_diff", "description": "Parse unified diff text into structured files, hunks, and line changes"},
+            {"name": "check_pr_commits", "description": "Validate a PR's commit messages against the configured commit convention"},
+            {"name": "find_stale", "description": "Find open issues/PRs inactive for N days; optionally label or comment on them"},
+            {"name": "summarize_tree", "description": "Summarize a repo tree: per-directory counts and sizes, extension/language breakdown, largest files"},
+            {"name": "create_check_run", "description": "Create a check run on a commit with status, conclusion, summary, and annotations (GitHub App token required)"},
+            {"name": "update_check_run", "description": "Update a check run's status, conclusion, output, and annotations (GitHub App token required)"},
+            {"name": "list_check_suites", "description": "List check suites for a commit SHA, branch, or tag"},
+            {"name": "rerequest_check_suite", "description": "Rerequest a check suite so its App runs it again"},
+            {"name": "deployment_history", "description": "Deployment history for an environment (who deployed which SHA when, with status) and what is currently live"},
+            {"name": "branch_drift", "description": "Report how far a branch is ahead/behind a base, with diverging commits and changed files"},
+            {"name": "list_commits", "description": "List commits with signature verification (`sha`, `path`, `author`, `committer`, `since`/`until`, `page`, `per_page`, `max_pages`, `verified` filter)"},
+            {"name": "get_commit", "description": "Get a commit with its signature verification and per-file patches (`format`: json, diff, or patch)"},
+            {"name": "compare_commits", "description": "Compare `base...head`: ahead/behind counts, commits, and per-file patches (`format`: json, diff, or patch)"},
+            {"name": "get_file_history", "description": "Commits that touched a file, newest first, with messages; follows renames (`follow_renames`, default true); optional `ref` and `limit` (max 300)"},
+            {"name": "check_signed_commits", "description": "Audit that every commit in base...head is signed and verified"},
+            {"name": "get_interaction_limits", "description": "Get interaction limits on a repo or org"},
+            {"name": "set_interaction_limits", "description": "Restrict interactions on a repo or org (existing_users, contributors_only, collaborators_only) with an expiry"},
+            {"name": "remove_interaction_limits", "description": "Remove interaction limits from a repo or org"},
+            {"name": "list_blocked_users", "description": "List users blocked by the token user, or by an org when `org` is given"},
+            {"name": "block_user", "description": "Block a user from the token user's account or from an org"},
+            {"name": "unblock_user", "description": "Unblock a user at the user or org level"},
+            {"name": "get_org_billing", "description": "Org billing usage for Actions, Packages, and shared storage (`kind` selects one)"},
+            {"name": "set_repo_visibility", "description": "Change repository visibility; first call previews exposure and returns a confirm_token"},
+            {"name": "get_issue_forms", "description": "Parse .github/ISSUE_TEMPLATE issue forms into field schemas with required fields and a body template"},
+            {"name": "get_pr_templates", "description": "Get pull request templates from every recognized location, including multiple-template directories"},
+            {"name": "create_pr", "description": "Create a pull request (`template` prefills the body from a PR template, filling `{{placeholders}}` from `template_vars`)"},
+            {"name": "update_pr", "description": "Update a pull request's title, body, state (open/closed), or base branch"},
+            {"name": "merge_pr", "description": "Merge a pull request (`merge_method`: merge, squash, or rebase; optional commit_title, commit_message, and head `sha`)"},
+            {"name": "get_required_status_checks", "description": "Get the required status checks (strict flag and check names) on a protected branch"},
+            {"name": "update_required_status_checks", "description": "Replace (`checks`) or edit (`add`/`remove`) a protected branch's required status checks, and set `strict`"},
+            {"name": "list_tag_protection", "description": "List tag protection patterns and tag rulesets for one repo or many (`repos`)"},
+            {"name": "create_tag_protection", "description": "Protect tags matching a pattern (e.g. v*) on one repo or many, as a tag ruleset or legacy tag protection"},
+            {"name": "delete_tag_protection", "description": "Delete a tag ruleset or legacy tag protection pattern by id"},
+            {"name": "suggest_reviewers", "description": "Rank reviewers for a PR from CODEOWNERS, recent history of the changed files, and current review load"},
+            {"name": "list_my_orgs", "description": "List the orgs the token's user belongs to, with membership state and role"},
+            {"name": "get_my_org_membership", "description": "Get the token user's membership state and role in an org"},
+            {"name": "check_permission", "description": "Check whether a user has at least a given permission (read, triage, write, maintain, admin) on a repo"},
+            {"name": "list_custom_repo_roles", "description": "List an org's custom repository roles"},
+            {"name": "get_custom_repo_role", "description": "Get a custom repository role with its base role and permissions"},
+            {"name": "add_collaborator", "description": "Add a repo collaborator with a built-in or custom repository role"},
+            {"name": "add_team_repo", "description": "Grant a team access to a repo with a built-in or custom repository role"},
+            {"name": "get_code_scanning_setup", "description": "Get CodeQL default setup (state, languages, query suite, schedule) for one repo or many"},
+            {"name": "update_code_scanning_setup", "description": "Enable or disable CodeQL default setup with languages and query suite on one repo or many"},
+            {"name": "set_security_features", "description": "Enable or disable vulnerability alerts, Dependabot security updates, secret scanning, and push protection on one repo or many"},
+            {"name": "get_run_attempts", "description": "List a workflow run's attempts with per-job queue vs execution time, totalled across attempts"},
+            {"name": "cleanup_artifacts", "description": "Delete Actions artifacts older than N days or matching name patterns (`dry_run` to preview), reporting reclaimed storage; resume a truncated scan with `page`"},
+            {"name": "get_actions_policy", "description": "Get org or repo Actions policy: allowed actions, default workflow token permissions, fork PR approval"},
+            {"name": "set_actions_policy", "description": "Update org or repo Actions policy sections (allowed actions and patterns, workflow token permissions, fork PR approval)"},
+            {"name": "get_oidc_subject", "description": "Get the Actions OIDC subject claim template for an org or repo (`include_claim_keys`, `use_default`)"},
+            {"name": "set_oidc_subject", "description": "Set the Actions OIDC subject claim template for an org or repo from `include_claim_keys`, or reset a repo with `use_default`"},
+            {"name": "list_org_secrets", "description": "List org Actions secrets (names, visibility, timestamps; never values)"},
+            {"name": "set_org_secret", "description": "Create or update an org Actions secret (encrypted client-side) with all, private, or selected visibility"},
+            {"name": "list_org_variables", "description": "List org Actions variables"},
+            {"name": "set_org_variable", "description": "Create or update an org Actions variable with all, private, or selected visibility"},
+            {"name": "get_org_selected_repos", "description": "List repositories that can use a selected-visibility org secret or variable"},
+            {"name": "set_org_selected_repos", "description": "Set (`repos`) or edit (`add`/`remove`) the repositories for a selected-visibility org secret or variable"},
+            {"name": "list_environment_secrets", "description": "List secrets scoped to a deployment environment (names and timestamps only)"},
+            {"name": "set_environment_secret", "description": "Create or rotate a deployment environment secret (encrypted with the environment's key)"},
+            {"name": "delete_environment_secret", "description": "Delete a deployment environment secret"},
+            {"name": "list_named_queries", "description": "List registered GraphQL queries (bundled and from config) with their variables"},
+            {"name": "run_named_query", "description": "Run a registered GraphQL query by name with `variables`; query text cannot be supplied"},
+            {"name": "graphql", "description": "Run an arbitrary GraphQL query with `variables`; mutations require `allow_mutation`"},
+            {"name": "export_issues", "description": "Export all issues as NDJSON or CSV chunks (optionally with comments), resumable via `cursor`"},
+            {"name": "import_issues", "description": "Create issues from an array of definitions (title, body, labels, assignees, closed) with throttling and per-item results"},
+            {"name": "sync_labels", "description": "Create, update, or rename labels across repos to match a canonical set (`dry_run`, `delete_extra`), reporting the diff"},
+            {"name": "org_repos", "description": "Inventory all repos of an org (or an App installation) with `type`/`visibility` filters, auto-paginated up to `max_items`; resume with `page` and `offset`"},
+            {"name": "snapshot_traffic", "description": "Store the last 14 days of views and clones in plugin state (`emit_only` just returns them)"},
+            {"name": "traffic_history", "description": "Read stored traffic history, daily or per week (`per`), from `since`"},
+            {"name": "contributor_activity", "description": "Per-user commits, PRs opened and merged, and reviews in a repo over a date range"},
+            {"name": "commit_activity", "description": "Weekly commit counts with per-day breakdown for the last year (waits out GitHub's 202 while stats compute)"},
+            {"name": "code_frequency", "description": "Weekly additions and deletions (waits out GitHub's 202 while stats compute)"},
+            {"name": "punch_card", "description": "Commits by weekday and hour with weekday/hour totals and the busiest slots"},
+            {"name": "participation", "description": "Owner vs community weekly commit counts for the last year, with the community share"},
+            {"name": "pr_metrics", "description": "PR size and complexity (lines, files, directories, largest-file share) for one PR or PRs merged in a period, flagging oversized ones"},
+            {"name": "detect_flaky_jobs", "description": "Rank jobs that pass and fail on the same commit or pass after a rerun across recent workflow runs"},
+            {"name": "validate_workflow", "description": "Statically check a workflow file (or inline `content`) for unknown keys, a missing `on`, `needs` cycles, and deprecated workflow commands"},
+            {"name": "list_dependencies", "description": "List a repo's declared dependencies per manifest from the dependency graph (`ecosystem` filter)"},
+            {"name": "find_dependents", "description": "Find repositories whose manifests reference a package (code search; optionally within an org)"},
+            {"name": "list_bypass_requests", "description": "List secret scanning push protection bypass requests for a repo or org (open ones by default)"},
+            {"name": "review_bypass_request", "description": "Approve or deny a push protection bypass request"},
+            {"name": "list_my_invitations", "description": "List the token user's pending repository invitations"},
+            {"name": "respond_to_invitation", "description": "Accept or decline a repository invitation (`action`: accept or decline)"},
+            {"name": "compare_releases", "description": "Commits and merged PRs between two release tags (`from` defaults to the previous release)"},
+            {"name": "release_readiness", "description": "Pass/fail release checklist for a ref: CI green, no open `blocker_label` (release-blocker) issues, changelog entry, and version bumped past the latest release tag"},
+            {"name": "list_branches", "description": "List branches (`protected` filter, `page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
+            {"name": "get_branch", "description": "Get a branch with its head commit and protection status"},
+            {"name": "create_branch", "description": "Create a branch at `sha` or at the tip of `from` (branch, tag, or SHA; default branch when omitted)"},
+            {"name": "delete_branch", "description": "Delete a branch; the default branch is refused and unmerged commits require the confirmation handshake"},
+            {"name": "get_ref", "description": "Get a git ref (e.g. heads/main or tags/v1.0) and the object it points at"},
+            {"name": "update_ref", "description": "Point a git ref at a SHA (`force` requires the confirmation handshake)"},
+            {"name": "cherry_pick_commit", "description": "Cherry-pick a commit onto a branch via the Git data API (`open_pr` to land it through a PR); trailers via `trailers`, `co_authors`, `signed_off_by`, `closes`"},
+            {"name": "revert_commit", "description": "Revert a commit on a branch via the Git data API (`open_pr` to land it through a PR); trailers via `trailers`, `co_authors`, `signed_off_by`, `closes`"},
+            {"name": "backport_pr", "description": "Backport a merged PR to target `branches`, opening a labeled PR per branch and reporting conflicts; trailers via `trailers`, `co_authors`, `signed_off_by`, `closes`"},
+            {"name": "list_prs_by_path", "description": "List open PRs touching any of the given `paths` prefixes (file lists cached per head SHA)"},
+            {"name": "get_pr_files", "description": "List the files a PR changes with per-file patches (`page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
+            {"name": "get_pr_diff", "description": "Get a PR as a unified diff (`format`: diff or patch); oversized diffs point to get_pr_files"},
+            {"name": "list_reviews", "description": "List reviews on a pull request (`page`, `per_page`, `max_pages`, `fields`; returns items and page_info)"},
+            {"name": "list_review_comments", "description": "List inline review comments on a pull request, optionally for one `review_id`"},
+            {"name": "submit_review", "description": "Submit a PR review (APPROVE, REQUEST_CHANGES, or COMMENT) with optional inline comments by path and line"},
+            {"name": "wait_for", "description": "Poll a workflow run, fork, or commit statuses until terminal or `timeout_secs`, returning the final state"},
+            {"name": "list_hook_deliveries", "description": "List recent deliveries for a repo or org webhook (`cursor` paging, `failed_only`)"},
+            {"name": "get_hook_delivery", "description": "Get one webhook delivery with its full request and response"},
+            {"name": "redeliver_hook_delivery", "description": "Redeliver a webhook delivery"},
+            {"name": "preview_change", "description": "Unified diff of proposed file content (or `delete`) against a branch, without committing"},
+            {"name": "list_comments", "description": "List conversation comments on an issue or PR (`since`, `page`, `per_page`, `max_pages`, `fields`)"},
+            {"name": "create_comment", "description": "Comment on an issue or PR"},
+            {"name": "update_comment", "description": "Edit an issue or PR comment"},
+            {"name": "delete_comment", "description": "Delete an issue or PR comment"},
+            {"name": "add_reaction", "description": "React (+1, -1, laugh, confused, heart, hooray, rocket, eyes) to an issue, comment, or review comment"},
+            {"name": "remove_reaction", "description": "Remove a reaction from an issue, comment, or review comment"},
+            {"name": "membership_diff", "description": "Compare org members and team rosters with a canonical list, reporting who to add and remove"},
+            {"name": "list_pat_requests", "description": "List pending fine-grained PAT requests to an org (filters: `owner`, `repository`, `permission`, `last_used_before`/`after`, `token_id`; `include_repositories`)"},
+            {"name": "review_pat_requests", "description": "Approve or deny fine-grained PAT requests by `request_ids` (`action`: approve or deny, optional `reason`)"},
+            {"name": "list_pat_grants", "description": "List fine-grained PATs with access to an org and their permissions (same filters; `include_repositories` adds the repository scope)"},
+            {"name": "list_credential_authorizations", "description": "Audit an org's SAML SSO authorized credentials (optional `login`), flagging stale (`stale_days`, default 90) and expired ones"},
+            {"name": "revoke_credential_authorization", "description": "Revoke an org SAML SSO credential authorization by `credential_id`"},
+            {"name": "triage_dependabot_prs", "description": "Rank open Dependabot PRs by security fix and bump size with CI status, vulnerability delta, and a `merge_eligible` flag"},
+            {"name": "pr_overview", "description": "A pull request with reviews, requested reviewers, unresolved threads, CI checks, and linked issues in one GraphQL call"},
+            {"name": "repo_overview", "description": "Summarize a repository: metadata, default branch, top-level tree, languages, latest release, open counts, and CI status"},
+            {"name": "issue_context", "description": "Bundle an issue with its comments, cross-references, linked PRs, labels, and assignees"},
+            {"name": "reference_graph", "description": "Graph of issues and PRs related to an issue or PR through cross-references (`depth` up to 3, `max_nodes` up to 50), with state and title per node"},
+            {"name": "repo_health", "description": "Scorecard of default-branch CI, open PR age, stale issues, Dependabot alerts, and last-release age"},
+            {"name": "get_combined_status", "description": "Merge commit statuses and check runs for any branch, tag, or SHA into one pass/fail/pending verdict"},
+            {"name": "sync_issues", "description": "Return issues changed since the previous sync of a repository"},
+            {"name": "sync_prs", "description": "Return pull requests changed since the previous sync of a repository"},
+            {"name": "list_events", "description": "Read a repo, org, or user event feed with paging, `types` filtering, and `only_new` ETag polling"},
+            {"name": "poll_notifications", "description": "Return only notifications new since the previous poll (conditional request; `all`, `participating`, `reset`)"},
+            {"name": "set_mock_fixtures", "description": "Store mock-mode fixtures in plugin state"},
+            {"name": "get_rate_limit", "description": "Remaining API quota and reset time per resource (core, search, graphql, ...); optional `resource` to pick one"},
+            {"name": "usage_stats", "description": "Report per-tool API call counts, bytes transferred, and rate-limit consumption"},
+            {"name": "continue_response", "description": "Fetch the next part of a truncated result by continuation token (tokens expire after ten minutes)"}
         ]
     }))))
 }
@@ -35,9 +251,61 @@ pub fn config_schema() -> FnResult<Json<serde_json::Value>> {
                 "type": "string",
                 "description": "GitHub personal access token"
             },
+            "api_base_url": {
+                "type": "string",
+                "description": "REST API base URL; for GitHub Enterprise Server the host (e.g. https://ghe.example.com) is enough, /api/v3 is added",
+                "default": endpoint::DEFAULT_API_BASE
+            },
             "default_owner": {
                 "type": "string",
                 "description": "Default repository owner (user or org)"
+            },
+            "commit_convention": {
+                "type": "object",
+                "description": "Commit message rules for check_pr_commits: conventional, types, max_subject_length, require_issue_ref, skip_merges"
+            },
+            "commit_signing": {
+                "type": "object",
+                "description": "Sign commits the plugin creates: {key, name, email} with an unencrypted OpenSSH ed25519 private key registered on GitHub as a signing key; name and email become the committer"
+            },
+            "graphql_queries": {
+                "type": "object",
+                "description": "Named GraphQL queries for run_named_query, each a query string or {query, description}"
+            },
+            "etag_cache": {
+                "type": "boolean",
+                "description": "Cache GET responses by ETag and revalidate with If-None-Match; writes invalidate affected entries",
+                "default": false
+            },
+            "mock_mode": {
+                "type": "boolean",
+                "description": "Serve API calls from fixtures instead of the network (no token needed)",
+                "default": false
+            },
+            "mock_fixtures": {
+                "type": "object",
+                "description": "Mock responses keyed by \"METHOD /path\", each {status, headers, body}"
+            },
+            "log_level": {
+                "type": "string",
+                "enum": ["error", "warn", "info", "debug"],
+                "description": "Plugin log verbosity; debug logs every API request with secrets redacted",
+                "default": "info"
+            },
+            "max_pages": {
+                "type": "integer",
+                "description": "Upper bound on `max_pages` for list tools that walk several pages in one call",
+                "default": paging::DEFAULT_MAX_PAGES
+            },
+            "max_retries": {
+                "type": "integer",
+                "description": "Retries for rate-limited (403/429) responses, waiting per Retry-After / X-RateLimit-Reset or with exponential backoff (0 disables)",
+                "default": ratelimit::DEFAULT_MAX_RETRIES
+            },
+            "max_response_bytes": {
+                "type": "integer",
+                "description": "Maximum serialized size of a tool result; larger results are truncated with a continuation token (0 disables; at least 4096)",
+                "default": limits::DEFAULT_MAX_RESPONSE_BYTES
             }
         },
         "required": ["github_token"]
@@ -47,9 +315,18 @@ pub fn config_schema() -> FnResult<Json<serde_json::Value>> {
 #[plugin_fn]
 pub fn init(Json(input): Json<DataType>) -> FnResult<Json<DataType>> {
     let config = input.get("config").cloned().unwrap_or(DataType::Null);
-    if config.get("github_token").and_then(|t| t.as_str()).is_none() {
+    let mock_mode = config.get("mock_mode").and_then(|v| v.to_json().as_bool()).unwrap_or(false);
+    if config.get("github_token").and_then(|t| t.as_str()).is_none() && !mock_mode {
         return Ok(Json(DataType::from_json(json!({"error": "github_token is required"}))));
     }
+    if let Some(base) = config.get("api_base_url").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        if let Err(e) = endpoint::normalize(base) {
+            return Ok(Json(DataType::from_json(json!({"error": e}))));
+        }
+    }
+    if let Err(e) = signing::validate(&config) {
+        return Ok(Json(DataType::from_json(json!({"error": e}))));
+    }
     magi_pdk::log_info("GitHub plugin initialized");
     Ok(Json(DataType::from_json(json!({"success": true}))))
 }
@@ -68,51 +345,482 @@ pub fn process(Json(input): Json<DataType>) -> FnResult<Json<DataType>> {
         .get("github_token")
         .and_then(|v| v.as_str())
         .unwrap_or("");
+    redact::set_secret(token);
+    logging::configure(&config);
+    endpoint::configure(&config);
+    mock::configure(&config);
+    paging::configure(&config);
+    ratelimit::configure(&config);
+    signing::configure(&config);
+    cache::configure(config.get("etag_cache").and_then(|v| v.to_json().as_bool()).unwrap_or(false));
+    let max_bytes = config
+        .get("max_response_bytes")
+        .and_then(|v| v.to_json().as_u64())
+        .map(|n| match n as usize {
+            0 => 0,
+            n => n.max(limits::MIN_RESPONSE_BYTES),
+        })
+        .unwrap_or(limits::DEFAULT_MAX_RESPONSE_BYTES);
 
-    match tool.as_str() {
+    usage::begin(&tool);
+    let result = match tool.as_str() {
         "list_repos" => list_repos(token, &args),
         "get_repo" => get_repo(token, &args),
         "list_issues" => list_issues(token, &args),
         "create_issue" => create_issue(token, &args),
+        "update_issue" => issues::update_issue(token, &args),
+        "close_issue" => issues::close_issue(token, &args),
+        "add_labels" => issues::add_labels(token, &args),
+        "remove_label" => issues::remove_label(token, &args),
+        "add_assignees" => issues::add_assignees(token, &args),
+        "set_milestone" => issues::set_milestone(token, &args),
         "list_prs" => list_prs(token, &args),
         "get_pr" => get_pr(token, &args),
         "get_file" => get_file(token, &args),
-        "search_code" => search_code(token, &args),
+        "find_definition" => search::find_definition(token, &args),
+        "create_or_update_file" => contents::create_or_update_file(token, &args),
+        "delete_file" => contents::delete_file(token, &args),
+        "search_code" => search::search_code(token, &args),
+        "search_issues" => search::search_issues(token, &args),
+        "search_repos" => search::search_repos(token, &args),
+        "search_users" => search::search_users(token, &args),
+        "render_markdown" => misc::render_markdown(token, &args),
+        "list_gitignore_templates" => misc::list_gitignore_templates(token),
+        "get_gitignore_template" => misc::get_gitignore_template(token, &args),
+        "list_licenses" => misc::list_licenses(token, &args),
+        "get_license" => misc::get_license(token, &args),
+        "get_meta" => misc::get_meta(token, &args),
+        "list_emojis" => misc::list_emojis(token, &args),
+        "parse_diff" => diff::parse_diff(&args),
+        "check_pr_commits" => commit_lint::check_pr_commits(token, &args, &config),
+        "find_stale" => stale::find_stale(token, &args),
+        "summarize_tree" => tree::summarize_tree(token, &args),
+        "create_check_run" => checks::create_check_run(token, &args),
+        "update_check_run" => checks::update_check_run(token, &args),
+        "list_check_suites" => checks::list_check_suites(token, &args),
+        "rerequest_check_suite" => checks::rerequest_check_suite(token, &args),
+        "deployment_history" => deployments::deployment_history(token, &args),
+        "branch_drift" => branches::branch_drift(token, &args),
+        "list_commits" => commits::list_commits(token, &args),
+        "get_commit" => commits::get_commit(token, &args),
+        "compare_commits" => commits::compare_commits(token, &args),
+        "get_file_history" => commits::get_file_history(token, &args),
+        "check_signed_commits" => commits::check_signed_commits(token, &args),
+        "get_interaction_limits" => moderation::get_interaction_limits(token, &args),
+        "set_interaction_limits" => moderation::set_interaction_limits(token, &args),
+        "remove_interaction_limits" => moderation::remove_interaction_limits(token, &args),
+        "list_blocked_users" => moderation::list_blocked_users(token, &args),
+        "block_user" => moderation::block_user(token, &args),
+        "unblock_user" => moderation::unblock_user(token, &args),
+        "get_org_billing" => orgs::get_org_billing(token, &args),
+        "set_repo_visibility" => repo_admin::set_repo_visibility(token, &args),
+        "get_issue_forms" => templates::get_issue_forms(token, &args),
+        "get_pr_templates" => templates::get_pr_templates(token, &args),
+        "create_pr" => pulls::create_pr(token, &args),
+        "update_pr" => pulls::update_pr(token, &args),
+        "merge_pr" => pulls::merge_pr(token, &args),
+        "get_required_status_checks" => protection::get_required_status_checks(token, &args),
+        "update_required_status_checks" => protection::update_required_status_checks(token, &args),
+        "list_tag_protection" => protection::list_tag_protection(token, &args),
+        "create_tag_protection" => protection::create_tag_protection(token, &args),
+        "delete_tag_protection" => protection::delete_tag_protection(token, &args),
+        "suggest_reviewers" => reviewers::suggest_reviewers(token, &args),
+        "list_my_orgs" => orgs::list_my_orgs(token, &args),
+        "get_my_org_membership" => orgs::get_my_org_membership(token, &args),
+        "check_permission" => permissions::check_permission(token, &args),
+        "list_custom_repo_roles" => permissions::list_custom_repo_roles(token, &args),
+        "get_custom_repo_role" => permissions::get_custom_repo_role(token, &args),
+        "add_collaborator" => permissions::add_collaborator(token, &args),
+        "add_team_repo" => permissions::add_team_repo(token, &args),
+        "get_code_scanning_setup" => security::get_code_scanning_setup(token, &args),
+        "update_code_scanning_setup" => security::update_code_scanning_setup(token, &args),
+        "set_security_features" => security::set_security_features(token, &args),
+        "get_run_attempts" => actions::get_run_attempts(token, &args),
+        "cleanup_artifacts" => actions::cleanup_artifacts(token, &args),
+        "get_actions_policy" => actions::get_actions_policy(token, &args),
+        "set_actions_policy" => actions::set_actions_policy(token, &args),
+        "get_oidc_subject" => actions::get_oidc_subject(token, &args),
+        "set_oidc_subject" => actions::set_oidc_subject(token, &args),
+        "list_org_secrets" => secrets::list_org_secrets(token, &args),
+        "set_org_secret" => secrets::set_org_secret(token, &args),
+        "list_org_variables" => secrets::list_org_variables(token, &args),
+        "set_org_variable" => secrets::set_org_variable(token, &args),
+        "get_org_selected_repos" => secrets::get_org_selected_repos(token, &args),
+        "set_org_selected_repos" => secrets::set_org_selected_repos(token, &args),
+        "list_environment_secrets" => secrets::list_environment_secrets(token, &args),
+        "set_environment_secret" => secrets::set_environment_secret(token, &args),
+        "delete_environment_secret" => secrets::delete_environment_secret(token, &args),
+        "list_named_queries" => graphql::list_named_queries(&config),
+        "run_named_query" => graphql::run_named_query(token, &args, &config),
+        "graphql" => graphql::graphql(token, &args),
+        "export_issues" => migrate::export_issues(token, &args),
+        "import_issues" => migrate::import_issues(token, &args),
+        "sync_labels" => labels::sync_labels(token, &args),
+        "org_repos" => orgs::org_repos(token, &args),
+        "snapshot_traffic" => traffic::snapshot_traffic(token, &args),
+        "traffic_history" => traffic::traffic_history(&args),
+        "contributor_activity" => stats::contributor_activity(token, &args),
+        "commit_activity" => stats::commit_activity(token, &args),
+        "code_frequency" => stats::code_frequency(token, &args),
+        "punch_card" => stats::punch_card(token, &args),
+        "participation" => stats::participation(token, &args),
+        "pr_metrics" => stats::pr_metrics(token, &args),
+        "detect_flaky_jobs" => actions::detect_flaky_jobs(token, &args),
+        "validate_workflow" => workflow_lint::validate_workflow(token, &args),
+        "list_dependencies" => dependencies::list_dependencies(token, &args),
+        "find_dependents" => dependencies::find_dependents(token, &args),
+        "list_bypass_requests" => security::list_bypass_requests(token, &args),
+        "review_bypass_request" => security::review_bypass_request(token, &args),
+        "list_my_invitations" => users::list_my_invitations(token, &args),
+        "respond_to_invitation" => users::respond_to_invitation(token, &args),
+        "compare_releases" => releases::compare_releases(token, &args),
+        "release_readiness" => releases::release_readiness(token, &args),
+        "list_branches" => branches::list_branches(token, &args),
+        "get_branch" => branches::get_branch(token, &args),
+        "create_branch" => branches::create_branch(token, &args),
+        "delete_branch" => branches::delete_branch(token, &args),
+        "get_ref" => refs::get_ref(token, &args),
+        "update_ref" => refs::update_ref(token, &args),
+        "cherry_pick_commit" => cherry_pick::cherry_pick_commit(token, &args),
+        "revert_commit" => cherry_pick::revert_commit(token, &args),
+        "backport_pr" => cherry_pick::backport_pr(token, &args),
+        "list_prs_by_path" => pulls::list_prs_by_path(token, &args),
+        "get_pr_files" => pulls::get_pr_files(token, &args),
+        "get_pr_diff" => pulls::get_pr_diff(token, &args),
+        "list_reviews" => pulls::list_reviews(token, &args),
+        "list_review_comments" => pulls::list_review_comments(token, &args),
+        "submit_review" => pulls::submit_review(token, &args),
+        "wait_for" => wait::wait_for(token, &args),
+        "list_hook_deliveries" => webhooks::list_hook_deliveries(token, &args),
+        "get_hook_delivery" => webhooks::get_hook_delivery(token, &args),
+        "redeliver_hook_delivery" => webhooks::redeliver_hook_delivery(token, &args),
+        "preview_change" => diff::preview_change(token, &args),
+        "list_comments" => comments::list_comments(token, &args),
+        "create_comment" => comments::create_comment(token, &args),
+        "update_comment" => comments::update_comment(token, &args),
+        "delete_comment" => comments::delete_comment(token, &args),
+        "add_reaction" => comments::add_reaction(token, &args),
+        "remove_reaction" => comments::remove_reaction(token, &args),
+        "membership_diff" => orgs::membership_diff(token, &args),
+        "list_pat_requests" => pat::list_pat_requests(token, &args),
+        "review_pat_requests" => pat::review_pat_requests(token, &args),
+        "list_pat_grants" => pat::list_pat_grants(token, &args),
+        "list_credential_authorizations" => sso::list_credential_authorizations(token, &args),
+        "revoke_credential_authorization" => sso::revoke_credential_authorization(token, &args),
+        "triage_dependabot_prs" => dependabot::triage_dependabot_prs(token, &args),
+        "pr_overview" => composite::pr_overview(token, &args),
+        "repo_overview" => composite::repo_overview(token, &args),
+        "issue_context" => composite::issue_context(token, &args),
+        "reference_graph" => composite::reference_graph(token, &args),
+        "repo_health" => composite::repo_health(token, &args),
+        "get_combined_status" => composite::get_combined_status(token, &args),
+        "sync_issues" => sync::sync(token, &args, sync::SyncKind::Issues),
+        "sync_prs" => sync::sync(token, &args, sync::SyncKind::Prs),
+        "list_events" => events::list_events(token, &args),
+        "poll_notifications" => notifications::poll_notifications(token, &args),
+        "set_mock_fixtures" => mock::set_mock_fixtures(&args),
+        "get_rate_limit" => ratelimit::get_rate_limit(token, &args),
+        "usage_stats" => usage::usage_stats(&args),
+        "continue_response" => limits::continue_response(&args, max_bytes),
         _ => Ok(Json(DataType::from_json(json!({"error": format!("unknown tool: {tool}")})))),
+    };
+    let mut output = match result {
+        Ok(Json(result)) => errors::normalize(result.to_json()),
+        Err(WithReturnCode(e, _)) => errors::failure(&format!("{e:#}")),
+    };
+    usage::finish(errors::is_error(&output));
+    // HTTP-layer errors can echo request details; never hand the token back.
+    redact::redact_value(&mut output);
+    Ok(Json(DataType::from_json(limits::enforce(output, max_bytes))))
+}
+
+// =============================================================================
+// Argument helpers
+// =============================================================================
+
+fn bool_arg(args: &DataType, key: &str) -> bool {
+    match args.get(key).map(|v| v.to_json()) {
+        Some(serde_json::Value::Bool(b)) => b,
+        Some(serde_json::Value::String(s)) => s == "true",
+        _ => false,
+    }
+}
+
+// Accepts numbers or numeric strings, as callers send both.
+fn u64_arg(args: &DataType, key: &str) -> Option<u64> {
+    match args.get(key).map(|v| v.to_json())? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+// Accepts a JSON array of strings or a comma-separated string.
+fn str_list_arg(args: &DataType, key: &str) -> Vec<String> {
+    match args.get(key).map(|v| v.to_json()) {
+        Some(serde_json::Value::Array(items)) => {
+            items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect()
+        }
+        Some(serde_json::Value::String(s)) => {
+            s.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+        }
+        _ => Vec::new(),
     }
 }
 
+// Target repos for bulk tools: `repos` as "owner/name" entries, else the
+// single `owner` + `repo`.
+fn repos_arg(args: &DataType) -> Vec<(String, String)> {
+    let listed: Vec<(String, String)> = str_list_arg(args, "repos")
+        .iter()
+        .filter_map(|r| r.split_once('/'))
+        .map(|(o, r)| (o.to_string(), r.to_string()))
+        .collect();
+    if !listed.is_empty() {
+        return listed;
+    }
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Vec::new();
+    }
+    vec![(owner.to_string(), repo.to_string())]
+}
+
+// Percent-encodes everything but RFC 3986 unreserved characters.
+fn url_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
 // =============================================================================
 // GitHub API helpers
 // =============================================================================
 
+const JSON_MEDIA_TYPE: &str = "application/vnd.github+json";
+
+// Short names for the media types tools ask for; full types pass through.
+fn media_type(name: &str) -> &str {
+    match name {
+        "raw" => "application/vnd.github.raw+json",
+        "html" => "application/vnd.github.html+json",
+        "text" => "application/vnd.github.text+json",
+        "full" => "application/vnd.github.full+json",
+        "diff" => "application/vnd.github.diff",
+        "patch" => "application/vnd.github.patch",
+        "sha" => "application/vnd.github.sha",
+        "star" => "application/vnd.github.star+json",
+        "" | "json" => JSON_MEDIA_TYPE,
+        other => other,
+    }
+}
+
 fn github_get(token: &str, path: &str) -> Result<serde_json::Value, Error> {
-    let url = if path.starts_with("https://") {
-        path.to_string()
-    } else {
-        format!("https://api.github.com{path}")
-    };
-    let req = HttpRequest::new(&url)
-        .with_header("Authorization", &format!("Bearer {token}"))
-        .with_header("Accept", "application/vnd.github+json")
-        .with_header("User-Agent", "magi-github-plugin/0.1")
-        .with_header("X-GitHub-Api-Version", "2022-11-28");
-    let resp = http::request::<String>(&req, None::<String>)?;
-    serde_json::from_slice(&resp.body()).map_err(|e| Error::msg(format!("JSON parse error: {e}")))
+    github_get_fields(token, path, &Fields::default())
+}
+
+// Like `github_get`, but list items are cut down to `fields` while parsing so
+// large listings never materialize in full.
+fn github_get_fields(token: &str, path: &str, fields: &Fields) -> Result<serde_json::Value, Error> {
+    let resp = github_request(token, "GET", path, None, JSON_MEDIA_TYPE)?;
+    json_body(&resp, fields)
+}
+
+// Fetches `paging.max_pages` pages of `path` from `paging.page` on, merged into
+// one `{items, page_info}` envelope whose `next_page` resumes after the last.
+fn github_list(token: &str, path: &str, paging: &Paging, fields: &Fields) -> Result<serde_json::Value, Error> {
+    let mut page = paging.page;
+    let mut merged: Option<serde_json::Value> = None;
+    let mut fetched = 0;
+    while fetched < paging.max_pages {
+        let resp = github_request(token, "GET", &paging.url(path, page), None, JSON_MEDIA_TYPE)?;
+        // Error bodies end the walk; a failure on the first page is returned
+        // as is. They skip `envelope`, which would take a 422's `errors`
+        // array for the page's items.
+        if resp.status >= 400 {
+            return Ok(match merged {
+                Some(merged) => merged,
+                None => json_body(&resp, fields)?,
+            });
+        }
+        let data = paging::envelope(json_body(&resp, fields)?, resp.header("link"));
+        if data.get("page_info").is_none() {
+            return Ok(merged.unwrap_or(data));
+        }
+        fetched += 1;
+        let next = data.pointer("/page_info/next_page").and_then(|v| v.as_u64());
+        match merged.as_mut() {
+            Some(acc) => paging::append(acc, data),
+            None => merged = Some(data),
+        }
+        match next {
+            Some(next) => page = next,
+            None => break,
+        }
+    }
+    let mut data = merged.unwrap_or_else(|| json!({"items": []}));
+    data["page_info"]["pages_fetched"] = json!(fetched);
+    Ok(data)
+}
+
+// GET a non-JSON representation (raw file, diff, patch, HTML). Error bodies
+// are JSON, so they surface as errors rather than as the requested text.
+fn github_get_text(token: &str, path: &str, accept: &str) -> Result<String, Error> {
+    let resp = github_request(token, "GET", path, None, media_type(accept))?;
+    let text = String::from_utf8_lossy(&resp.body).into_owned();
+    if resp.status >= 400 {
+        return Err(Error::msg(format!("GitHub API returned {}: {text}", resp.status)));
+    }
+    Ok(text)
 }
 
 fn github_post(token: &str, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, Error> {
-    let url = format!("https://api.github.com{path}");
-    let req = HttpRequest::new(&url)
-        .with_method("POST")
-        .with_header("Authorization", &format!("Bearer {token}"))
-        .with_header("Accept", "application/vnd.github+json")
-        .with_header("User-Agent", "magi-github-plugin/0.1")
-        .with_header("X-GitHub-Api-Version", "2022-11-28")
-        .with_header("Content-Type", "application/json");
-    let body_str = serde_json::to_string(body)?;
-    let resp = http::request::<String>(&req, Some(body_str))?;
-    serde_json::from_slice(&resp.body()).map_err(|e| Error::msg(format!("JSON parse error: {e}")))
+    let resp = github_request(token, "POST", path, Some(body), JSON_MEDIA_TYPE)?;
+    json_body(&resp, &Fields::default())
+}
+
+fn github_patch(token: &str, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, Error> {
+    let resp = github_request(token, "PATCH", path, Some(body), JSON_MEDIA_TYPE)?;
+    json_body(&resp, &Fields::default())
+}
+
+fn github_put(token: &str, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, Error> {
+    let resp = github_request(token, "PUT", path, Some(body), JSON_MEDIA_TYPE)?;
+    json_body(&resp, &Fields::default())
+}
+
+fn github_delete(token: &str, path: &str) -> Result<serde_json::Value, Error> {
+    let resp = github_request(token, "DELETE", path, None, JSON_MEDIA_TYPE)?;
+    json_body(&resp, &Fields::default())
+}
+
+// Many endpoints answer 201/202/204 with no body. Error bodies are kept whole
+// (a `fields` projection would drop `message`) and tagged with the numeric
+// HTTP status, replacing the string `status` GitHub puts in some of them.
+fn json_body(resp: &Response, fields: &Fields) -> Result<serde_json::Value, Error> {
+    if resp.body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(json!({"success": resp.status < 400, "status": resp.status}));
+    }
+    if resp.status < 400 {
+        return stream::parse(&resp.body, fields).map_err(|e| Error::msg(format!("JSON parse error: {e}")));
+    }
+    let mut data = stream::parse(&resp.body, &Fields::default())
+        .unwrap_or_else(|_| json!({"message": String::from_utf8_lossy(&resp.body)}));
+    if let serde_json::Value::Object(obj) = &mut data {
+        obj.insert("status".into(), json!(resp.status));
+        ratelimit::annotate(resp, obj);
+    }
+    Ok(data)
+}
+
+// For endpoints that answer with text (e.g. rendered HTML) instead of JSON.
+fn github_post_text(token: &str, path: &str, body: &serde_json::Value) -> Result<String, Error> {
+    let resp = github_request(token, "POST", path, Some(body), JSON_MEDIA_TYPE)?;
+    String::from_utf8(resp.body).map_err(|e| Error::msg(format!("invalid UTF-8 in response: {e}")))
+}
+
+struct Response {
+    status: u16,
+    // Header names are lowercased.
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+fn github_request(
+    token: &str,
+    method: &str,
+    path: &str,
+    body: Option<&serde_json::Value>,
+    accept: &str,
+) -> Result<Response, Error> {
+    github_request_with(token, method, path, body, accept, &[])
+}
+
+// `extra` headers are added as given. Callers sending their own conditional
+// headers want to see a 304 themselves, so those requests skip the ETag cache.
+fn github_request_with(
+    token: &str,
+    method: &str,
+    path: &str,
+    body: Option<&serde_json::Value>,
+    accept: &str,
+    extra: &[(&'static str, String)],
+) -> Result<Response, Error> {
+    let url = endpoint::url(path);
+    let mut headers = vec![
+        ("Authorization", format!("Bearer {token}")),
+        ("Accept", accept.to_string()),
+        ("User-Agent", "magi-github-plugin/0.1".to_string()),
+        ("X-GitHub-Api-Version", "2022-11-28".to_string()),
+    ];
+    headers.extend(extra.iter().cloned());
+    let body_str = match body {
+        Some(body) => {
+            headers.push(("Content-Type", "application/json".to_string()));
+            Some(serde_json::to_string(body)?)
+        }
+        None => None,
+    };
+    // The same path yields different bodies per media type.
+    let cache_key = if accept == JSON_MEDIA_TYPE { path.to_string() } else { format!("{path}#{accept}") };
+    let cacheable = method == "GET" && extra.is_empty();
+    let cached = if cacheable { cache::lookup(&cache_key) } else { None };
+    if let Some(entry) = &cached {
+        headers.push(("If-None-Match", entry.etag.clone()));
+    }
+    let req = headers
+        .iter()
+        .fold(HttpRequest::new(&url).with_method(method), |req, (k, v)| req.with_header(*k, v));
+    let sent = body_str.as_ref().map_or(0, |b| b.len());
+    let mut attempt = 0;
+    let resp = loop {
+        let started = std::time::Instant::now();
+        let resp = if mock::enabled() {
+            mock::respond(method, path)
+        } else {
+            let raw = http::request::<String>(&req, body_str.clone())?;
+            Response {
+                status: raw.status_code(),
+                headers: raw.headers().iter().map(|(k, v)| (k.to_ascii_lowercase(), v.clone())).collect(),
+                body: raw.body(),
+            }
+        };
+        logging::request(method, &url, &headers, &resp, started.elapsed());
+        usage::record_request(sent, &resp);
+        let Some(wait) = ratelimit::backoff(&resp, attempt) else {
+            break resp;
+        };
+        attempt += 1;
+        logging::warn(&format!("{method} {path} rate limited; retry {attempt} in {}s", wait.as_secs()));
+        std::thread::sleep(wait);
+    };
+
+    if method != "GET" {
+        cache::invalidate(path);
+        return Ok(resp);
+    }
+    if !cacheable {
+        return Ok(resp);
+    }
+    match cached {
+        Some(entry) if resp.status == 304 => Ok(entry.revalidated(resp.headers)),
+        _ => {
+            cache::store(&cache_key, &resp);
+            Ok(resp)
+        }
+    }
 }
 
 // =============================================================================
@@ -122,11 +830,11 @@ fn github_post(token: &str, path: &str, body: &serde_json::Value) -> Result<serd
 fn list_repos(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
     let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("").to_string();
     let path = if owner.is_empty() {
-        "/user/repos?per_page=30&sort=updated".to_string()
+        "/user/repos?sort=updated".to_string()
     } else {
-        format!("/users/{owner}/repos?per_page=30&sort=updated")
+        format!("/users/{owner}/repos?sort=updated")
     };
-    let data = github_get(token, &path)?;
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
     Ok(Json(DataType::from_json(data)))
 }
 
@@ -147,7 +855,8 @@ fn list_issues(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
     if owner.is_empty() || repo.is_empty() {
         return Ok(Json(DataType::from_json(json!({"error": "owner and repo are required"}))));
     }
-    let data = github_get(token, &format!("/repos/{owner}/{repo}/issues?state={state}&per_page=30"))?;
+    let path = format!("/repos/{owner}/{repo}/issues?state={state}");
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
     Ok(Json(DataType::from_json(data)))
 }
 
@@ -171,7 +880,8 @@ fn list_prs(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
     if owner.is_empty() || repo.is_empty() {
         return Ok(Json(DataType::from_json(json!({"error": "owner and repo are required"}))));
     }
-    let data = github_get(token, &format!("/repos/{owner}/{repo}/pulls?state={state}&per_page=30"))?;
+    let path = format!("/repos/{owner}/{repo}/pulls?state={state}");
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
     Ok(Json(DataType::from_json(data)))
 }
 
@@ -187,8 +897,14 @@ fn get_pr(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
     }
     // Strip quotes if the number was a string
     let num = number.trim_matches('"');
-    let data = github_get(token, &format!("/repos/{owner}/{repo}/pulls/{num}"))?;
-    Ok(Json(DataType::from_json(data)))
+    let path = format!("/repos/{owner}/{repo}/pulls/{num}");
+    match args.get("format").and_then(|v| v.as_str()).unwrap_or("json") {
+        media @ ("diff" | "patch") => {
+            let text = github_get_text(token, &path, media)?;
+            Ok(Json(DataType::from_json(json!({"format": media, "content": text}))))
+        }
+        _ => Ok(Json(DataType::from_json(github_get(token, &path)?))),
+    }
 }
 
 fn get_file(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
@@ -199,16 +915,23 @@ fn get_file(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
     if owner.is_empty() || repo.is_empty() || path.is_empty() {
         return Ok(Json(DataType::from_json(json!({"error": "owner, repo, and path are required"}))));
     }
-    let data = github_get(token, &format!("/repos/{owner}/{repo}/contents/{path}?ref={branch}"))?;
-    Ok(Json(DataType::from_json(data)))
-}
-
-fn search_code(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
-    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
-    if query.is_empty() {
-        return Ok(Json(DataType::from_json(json!({"error": "query is required"}))));
+    let path = format!("/repos/{owner}/{repo}/contents/{path}?ref={branch}");
+    match args.get("format").and_then(|v| v.as_str()).unwrap_or("json") {
+        media @ ("raw" | "html") => {
+            let text = github_get_text(token, &path, media)?;
+            Ok(Json(DataType::from_json(json!({"format": media, "content": text}))))
+        }
+        _ => {
+            let data = github_get(token, &path)?;
+            // Directory listings, symlinks, submodules, and errors pass through.
+            if data.get("type").and_then(|v| v.as_str()) != Some("file") {
+                return Ok(Json(DataType::from_json(data)));
+            }
+            let Some((bytes, source)) = contents::file_bytes(token, owner, repo, &path, &data)? else {
+                return Ok(Json(DataType::from_json(json!({"error": "could not download file content"}))));
+            };
+            let file = contents::envelope(&data, &bytes, source, bool_arg(args, "as_base64"));
+            Ok(Json(DataType::from_json(file)))
+        }
     }
-    let encoded = query.replace(' ', "+");
-    let data = github_get(token, &format!("/search/code?q={encoded}&per_page=20"))?;
-    Ok(Json(DataType::from_json(data)))
 }
@@ -0,0 +1,207 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::{state, time};
+
+pub(crate) const DEFAULT_MAX_RESPONSE_BYTES: usize = 256 * 1024;
+// Smaller limits leave no room for content once the envelope is paid for.
+pub(crate) const MIN_RESPONSE_BYTES: usize = 4 * 1024;
+
+// Room left for the `truncated`/`remaining`/`continuation_token` fields.
+const ENVELOPE_OVERHEAD: usize = 256;
+
+const TOKEN_COUNTER: &str = "continuation/next";
+const TOKEN_INDEX: &str = "continuation/index";
+const TOKEN_TTL_SECS: i64 = 600;
+
+// =============================================================================
+// Response size enforcement
+// =============================================================================
+
+/// Caps a tool result at `max_bytes` of serialized JSON. Arrays (top-level or
+/// the largest array field of an object) are cut at an item boundary; anything
+/// else is cut as text. The remainder is parked in plugin state under a
+/// continuation token for `continue_response`, valid for ten minutes.
+pub(crate) fn enforce(data: Value, max_bytes: usize) -> Value {
+    if max_bytes == 0 || serialized_len(&data) <= max_bytes {
+        return data;
+    }
+    let budget = max_bytes.saturating_sub(ENVELOPE_OVERHEAD);
+    match data {
+        Value::Array(items) => match split_items(&items, budget) {
+            Some(kept) => items_page(Map::new(), "items", items, kept),
+            None => text_page(serde_json::to_string(&items).unwrap_or_default(), budget),
+        },
+        Value::Object(mut obj) => {
+            let Some(field) = largest_array_field(&obj) else {
+                return text_page(Value::Object(obj).to_string(), budget);
+            };
+            let items = match obj.remove(&field) {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            let rest_len = serialized_len(&Value::Object(obj.clone()));
+            match split_items(&items, budget.saturating_sub(rest_len + field.len() + 4)) {
+                Some(kept) => items_page(obj, &field, items, kept),
+                None => {
+                    obj.insert(field, Value::Array(items));
+                    text_page(Value::Object(obj).to_string(), budget)
+                }
+            }
+        }
+        other => text_page(other.to_string(), budget),
+    }
+}
+
+pub(crate) fn continue_response(args: &DataType, max_bytes: usize) -> FnResult<Json<DataType>> {
+    let token = args.get("token").and_then(|v| v.as_str()).unwrap_or("");
+    if token.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "token is required"}))));
+    }
+    let key = format!("continuation/{token}");
+    let Some(stored) = state::load(&key) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("unknown or expired continuation token: {token}")}),
+        )));
+    };
+    state::remove(&key)?;
+    if stored.get("expires_at").and_then(|v| v.as_i64()).unwrap_or(0) < time::now() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("unknown or expired continuation token: {token}")}),
+        )));
+    }
+
+    let page = match stored {
+        Value::Object(mut obj) if obj.contains_key("text") => {
+            let text = obj
+                .remove("text")
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            text_page(text, max_bytes.saturating_sub(ENVELOPE_OVERHEAD))
+        }
+        // Re-wrapped under the field the items were cut from (`files`,
+        // `workflow_runs`, ...).
+        Value::Object(mut obj) => {
+            let field = obj
+                .remove("field")
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_else(|| "items".to_string());
+            let items = obj.remove("items").unwrap_or(json!([]));
+            let mut page = Map::new();
+            page.insert(field, items);
+            enforce(Value::Object(page), max_bytes)
+        }
+        _ => json!({"error": "corrupt continuation state"}),
+    };
+    Ok(Json(DataType::from_json(page)))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn serialized_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map(|b| b.len()).unwrap_or(0)
+}
+
+fn largest_array_field(obj: &Map<String, Value>) -> Option<String> {
+    obj.iter()
+        .filter(|(_, v)| v.is_array())
+        .max_by_key(|(_, v)| serialized_len(v))
+        .map(|(k, _)| k.clone())
+}
+
+// Number of leading items that fit in `budget`, or None if not even one does.
+fn split_items(items: &[Value], budget: usize) -> Option<usize> {
+    let mut used = 2;
+    let mut kept = 0;
+    for item in items {
+        let len = serialized_len(item) + 1;
+        if used + len > budget {
+            break;
+        }
+        used += len;
+        kept += 1;
+    }
+    (kept > 0).then_some(kept)
+}
+
+fn items_page(mut obj: Map<String, Value>, field: &str, mut items: Vec<Value>, kept: usize) -> Value {
+    let rest = items.split_off(kept);
+    obj.insert(field.to_string(), Value::Array(items));
+    if !rest.is_empty() {
+        obj.insert("truncated".into(), json!(true));
+        obj.insert("remaining".into(), json!(rest.len()));
+        obj.insert(
+            "continuation_token".into(),
+            json!(park(json!({"field": field, "items": rest}))),
+        );
+    }
+    Value::Object(obj)
+}
+
+fn text_page(text: String, budget: usize) -> Value {
+    let mut used = 2;
+    let mut cut = text.len();
+    for (i, c) in text.char_indices() {
+        let len = escaped_len(c);
+        if used + len > budget {
+            cut = i;
+            break;
+        }
+        used += len;
+    }
+    // Always move forward by at least one char, so a continuation chain
+    // ends whatever the budget.
+    if cut == 0 {
+        cut = text.chars().next().map_or(0, char::len_utf8);
+    }
+    if cut == text.len() {
+        return json!({"text": text});
+    }
+    let rest = text[cut..].to_string();
+    json!({
+        "text": &text[..cut],
+        "truncated": true,
+        "remaining_bytes": rest.len(),
+        "continuation_token": park(json!({"text": rest}))
+    })
+}
+
+// Length of a char once escaped inside a JSON string.
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+// Parks `remainder` for TOKEN_TTL_SECS. Tokens are indexed with their
+// expiry so each park also drops remainders nobody came back for.
+fn park(mut remainder: Value) -> String {
+    let now = time::now();
+    let token = format!("ct-{}", state::next_id(TOKEN_COUNTER));
+    remainder["expires_at"] = json!(now + TOKEN_TTL_SECS);
+    if let Err(e) = state::save(&format!("continuation/{token}"), &remainder) {
+        magi_pdk::log_info(&format!("failed to store continuation {token}: {e}"));
+    }
+
+    let mut index = match state::load(TOKEN_INDEX) {
+        Some(Value::Array(index)) => index,
+        _ => Vec::new(),
+    };
+    index.retain(|entry| {
+        let live = entry.get("expires_at").and_then(|v| v.as_i64()).unwrap_or(0) >= now;
+        if !live {
+            if let Some(old) = entry.get("token").and_then(|v| v.as_str()) {
+                let _ = state::remove(&format!("continuation/{old}"));
+            }
+        }
+        live
+    });
+    index.push(json!({"token": token, "expires_at": now + TOKEN_TTL_SECS}));
+    let _ = state::save(TOKEN_INDEX, &Value::Array(index));
+    token
+}
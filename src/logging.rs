@@ -0,0 +1,90 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use magi_pdk::DataType;
+
+use crate::redact::redact;
+use crate::Response;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+thread_local! {
+    static LEVEL: Cell<Level> = const { Cell::new(Level::Info) };
+}
+
+// =============================================================================
+// Request logging
+// =============================================================================
+
+pub(crate) fn configure(config: &DataType) {
+    let level = match config.get("log_level").and_then(|v| v.as_str()).unwrap_or("info") {
+        "error" => Level::Error,
+        "warn" => Level::Warn,
+        "debug" => Level::Debug,
+        _ => Level::Info,
+    };
+    LEVEL.with(|l| l.set(level));
+}
+
+fn enabled(level: Level) -> bool {
+    LEVEL.with(|l| l.get()) >= level
+}
+
+pub(crate) fn debug(message: &str) {
+    if enabled(Level::Debug) {
+        magi_pdk::log_info(&format!("[debug] {}", redact(message)));
+    }
+}
+
+pub(crate) fn warn(message: &str) {
+    if enabled(Level::Warn) {
+        magi_pdk::log_info(&format!("[warn] {}", redact(message)));
+    }
+}
+
+/// Logs one API round trip at debug level. Header values pass through
+/// redaction, and Authorization is never printed.
+pub(crate) fn request(method: &str, url: &str, headers: &[(&str, String)], resp: &Response, elapsed: Duration) {
+    if !enabled(Level::Debug) {
+        return;
+    }
+    let sent: Vec<String> = headers
+        .iter()
+        .map(|(k, v)| {
+            if k.eq_ignore_ascii_case("authorization") {
+                format!("{k}: [REDACTED]")
+            } else {
+                format!("{k}: {v}")
+            }
+        })
+        .collect();
+    let rate_limit: Vec<String> = [
+        "x-ratelimit-limit",
+        "x-ratelimit-remaining",
+        "x-ratelimit-used",
+        "x-ratelimit-reset",
+        "x-ratelimit-resource",
+        "retry-after",
+    ]
+    .iter()
+    .filter_map(|h| resp.header(h).map(|v| format!("{h}={v}")))
+    .collect();
+    debug(&format!(
+        "{method} {url} -> {} in {}ms ({} bytes) | request headers: {} | rate limit: {}",
+        resp.status,
+        elapsed.as_millis(),
+        resp.body.len(),
+        sent.join(", "),
+        if rate_limit.is_empty() {
+            "n/a".to_string()
+        } else {
+            rate_limit.join(" ")
+        }
+    ));
+}
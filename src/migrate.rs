@@ -0,0 +1,290 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_all;
+use crate::paging::Paging;
+use crate::{bool_arg, github_get, github_patch, github_post, u64_arg};
+
+const DEFAULT_PAGES_PER_CALL: u64 = 3;
+const DEFAULT_IMPORT_LIMIT: u64 = 50;
+// GitHub asks for a pause between content-creating requests to stay clear of
+// secondary rate limits.
+const DEFAULT_THROTTLE_MS: u64 = 1000;
+const CSV_COLUMNS: &[&str] = &[
+    "number",
+    "title",
+    "state",
+    "state_reason",
+    "author",
+    "labels",
+    "assignees",
+    "milestone",
+    "created_at",
+    "updated_at",
+    "closed_at",
+    "comments",
+    "body",
+];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn logins(items: Option<&Value>, key: &str) -> Vec<String> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|i| i.get(key).and_then(|v| v.as_str()).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn flatten(issue: &Value, comments: Option<Vec<Value>>) -> Value {
+    let mut record = json!({
+        "number": issue.get("number"),
+        "title": issue.get("title"),
+        "state": issue.get("state"),
+        "state_reason": issue.get("state_reason"),
+        "author": issue.pointer("/user/login"),
+        "labels": logins(issue.get("labels"), "name"),
+        "assignees": logins(issue.get("assignees"), "login"),
+        "milestone": issue.pointer("/milestone/title"),
+        "created_at": issue.get("created_at"),
+        "updated_at": issue.get("updated_at"),
+        "closed_at": issue.get("closed_at"),
+        "comments": issue.get("comments"),
+        "body": issue.get("body")
+    });
+    if let Some(comments) = comments {
+        let comments: Vec<Value> = comments
+            .iter()
+            .map(|c| {
+                json!({
+                    "author": c.pointer("/user/login"),
+                    "created_at": c.get("created_at"),
+                    "body": c.get("body")
+                })
+            })
+            .collect();
+        record["comment_list"] = json!(comments);
+    }
+    record
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(Value::is_string) => {
+            items.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(";")
+        }
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn csv_row(record: &Value, columns: &[&str]) -> String {
+    columns
+        .iter()
+        .map(|c| csv_field(&record[*c]))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Builds the create payload for one import definition, or says what is wrong.
+fn issue_payload(def: &Value) -> Result<Value, String> {
+    let title = def.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    if title.is_empty() {
+        return Err("title is required".into());
+    }
+    let mut payload = json!({"title": title, "body": def.get("body").and_then(|v| v.as_str()).unwrap_or("")});
+    for key in ["labels", "assignees"] {
+        match def.get(key) {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(items)) if items.iter().all(Value::is_string) => payload[key] = json!(items),
+            Some(_) => return Err(format!("{key} must be an array of strings")),
+        }
+    }
+    if let Some(milestone) = def.get("milestone").and_then(|v| v.as_u64()) {
+        payload["milestone"] = json!(milestone);
+    }
+    Ok(payload)
+}
+
+fn wants_closed(def: &Value) -> bool {
+    def.get("closed").and_then(|v| v.as_bool()).unwrap_or(false)
+        || def.get("state").and_then(|v| v.as_str()) == Some("closed")
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Exports issues oldest first as NDJSON or CSV, `pages_per_call` pages of 100
+/// at a time. Creation order keeps pages stable while new issues arrive, so
+/// passing back `next_cursor` resumes exactly where the last chunk ended.
+pub(crate) fn export_issues(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("ndjson");
+    if format != "ndjson" && format != "csv" {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "format must be ndjson or csv"}),
+        )));
+    }
+    let state = args.get("state").and_then(|v| v.as_str()).unwrap_or("all");
+    let with_comments = bool_arg(args, "include_comments");
+    let with_prs = bool_arg(args, "include_prs");
+    let pages = u64_arg(args, "pages_per_call")
+        .unwrap_or(DEFAULT_PAGES_PER_CALL)
+        .clamp(1, Paging::all().max_pages);
+    let start = u64_arg(args, "cursor").unwrap_or(1).max(1);
+
+    let mut records = Vec::new();
+    let mut next = Some(start);
+    for page in start..start + pages {
+        let path =
+            format!("/repos/{owner}/{repo}/issues?state={state}&sort=created&direction=asc&per_page=100&page={page}");
+        let batch = match github_get(token, &path)? {
+            Value::Array(batch) => batch,
+            other => return Ok(Json(DataType::from_json(other))),
+        };
+        let full = batch.len() == 100;
+        for issue in batch.iter().filter(|i| with_prs || i.get("pull_request").is_none()) {
+            let count = issue.get("comments").and_then(|c| c.as_u64()).unwrap_or(0);
+            let number = issue.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            let comments = match (with_comments, count) {
+                (false, _) => None,
+                (true, 0) => Some(Vec::new()),
+                (true, _) => match get_all(token, &format!("/repos/{owner}/{repo}/issues/{number}/comments")) {
+                    Ok(listing) => Some(listing.items),
+                    Err(error) => return Ok(Json(DataType::from_json(error))),
+                },
+            };
+            records.push(flatten(issue, comments));
+        }
+        next = full.then_some(page + 1);
+        if !full {
+            break;
+        }
+    }
+
+    let chunk = if format == "csv" {
+        let mut columns = CSV_COLUMNS.to_vec();
+        if with_comments {
+            columns.push("comment_list");
+        }
+        let mut lines = Vec::with_capacity(records.len() + 1);
+        // The header only opens the first chunk so chunks concatenate cleanly.
+        if start == 1 {
+            lines.push(columns.join(","));
+        }
+        lines.extend(records.iter().map(|r| csv_row(r, &columns)));
+        lines.join("\n")
+    } else {
+        records.iter().map(Value::to_string).collect::<Vec<_>>().join("\n")
+    };
+
+    Ok(Json(DataType::from_json(json!({
+        "format": format,
+        "count": records.len(),
+        "chunk": chunk,
+        "next_cursor": next,
+        "done": next.is_none()
+    }))))
+}
+
+/// Creates issues from `issues` definitions (title, body, labels, assignees,
+/// milestone, closed/state, state_reason), pausing `throttle_ms` between
+/// creations. At most `limit` are handled per call; `next_index` resumes a
+/// partial import. Every item gets its own result, so one bad definition
+/// never aborts the batch.
+pub(crate) fn import_issues(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(Value::Array(defs)) = args.get("issues").map(|v| v.to_json()) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "issues must be an array of issue definitions"}),
+        )));
+    };
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let start = u64_arg(args, "start_index").unwrap_or(0) as usize;
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_IMPORT_LIMIT) as usize;
+    let throttle = std::time::Duration::from_millis(u64_arg(args, "throttle_ms").unwrap_or(DEFAULT_THROTTLE_MS));
+    let dry_run = bool_arg(args, "dry_run");
+
+    let mut results = Vec::new();
+    let mut created = 0;
+    let end = defs.len().min(start.saturating_add(limit));
+    for (index, def) in defs.iter().enumerate().take(end).skip(start) {
+        let payload = match issue_payload(def) {
+            Ok(payload) => payload,
+            Err(e) => {
+                results.push(json!({"index": index, "ok": false, "error": e}));
+                continue;
+            }
+        };
+        if dry_run {
+            results.push(json!({"index": index, "ok": true, "title": payload["title"]}));
+            continue;
+        }
+        if created > 0 {
+            std::thread::sleep(throttle);
+        }
+        let issue = match github_post(token, &format!("/repos/{owner}/{repo}/issues"), &payload) {
+            Ok(issue) if issue.get("number").is_some() => issue,
+            Ok(other) => {
+                results.push(json!({"index": index, "ok": false, "error": other.get("message")}));
+                continue;
+            }
+            Err(e) => {
+                results.push(json!({"index": index, "ok": false, "error": e.to_string()}));
+                continue;
+            }
+        };
+        created += 1;
+        let number = issue.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+        let mut result = json!({"index": index, "ok": true, "number": number, "url": issue.get("html_url")});
+        if wants_closed(def) {
+            let reason = def.get("state_reason").and_then(|v| v.as_str()).unwrap_or("completed");
+            let closed = github_patch(
+                token,
+                &format!("/repos/{owner}/{repo}/issues/{number}"),
+                &json!({"state": "closed", "state_reason": reason}),
+            );
+            let ok = matches!(&closed, Ok(c) if c.get("state").and_then(|s| s.as_str()) == Some("closed"));
+            result["closed"] = json!(ok);
+            if !ok {
+                result["ok"] = json!(false);
+                result["error"] = json!("created but could not be closed");
+            }
+        }
+        results.push(result);
+    }
+
+    let failed = results.iter().filter(|r| r["ok"] != true).count();
+    Ok(Json(DataType::from_json(json!({
+        "dry_run": dry_run,
+        "processed": results.len(),
+        "created": created,
+        "failed": failed,
+        "next_index": (end < defs.len()).then_some(end),
+        "results": results
+    }))))
+}
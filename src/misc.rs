@@ -0,0 +1,121 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::stream::Fields;
+use crate::{bool_arg, github_get, github_list, github_post_text, u64_arg};
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn render_markdown(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let text = args.get("text").and_then(|v| v.as_str()).unwrap_or("");
+    if text.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "text is required"}))));
+    }
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    // Plain `markdown` mode ignores context; default to GFM so issue refs,
+    // mentions, and task lists resolve the way they will on GitHub.
+    let mode = args.get("mode").and_then(|v| v.as_str()).unwrap_or("gfm");
+    let mut body = json!({"text": text, "mode": mode});
+    if !owner.is_empty() && !repo.is_empty() {
+        body["context"] = json!(format!("{owner}/{repo}"));
+    }
+    let html = github_post_text(token, "/markdown", &body)?;
+    Ok(Json(DataType::from_json(json!({"html": html}))))
+}
+
+pub(crate) fn list_gitignore_templates(token: &str) -> FnResult<Json<DataType>> {
+    let data = github_get(token, "/gitignore/templates")?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_gitignore_template(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    if name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "name is required"}))));
+    }
+    let data = github_get(token, &format!("/gitignore/templates/{name}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn list_licenses(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let featured = if bool_arg(args, "featured") {
+        "&featured=true"
+    } else {
+        ""
+    };
+    let data = github_get(token, &format!("/licenses?per_page=100{featured}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_license(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let key = args.get("license").and_then(|v| v.as_str()).unwrap_or("");
+    if key.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "license is required"}))));
+    }
+    let data = github_get(token, &format!("/licenses/{key}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_meta(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let data = github_get(token, "/meta")?;
+    // The full payload is large (hundreds of CIDRs); `sections` narrows it to
+    // e.g. ["hooks", "actions", "ssh_key_fingerprints", "ssh_keys"].
+    let sections: Vec<String> = match args.get("sections").map(|v| v.to_json()) {
+        Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        Some(Value::String(s)) => s.split(',').map(|p| p.trim().to_string()).collect(),
+        _ => Vec::new(),
+    };
+    if sections.is_empty() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    let mut out = json!({});
+    for section in &sections {
+        out[section.as_str()] = data.get(section).cloned().unwrap_or(Value::Null);
+    }
+    Ok(Json(DataType::from_json(out)))
+}
+
+pub(crate) fn list_emojis(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let data = github_get(token, "/emojis")?;
+    let Value::Object(emojis) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
+    let limit = u64_arg(args, "limit").unwrap_or(50) as usize;
+    let mut matches: Vec<(u8, &String, &Value)> = emojis
+        .iter()
+        .filter_map(|(name, url)| fuzzy_rank(&query, name).map(|rank| (rank, name, url)))
+        .collect();
+    matches.sort_by(|a, b| (a.0, a.1.len(), a.1).cmp(&(b.0, b.1.len(), b.1)));
+    let total = matches.len();
+    let items: Vec<Value> = matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, name, url)| json!({"name": name, "shortcode": format!(":{name}:"), "url": url}))
+        .collect();
+    Ok(Json(DataType::from_json(json!({"total": total, "emojis": items}))))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Lower is better: exact, prefix, substring, then in-order subsequence
+// (so "thup" still finds "thumbsup"). None means no match at all.
+fn fuzzy_rank(query: &str, name: &str) -> Option<u8> {
+    if query.is_empty() || name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    if name.contains(query) {
+        return Some(2);
+    }
+    let mut chars = name.chars();
+    query.chars().all(|q| chars.any(|c| c == q)).then_some(3)
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::{endpoint, state, Response};
+
+const FIXTURES_KEY: &str = "mock/fixtures";
+
+thread_local! {
+    // Some(fixtures from config) while mock mode is on.
+    static MOCK: RefCell<Option<Value>> = const { RefCell::new(None) };
+}
+
+// =============================================================================
+// Mock HTTP mode
+// =============================================================================
+
+pub(crate) fn configure(config: &DataType) {
+    let enabled = config
+        .get("mock_mode")
+        .and_then(|v| v.to_json().as_bool())
+        .unwrap_or(false);
+    let fixtures = config.get("mock_fixtures").map(|v| v.to_json()).unwrap_or(Value::Null);
+    MOCK.with(|m| *m.borrow_mut() = enabled.then_some(fixtures));
+}
+
+pub(crate) fn enabled() -> bool {
+    MOCK.with(|m| m.borrow().is_some())
+}
+
+/// Answers a request from fixtures. Fixtures are keyed by `"METHOD /path"`,
+/// tried first with the query string and then without; config fixtures win
+/// over ones stored with `set_mock_fixtures`. Each fixture is
+/// `{status, headers, body}` (or `body_text` for non-JSON payloads).
+/// Unmatched requests get a GitHub-style 404.
+pub(crate) fn respond(method: &str, path: &str) -> Response {
+    let path = endpoint::strip(path);
+    let bare = path.split('?').next().unwrap_or(path);
+    let keys = [format!("{method} {path}"), format!("{method} {bare}")];
+
+    let from_config = MOCK.with(|m| m.borrow().clone()).unwrap_or(Value::Null);
+    let stored = state::load(FIXTURES_KEY).unwrap_or(Value::Null);
+    let fixture = keys
+        .iter()
+        .find_map(|k| from_config.get(k).or_else(|| stored.get(k)).cloned());
+
+    let Some(fixture) = fixture else {
+        let message = format!("no mock fixture for {method} {path}");
+        return Response {
+            status: 404,
+            headers: BTreeMap::new(),
+            body: json!({"message": message, "documentation_url": "mock"})
+                .to_string()
+                .into_bytes(),
+        };
+    };
+    let headers = fixture
+        .get("headers")
+        .and_then(|h| h.as_object())
+        .map(|h| {
+            h.iter()
+                .filter_map(|(k, v)| Some((k.to_ascii_lowercase(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = match fixture.get("body_text").and_then(|v| v.as_str()) {
+        Some(text) => text.as_bytes().to_vec(),
+        None => fixture.get("body").unwrap_or(&Value::Null).to_string().into_bytes(),
+    };
+    Response {
+        status: fixture.get("status").and_then(|v| v.as_u64()).unwrap_or(200) as u16,
+        headers,
+        body,
+    }
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+pub(crate) fn set_mock_fixtures(args: &DataType) -> FnResult<Json<DataType>> {
+    let fixtures = args.get("fixtures").map(|v| v.to_json()).unwrap_or(Value::Null);
+    let Some(map) = fixtures.as_object() else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "fixtures must be an object keyed by \"METHOD /path\""}),
+        )));
+    };
+    let count = map.len();
+    state::save(FIXTURES_KEY, &fixtures)?;
+    Ok(Json(DataType::from_json(json!({"success": true, "fixtures": count}))))
+}
@@ -0,0 +1,110 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::json;
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_get, github_list, github_put};
+
+const LIMITS: &[&str] = &["existing_users", "contributors_only", "collaborators_only"];
+const EXPIRIES: &[&str] = &["one_day", "three_days", "one_week", "one_month", "six_months"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Interaction limits live on either a repo (owner + repo) or an org.
+fn limits_path(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if !owner.is_empty() && !repo.is_empty() {
+        Some(format!("/repos/{owner}/{repo}/interaction-limits"))
+    } else if !org.is_empty() {
+        Some(format!("/orgs/{org}/interaction-limits"))
+    } else {
+        None
+    }
+}
+
+// Blocks are the token user's own, or an org's when `org` is given.
+fn blocks_path(args: &DataType) -> String {
+    match args.get("org").and_then(|v| v.as_str()).filter(|o| !o.is_empty()) {
+        Some(org) => format!("/orgs/{org}/blocks"),
+        None => "/user/blocks".to_string(),
+    }
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn get_interaction_limits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = limits_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let data = github_get(token, &path)?;
+    // An empty body means no limit is in effect.
+    if data.get("limit").is_none() && data.get("message").is_none() {
+        return Ok(Json(DataType::from_json(json!({"limit": null}))));
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn set_interaction_limits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = limits_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let limit = args.get("limit").and_then(|v| v.as_str()).unwrap_or("");
+    let expiry = args.get("expiry").and_then(|v| v.as_str()).unwrap_or("one_day");
+    if !LIMITS.contains(&limit) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("limit must be one of: {}", LIMITS.join(", "))}),
+        )));
+    }
+    if !EXPIRIES.contains(&expiry) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("expiry must be one of: {}", EXPIRIES.join(", "))}),
+        )));
+    }
+    let data = github_put(token, &path, &json!({"limit": limit, "expiry": expiry}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn remove_interaction_limits(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = limits_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let data = github_delete(token, &path)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn list_blocked_users(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let fields = Fields::parse(&["login", "html_url"]);
+    let data = github_list(token, &blocks_path(args), &Paging::from_args(args, 100), &fields)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn block_user(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+    if username.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "username is required"}))));
+    }
+    let data = github_put(token, &format!("{}/{username}", blocks_path(args)), &json!({}))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn unblock_user(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+    if username.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "username is required"}))));
+    }
+    let data = github_delete(token, &format!("{}/{username}", blocks_path(args)))?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,156 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_request_with, json_body, state, u64_arg, JSON_MEDIA_TYPE};
+
+const DEFAULT_MAX_PAGES: u64 = 5;
+const PER_PAGE: usize = 50;
+
+fn summary(thread: &Value) -> Value {
+    json!({
+        "id": thread.get("id"),
+        "reason": thread.get("reason"),
+        "unread": thread.get("unread"),
+        "updated_at": thread.get("updated_at"),
+        "repository": thread.pointer("/repository/full_name"),
+        "type": thread.pointer("/subject/type"),
+        "title": thread.pointer("/subject/title"),
+        "url": thread.pointer("/subject/url")
+    })
+}
+
+// =============================================================================
+// Delta polling
+// =============================================================================
+
+/// Returns notifications that are new or updated since the previous poll.
+/// The first page is requested with `If-Modified-Since` set to the stored
+/// `Last-Modified`, so an unchanged inbox costs one 304 that does not count
+/// against the rate limit. Threads already returned at the latest
+/// `updated_at` are remembered since `since` is inclusive.
+pub(crate) fn poll_notifications(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let all = bool_arg(args, "all");
+    let participating = bool_arg(args, "participating");
+    let max_pages = u64_arg(args, "max_pages")
+        .unwrap_or(DEFAULT_MAX_PAGES)
+        .clamp(1, Paging::all().max_pages);
+    let key = format!(
+        "notifications/{}{}",
+        if all { "all" } else { "unread" },
+        if participating { "/participating" } else { "" }
+    );
+    let previous = if bool_arg(args, "reset") {
+        None
+    } else {
+        state::load(&key)
+    };
+
+    let last_modified = previous
+        .as_ref()
+        .and_then(|p| p.get("last_modified"))
+        .and_then(|v| v.as_str());
+    let since = previous
+        .as_ref()
+        .and_then(|p| p.get("since"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let seen: Vec<String> = previous
+        .as_ref()
+        .and_then(|p| p.get("seen_at_cursor"))
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|id| id.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    let mut query = format!("all={all}&participating={participating}&per_page={PER_PAGE}");
+    if !since.is_empty() {
+        query.push_str(&format!("&since={since}"));
+    }
+
+    let mut threads = Vec::new();
+    let mut new_last_modified = last_modified.map(str::to_string);
+    let mut poll_interval = None;
+    let mut more = false;
+    for page in 1..=max_pages {
+        let extra = match (page, last_modified) {
+            (1, Some(lm)) => vec![("If-Modified-Since", lm.to_string())],
+            _ => Vec::new(),
+        };
+        let resp = github_request_with(
+            token,
+            "GET",
+            &format!("/notifications?{query}&page={page}"),
+            None,
+            JSON_MEDIA_TYPE,
+            &extra,
+        )?;
+        if page == 1 {
+            poll_interval = resp.header("x-poll-interval").and_then(|v| v.parse::<u64>().ok());
+            if resp.status == 304 {
+                return Ok(Json(DataType::from_json(json!({
+                    "changed": false,
+                    "notifications": [],
+                    "poll_interval": poll_interval
+                }))));
+            }
+            if let Some(lm) = resp.header("last-modified") {
+                new_last_modified = Some(lm.to_string());
+            }
+        }
+        let batch = match json_body(&resp, &Fields::default())? {
+            Value::Array(batch) => batch,
+            other => return Ok(Json(DataType::from_json(other))),
+        };
+        let full = batch.len() == PER_PAGE;
+        threads.extend(batch);
+        if !full {
+            break;
+        }
+        more = page == max_pages;
+    }
+
+    let fresh: Vec<Value> = threads
+        .iter()
+        .filter(|t| {
+            let updated = t.get("updated_at").and_then(|v| v.as_str()).unwrap_or("");
+            let id = t.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            !(updated == since && seen.iter().any(|s| s == id))
+        })
+        .map(summary)
+        .collect();
+
+    // Notifications come newest first; the cursor moves to the newest seen.
+    let cursor = threads
+        .iter()
+        .filter_map(|t| t.get("updated_at").and_then(|v| v.as_str()))
+        .max()
+        .unwrap_or(since)
+        .to_string();
+    let mut cursor_ids: Vec<String> = threads
+        .iter()
+        .filter(|t| t.get("updated_at").and_then(|v| v.as_str()) == Some(cursor.as_str()))
+        .filter_map(|t| t.get("id").and_then(|v| v.as_str()).map(str::to_string))
+        .collect();
+    if cursor == since {
+        cursor_ids.extend(seen);
+    }
+    // Moving the cursor past a capped walk would skip the older threads for
+    // good, so the saved state only advances once everything was read.
+    if !more {
+        state::save(
+            &key,
+            &json!({"last_modified": new_last_modified, "since": cursor, "seen_at_cursor": cursor_ids}),
+        )?;
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "changed": !fresh.is_empty(),
+        "notifications": fresh,
+        // Older threads remained past `max_pages`; the cursor stayed put, so
+        // polling again with a higher `max_pages` returns them.
+        "more": more,
+        "poll_interval": poll_interval
+    }))))
+}
@@ -0,0 +1,237 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::composite::{get_all, Listing};
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_get, github_list, str_list_arg, u64_arg};
+
+const REPO_TYPES: &[&str] = &["all", "public", "private", "forks", "sources", "member"];
+const DEFAULT_MAX_ITEMS: u64 = 1000;
+
+const BILLING_KINDS: &[(&str, &str)] = &[
+    ("actions", "actions"),
+    ("packages", "packages"),
+    ("shared_storage", "shared-storage"),
+];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn logins(users: &[Value]) -> Vec<String> {
+    users
+        .iter()
+        .filter_map(|u| u.get("login").and_then(|l| l.as_str()).map(str::to_string))
+        .collect()
+}
+
+// `pending` logins (open invitations) are neither missing nor extra. A
+// truncated `actual` can't prove anyone extra or missing, so it is never in
+// sync.
+fn roster_diff(expected: &[String], actual: &Listing, pending: &[String]) -> Value {
+    let actual_logins = logins(&actual.items);
+    let has = |list: &[String], login: &str| list.iter().any(|l| l.eq_ignore_ascii_case(login));
+    let to_add: Vec<&String> = expected
+        .iter()
+        .filter(|l| !has(&actual_logins, l) && !has(pending, l))
+        .collect();
+    let to_remove: Vec<&String> = actual_logins.iter().filter(|l| !has(expected, l)).collect();
+    json!({
+        "expected": expected.len(),
+        "actual": actual_logins.len(),
+        "in_sync": !actual.truncated() && to_add.is_empty() && to_remove.is_empty(),
+        "truncated": actual.truncated(),
+        "to_add": to_add,
+        "to_remove": to_remove
+    })
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Org-level billing usage for Actions minutes, Packages transfer, and shared
+/// storage; `kind` picks one, default is all three.
+pub(crate) fn get_org_billing(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("all");
+    let selected: Vec<&(&str, &str)> = BILLING_KINDS
+        .iter()
+        .filter(|(name, _)| kind == "all" || kind == *name)
+        .collect();
+    if selected.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "kind must be one of: all, actions, packages, shared_storage"}),
+        )));
+    }
+    let mut out = json!({"org": org});
+    for (name, endpoint) in selected {
+        let data = github_get(token, &format!("/orgs/{org}/settings/billing/{endpoint}"))?;
+        out[*name] = data;
+    }
+    if kind != "all" {
+        let single = out.get(kind).cloned().unwrap_or(Value::Null);
+        return Ok(Json(DataType::from_json(single)));
+    }
+    Ok(Json(DataType::from_json(out)))
+}
+
+/// Orgs the token's user belongs to, with membership state and role. Pending
+/// invitations are included unless `state` is "active".
+pub(crate) fn list_my_orgs(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let state = args.get("state").and_then(|v| v.as_str()).unwrap_or("");
+    let mut path = "/user/memberships/orgs".to_string();
+    if !state.is_empty() {
+        path.push_str(&format!("?state={state}"));
+    }
+    let fields = Fields::parse(&["organization.login", "organization.description", "state", "role"]);
+    let data = github_list(token, &path, &Paging::from_args(args, 100), &fields)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn get_my_org_membership(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let data = github_get(token, &format!("/user/memberships/orgs/{org}"))?;
+    if data.get("role").is_none() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    Ok(Json(DataType::from_json(json!({
+        "org": org,
+        "state": data.get("state"),
+        "role": data.get("role"),
+        "user": data.pointer("/user/login")
+    }))))
+}
+
+/// Full repository inventory of an org, including private and internal
+/// repos the token can see. Pages are followed until `max_items`; with
+/// `installation`, lists the repos a GitHub App installation token covers.
+/// A truncated inventory resumes from `next_page` and `next_offset` (passed
+/// back as `page` and `offset`), since the cut can fall inside a page.
+pub(crate) fn org_repos(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let installation = bool_arg(args, "installation");
+    if org.is_empty() && !installation {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let kind = args.get("type").and_then(|v| v.as_str()).unwrap_or("all");
+    if !REPO_TYPES.contains(&kind) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("type must be one of: {}", REPO_TYPES.join(", "))}),
+        )));
+    }
+    let visibility = args.get("visibility").and_then(|v| v.as_str()).unwrap_or("");
+    let max_items = u64_arg(args, "max_items").unwrap_or(DEFAULT_MAX_ITEMS) as usize;
+    // The visibility filter needs its field even when `fields` leaves it out.
+    let mut paths = str_list_arg(args, "fields");
+    if !paths.is_empty() && !visibility.is_empty() {
+        paths.push("visibility".into());
+    }
+    let fields = Fields::parse(&paths);
+
+    let mut repos = Vec::new();
+    let mut page = u64_arg(args, "page").unwrap_or(1).max(1);
+    let mut offset = u64_arg(args, "offset").unwrap_or(0) as usize;
+    // Where the next call picks up: a page and an index into it.
+    let mut resume = None;
+    'pages: loop {
+        let path = if installation {
+            "/installation/repositories".to_string()
+        } else {
+            format!("/orgs/{org}/repos?type={kind}&sort=full_name")
+        };
+        let data = github_list(token, &path, &Paging::single(page, 100), &fields)?;
+        let Some(items) = data.get("items").and_then(|i| i.as_array()) else {
+            return Ok(Json(DataType::from_json(data)));
+        };
+        for (i, repo) in items.iter().enumerate().skip(offset) {
+            if repos.len() == max_items {
+                resume = Some((page, i));
+                break 'pages;
+            }
+            if visibility.is_empty() || repo.get("visibility").and_then(|v| v.as_str()) == Some(visibility) {
+                repos.push(repo.clone());
+            }
+        }
+        offset = 0;
+        match data.pointer("/page_info/next_page").and_then(|v| v.as_u64()) {
+            Some(next) if repos.len() < max_items => page = next,
+            Some(next) => {
+                resume = Some((next, 0));
+                break;
+            }
+            None => break,
+        }
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "count": repos.len(),
+        "truncated": resume.is_some(),
+        "next_page": resume.map(|(page, _)| page),
+        "next_offset": resume.map(|(_, offset)| offset),
+        "items": repos
+    }))))
+}
+
+/// Compares org membership (and optionally team rosters) with a canonical
+/// list, e.g. an HR export: `members` is the expected set of logins and
+/// `teams` maps team slugs to their expected logins. Logins compare
+/// case-insensitively; pending invitations count as neither missing nor extra.
+pub(crate) fn membership_diff(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let expected = str_list_arg(args, "members");
+    let teams = match args.get("teams").map(|v| v.to_json()) {
+        Some(Value::Object(teams)) => teams,
+        _ => Map::new(),
+    };
+    if org.is_empty() || (expected.is_empty() && teams.is_empty()) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "org and members (or teams) are required"}),
+        )));
+    }
+
+    let mut out = json!({"org": org});
+    if !expected.is_empty() {
+        let actual = match get_all(token, &format!("/orgs/{org}/members")) {
+            Ok(listing) => listing,
+            Err(error) => return Ok(Json(DataType::from_json(error))),
+        };
+        let invitations = match get_all(token, &format!("/orgs/{org}/invitations")) {
+            Ok(listing) => listing,
+            Err(error) => return Ok(Json(DataType::from_json(error))),
+        };
+        let pending = logins(&invitations.items);
+        let mut report = roster_diff(&expected, &actual, &pending);
+        report["pending_invitations"] = json!(pending);
+        report["pending_truncated"] = json!(invitations.truncated());
+        if invitations.truncated() {
+            report["in_sync"] = json!(false);
+        }
+        out["members"] = report;
+    }
+
+    let mut team_reports = Map::new();
+    for (slug, roster) in &teams {
+        let wanted: Vec<String> = roster
+            .as_array()
+            .map(|r| r.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let report = match get_all(token, &format!("/orgs/{org}/teams/{slug}/members")) {
+            Ok(actual) => roster_diff(&wanted, &actual, &[]),
+            Err(error) => json!({"error": error}),
+        };
+        team_reports.insert(slug.clone(), report);
+    }
+    if !teams.is_empty() {
+        out["teams"] = Value::Object(team_reports);
+    }
+    Ok(Json(DataType::from_json(out)))
+}
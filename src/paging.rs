@@ -0,0 +1,163 @@
+use std::cell::Cell;
+
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::u64_arg;
+
+pub(crate) const DEFAULT_MAX_PAGES: u64 = 10;
+
+thread_local! {
+    static MAX_PAGES: Cell<u64> = const { Cell::new(DEFAULT_MAX_PAGES) };
+}
+
+// =============================================================================
+// Page selection
+// =============================================================================
+
+/// Caps how many pages one call may walk (`max_pages` config).
+pub(crate) fn configure(config: &DataType) {
+    let limit = config
+        .get("max_pages")
+        .and_then(|v| v.to_json().as_u64())
+        .unwrap_or(DEFAULT_MAX_PAGES)
+        .max(1);
+    MAX_PAGES.with(|m| m.set(limit));
+}
+
+/// Which pages a list tool fetches: `page` (the `next_page` cursor of an
+/// earlier call), `per_page` (1-100), and `max_pages` to walk on from there.
+pub(crate) struct Paging {
+    pub page: u64,
+    pub per_page: u64,
+    pub max_pages: u64,
+}
+
+impl Paging {
+    pub(crate) fn from_args(args: &DataType, default_per_page: u64) -> Paging {
+        Paging {
+            page: u64_arg(args, "page").unwrap_or(1).max(1),
+            per_page: u64_arg(args, "per_page").unwrap_or(default_per_page).clamp(1, 100),
+            max_pages: u64_arg(args, "max_pages")
+                .unwrap_or(1)
+                .clamp(1, MAX_PAGES.with(|m| m.get())),
+        }
+    }
+
+    /// Every page from the first, up to the `max_pages` config cap.
+    pub(crate) fn all() -> Paging {
+        Paging {
+            page: 1,
+            per_page: 100,
+            max_pages: MAX_PAGES.with(|m| m.get()),
+        }
+    }
+
+    /// A single page, for callers that drive their own loop.
+    pub(crate) fn single(page: u64, per_page: u64) -> Paging {
+        Paging {
+            page,
+            per_page,
+            max_pages: 1,
+        }
+    }
+
+    pub(crate) fn url(&self, path: &str, page: u64) -> String {
+        let sep = if path.contains('?') { '&' } else { '?' };
+        format!("{path}{sep}per_page={}&page={page}", self.per_page)
+    }
+}
+
+/// Appends the items of a later page's envelope to `acc`, taking its cursor.
+pub(crate) fn append(acc: &mut Value, next: Value) {
+    let Value::Object(mut next) = next else {
+        return;
+    };
+    if let (Some(Value::Array(items)), Some(Value::Array(more))) = (acc.get_mut("items"), next.remove("items")) {
+        items.extend(more);
+    }
+    acc["page_info"]["next_page"] = next
+        .get("page_info")
+        .and_then(|p| p.get("next_page"))
+        .cloned()
+        .unwrap_or(Value::Null);
+}
+
+// =============================================================================
+// Link header pagination
+// =============================================================================
+
+/// Parses an RFC 8288 `Link` header into (rel, url) pairs.
+pub(crate) fn parse_link(header: &str) -> Vec<(String, String)> {
+    header
+        .split(',')
+        .filter_map(|part| {
+            let (url, params) = part.split_once(';')?;
+            let url = url.trim().trim_start_matches('<').trim_end_matches('>');
+            let rel = params
+                .split(';')
+                .filter_map(|p| p.trim().strip_prefix("rel="))
+                .map(|r| r.trim_matches('"'))
+                .next()?;
+            Some((rel.to_string(), url.to_string()))
+        })
+        .collect()
+}
+
+fn query_param(url: &str, name: &str) -> Option<u64> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .find(|(k, _)| *k == name)
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+/// Wraps a list response as `{items, page_info: {next_page, total_hint}}`.
+/// Object responses that wrap their list (search `items`, Actions
+/// `workflow_runs`, ...) are unwrapped; anything else (e.g. an error body)
+/// is returned untouched.
+pub(crate) fn envelope(data: Value, link: Option<&str>) -> Value {
+    let links = link.map(parse_link).unwrap_or_default();
+    let rel = |name: &str| links.iter().find(|(r, _)| r == name).map(|(_, url)| url.as_str());
+    let next_page = rel("next").and_then(|url| query_param(url, "page"));
+
+    let (items, total_count) = match data {
+        Value::Array(items) => (items, None),
+        Value::Object(mut obj) => {
+            let Some(field) = list_field(&obj) else {
+                return Value::Object(obj);
+            };
+            let total = obj.get("total_count").and_then(|v| v.as_u64());
+            match obj.remove(&field) {
+                Some(Value::Array(items)) => (items, total),
+                _ => return Value::Object(obj),
+            }
+        }
+        other => return other,
+    };
+
+    // Exact when the API reports a count; otherwise an upper bound from the
+    // last page number, or exact when this is the only page.
+    let total_hint = total_count.or_else(|| {
+        let last = rel("last")?;
+        let pages = query_param(last, "page")?;
+        let per_page = query_param(last, "per_page").unwrap_or(items.len() as u64);
+        Some(pages * per_page)
+    });
+    let total_hint = match (total_hint, next_page, rel("prev")) {
+        (None, None, None) => Some(items.len() as u64),
+        (hint, _, _) => hint,
+    };
+    json!({
+        "items": items,
+        "page_info": {"next_page": next_page, "total_hint": total_hint}
+    })
+}
+
+fn list_field(obj: &Map<String, Value>) -> Option<String> {
+    let mut arrays = obj.iter().filter(|(_, v)| v.is_array()).map(|(k, _)| k.clone());
+    let field = arrays.next()?;
+    // Ambiguous with several arrays; leave such payloads alone.
+    arrays.next().is_none().then_some(field)
+}
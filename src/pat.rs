@@ -0,0 +1,151 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_all;
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, github_list, github_post, str_list_arg, url_encode};
+
+const REVIEW_ACTIONS: &[&str] = &["approve", "deny"];
+
+// Query filters shared by the request and grant listings.
+const FILTERS: &[&str] = &[
+    "owner",
+    "repository",
+    "permission",
+    "last_used_before",
+    "last_used_after",
+    "token_id",
+    "sort",
+    "direction",
+];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Like `str_list_arg`, but ids may also arrive as JSON numbers.
+fn id_list(args: &DataType, key: &str) -> Vec<String> {
+    match args.get(key).map(|v| v.to_json()) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| {
+                v.as_u64()
+                    .map(|n| n.to_string())
+                    .or_else(|| v.as_str().map(str::to_string))
+            })
+            .collect(),
+        Some(Value::Number(n)) => vec![n.to_string()],
+        _ => str_list_arg(args, key),
+    }
+}
+
+fn list_path(base: &str, args: &DataType) -> String {
+    let mut filters = Vec::new();
+    for key in FILTERS {
+        // `owner` and `token_id` accept several values as repeated params.
+        for value in id_list(args, key) {
+            let param = if matches!(*key, "owner" | "token_id") {
+                format!("{key}[]")
+            } else {
+                key.to_string()
+            };
+            filters.push(format!("{param}={}", url_encode(&value)));
+        }
+    }
+    if filters.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base}?{}", filters.join("&"))
+    }
+}
+
+// Repositories a request or grant covers. Only `subset` selections have a
+// list to fetch; `all` and `none` are complete as stated. A list that could
+// not be read, or was cut off, comes back as an object saying so.
+fn repository_scope(token: &str, item: &Value, base: &str) -> Value {
+    if item.get("repository_selection").and_then(|s| s.as_str()) != Some("subset") {
+        return Value::Null;
+    }
+    let Some(id) = item.get("id").and_then(|i| i.as_u64()) else {
+        return Value::Null;
+    };
+    let listing = match get_all(token, &format!("{base}/{id}/repositories")) {
+        Ok(listing) => listing,
+        Err(error) => return json!({"error": error}),
+    };
+    let repos: Vec<Value> = listing
+        .items
+        .iter()
+        .filter_map(|r| r.get("full_name").cloned())
+        .collect();
+    match listing.next_page {
+        Some(next_page) => json!({"repositories": repos, "truncated": true, "next_page": next_page}),
+        None => json!(repos),
+    }
+}
+
+fn list(token: &str, args: &DataType, kind: &str) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let base = format!("/orgs/{org}/{kind}");
+    let mut data = github_list(
+        token,
+        &list_path(&base, args),
+        &Paging::from_args(args, 30),
+        &Fields::from_args(args),
+    )?;
+    if bool_arg(args, "include_repositories") {
+        if let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) {
+            for item in items.iter_mut() {
+                item["repositories"] = repository_scope(token, item, &base);
+            }
+        }
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Pending fine-grained PAT requests awaiting org approval.
+pub(crate) fn list_pat_requests(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    list(token, args, "personal-access-token-requests")
+}
+
+/// Fine-grained PATs with access to the org, with their permissions and
+/// (with `include_repositories`) the repositories they can reach.
+pub(crate) fn list_pat_grants(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    list(token, args, "personal-access-tokens")
+}
+
+/// Approves or denies pending PAT requests in bulk (`request_ids`), with an
+/// optional `reason` shown to the requesters.
+pub(crate) fn review_pat_requests(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+    let ids: Vec<u64> = id_list(args, "request_ids")
+        .iter()
+        .filter_map(|id| id.parse().ok())
+        .collect();
+    if org.is_empty() || ids.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "org, request_ids, and action are required"}),
+        )));
+    }
+    if !REVIEW_ACTIONS.contains(&action) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("action must be one of: {}", REVIEW_ACTIONS.join(", "))}),
+        )));
+    }
+    let mut body = json!({"pat_request_ids": ids, "action": action});
+    if let Some(reason) = args.get("reason").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        body["reason"] = json!(reason);
+    }
+    let data = github_post(token, &format!("/orgs/{org}/personal-access-token-requests"), &body)?;
+    Ok(Json(DataType::from_json(data)))
+}
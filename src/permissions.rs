@@ -0,0 +1,180 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::{errors, github_get, github_put};
+
+// Repository roles from least to most privileged.
+const LEVELS: &[&str] = &["none", "read", "triage", "write", "maintain", "admin"];
+
+// Built-in values accepted by the collaborator and team endpoints.
+const BUILTIN_ROLES: &[&str] = &["pull", "triage", "push", "maintain", "admin"];
+
+fn rank(level: &str) -> Option<usize> {
+    LEVELS.iter().position(|l| *l == level)
+}
+
+fn custom_roles(token: &str, org: &str) -> Vec<Value> {
+    github_get(token, &format!("/orgs/{org}/custom-repository-roles"))
+        .ok()
+        .and_then(|data| data.get("custom_roles").and_then(|r| r.as_array()).cloned())
+        .unwrap_or_default()
+}
+
+// Returns the permission value to send, or an error listing what the org
+// accepts. Custom roles are matched by name, case-insensitively.
+fn resolve_role(token: &str, org: &str, role: &str) -> Result<String, Value> {
+    if BUILTIN_ROLES.contains(&role) {
+        return Ok(role.to_string());
+    }
+    let roles = custom_roles(token, org);
+    if let Some(name) = roles
+        .iter()
+        .filter_map(|r| r.get("name").and_then(|n| n.as_str()))
+        .find(|name| name.eq_ignore_ascii_case(role))
+    {
+        return Ok(name.to_string());
+    }
+    let mut available: Vec<String> = BUILTIN_ROLES.iter().map(|r| r.to_string()).collect();
+    available.extend(
+        roles
+            .iter()
+            .filter_map(|r| r.get("name").and_then(|n| n.as_str()).map(str::to_string)),
+    );
+    Err(json!({"error": format!("unknown role: {role}"), "available": available}))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Answers whether `username` holds at least `required` (default "write") on
+/// a repo. `role_name` is preferred as it distinguishes triage and maintain;
+/// custom roles fall back to their base `permission`.
+pub(crate) fn check_permission(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+    let required = args.get("required").and_then(|v| v.as_str()).unwrap_or("write");
+    if owner.is_empty() || repo.is_empty() || username.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and username are required"}),
+        )));
+    }
+    let Some(required_rank) = rank(required) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("required must be one of: {}", LEVELS[1..].join(", "))}),
+        )));
+    };
+
+    let data = github_get(
+        token,
+        &format!("/repos/{owner}/{repo}/collaborators/{username}/permission"),
+    )?;
+    let Some(permission) = data.get("permission").and_then(|v| v.as_str()) else {
+        // Non-collaborators get a 404 here; anything else is a real error.
+        if errors::is_not_found(&data) {
+            return Ok(Json(DataType::from_json(json!({
+                "username": username,
+                "permission": "none",
+                "required": required,
+                "allowed": required_rank == 0
+            }))));
+        }
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let role_name = data.get("role_name").and_then(|v| v.as_str()).unwrap_or(permission);
+    let effective = rank(role_name).or_else(|| rank(permission)).unwrap_or(0);
+
+    Ok(Json(DataType::from_json(json!({
+        "username": username,
+        "permission": permission,
+        "role_name": role_name,
+        "effective": LEVELS[effective],
+        "required": required,
+        "allowed": effective >= required_rank
+    }))))
+}
+
+pub(crate) fn list_custom_repo_roles(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let roles: Vec<Value> = custom_roles(token, org)
+        .iter()
+        .map(|r| {
+            json!({
+                "id": r.get("id"),
+                "name": r.get("name"),
+                "description": r.get("description"),
+                "base_role": r.get("base_role")
+            })
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"roles": roles}))))
+}
+
+pub(crate) fn get_custom_repo_role(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let id = args.get("id").and_then(|v| v.to_json().as_u64()).unwrap_or(0);
+    if org.is_empty() || id == 0 {
+        return Ok(Json(DataType::from_json(json!({"error": "org and id are required"}))));
+    }
+    let data = github_get(token, &format!("/orgs/{org}/custom-repository-roles/{id}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Adds (or invites) a collaborator with a built-in or custom role. Custom
+/// roles belong to the repo owner's org.
+pub(crate) fn add_collaborator(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+    let role = args.get("role").and_then(|v| v.as_str()).unwrap_or("push");
+    if owner.is_empty() || repo.is_empty() || username.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and username are required"}),
+        )));
+    }
+    let permission = match resolve_role(token, owner, role) {
+        Ok(permission) => permission,
+        Err(e) => return Ok(Json(DataType::from_json(e))),
+    };
+    let data = github_put(
+        token,
+        &format!("/repos/{owner}/{repo}/collaborators/{username}"),
+        &json!({"permission": permission}),
+    )?;
+    // 201 returns the invitation; 204 means they already had access and the role was updated.
+    let invited = data.get("id").is_some();
+    Ok(Json(DataType::from_json(json!({
+        "username": username,
+        "permission": permission,
+        "invited": invited,
+        "invitation": if invited { data } else { Value::Null }
+    }))))
+}
+
+pub(crate) fn add_team_repo(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let team = args.get("team").and_then(|v| v.as_str()).unwrap_or("");
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or(org);
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let role = args.get("role").and_then(|v| v.as_str()).unwrap_or("push");
+    if org.is_empty() || team.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "org, team, and repo are required"}),
+        )));
+    }
+    let permission = match resolve_role(token, org, role) {
+        Ok(permission) => permission,
+        Err(e) => return Ok(Json(DataType::from_json(e))),
+    };
+    let data = github_put(
+        token,
+        &format!("/orgs/{org}/teams/{team}/repos/{owner}/{repo}"),
+        &json!({"permission": permission}),
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,269 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::{github_delete, github_get, github_patch, github_post, repos_arg, str_list_arg};
+
+// Rules applied to protected tags when no `rules` are given.
+const DEFAULT_TAG_RULES: &[&str] = &["deletion", "update", "non_fast_forward"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn checks_path(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let branch = args.get("branch").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || branch.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "/repos/{owner}/{repo}/branches/{branch}/protection/required_status_checks"
+    ))
+}
+
+// Accepts check names or `{context, app_id}` objects, as an array or a
+// comma-separated string. A bare name matches any app.
+fn checks_arg(args: &DataType, key: &str) -> Vec<Value> {
+    let items = match args.get(key).map(|v| v.to_json()) {
+        Some(Value::Array(items)) => items,
+        Some(Value::String(s)) => s.split(',').map(|p| json!(p.trim())).collect(),
+        _ => return Vec::new(),
+    };
+    items
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::String(context) if !context.is_empty() => Some(json!({"context": context})),
+            Value::Object(obj) if obj.get("context").and_then(|c| c.as_str()).is_some() => Some(Value::Object(obj)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn context_of(check: &Value) -> &str {
+    check.get("context").and_then(|c| c.as_str()).unwrap_or("")
+}
+
+// Reduces GitHub's response to what callers change.
+fn summary(data: Value) -> Value {
+    if data.get("message").is_some() {
+        return data;
+    }
+    json!({
+        "strict": data.get("strict"),
+        "checks": data.get("checks").cloned().unwrap_or(json!([]))
+    })
+}
+
+fn tag_ref(pattern: &str) -> String {
+    if pattern.starts_with("refs/") {
+        pattern.to_string()
+    } else {
+        format!("refs/tags/{pattern}")
+    }
+}
+
+fn tag_rulesets(token: &str, owner: &str, repo: &str) -> Vec<Value> {
+    let listed = match github_get(
+        token,
+        &format!("/repos/{owner}/{repo}/rulesets?targets=tag&per_page=100"),
+    ) {
+        Ok(Value::Array(items)) => items,
+        _ => return Vec::new(),
+    };
+    // The listing omits conditions and rules, so fetch each ruleset.
+    listed
+        .iter()
+        .filter_map(|r| r.get("id").and_then(|id| id.as_u64()))
+        .filter_map(|id| github_get(token, &format!("/repos/{owner}/{repo}/rulesets/{id}")).ok())
+        .map(|r| {
+            json!({
+                "id": r.get("id"),
+                "name": r.get("name"),
+                "enforcement": r.get("enforcement"),
+                "source_type": r.get("source_type"),
+                "patterns": r.pointer("/conditions/ref_name/include"),
+                "rules": r
+                    .get("rules")
+                    .and_then(|rules| rules.as_array())
+                    .map(|rules| rules.iter().filter_map(|rule| rule.get("type").cloned()).collect::<Vec<_>>())
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn get_required_status_checks(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = checks_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    };
+    let data = github_get(token, &path)?;
+    Ok(Json(DataType::from_json(summary(data))))
+}
+
+/// Updates only the required status checks of a protected branch. `checks`
+/// replaces the list outright; otherwise `add` and `remove` edit the current
+/// one. `strict` (require branches to be up to date) is kept unless given.
+pub(crate) fn update_required_status_checks(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = checks_path(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and branch are required"}),
+        )));
+    };
+    let replace = args.get("checks").is_some();
+    let add = checks_arg(args, "add");
+    let remove = checks_arg(args, "remove");
+    let strict = args.get("strict").and_then(|v| v.to_json().as_bool());
+    if !replace && add.is_empty() && remove.is_empty() && strict.is_none() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "one of checks, add, remove, or strict is required"}),
+        )));
+    }
+
+    let current = github_get(token, &path)?;
+    if current.get("message").is_some() {
+        return Ok(Json(DataType::from_json(current)));
+    }
+    let mut checks = if replace {
+        checks_arg(args, "checks")
+    } else {
+        current
+            .get("checks")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    checks.retain(|c| !remove.iter().any(|r| context_of(r) == context_of(c)));
+    for check in add {
+        if !checks.iter().any(|c| context_of(c) == context_of(&check)) {
+            checks.push(check);
+        }
+    }
+
+    let mut body = json!({"checks": checks});
+    if let Some(strict) = strict {
+        body["strict"] = json!(strict);
+    }
+    let data = github_patch(token, &path, &body)?;
+    Ok(Json(DataType::from_json(summary(data))))
+}
+
+/// Lists legacy tag protection patterns alongside tag rulesets. The legacy
+/// endpoint is retired on most repositories; its error is reported, not fatal.
+pub(crate) fn list_tag_protection(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let repos = repos_arg(args);
+    if repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or repos, are required"}),
+        )));
+    }
+    let results: Vec<Value> = repos
+        .iter()
+        .map(|(owner, repo)| {
+            let legacy = match github_get(token, &format!("/repos/{owner}/{repo}/tags/protection")) {
+                Ok(Value::Array(items)) => json!(items
+                    .iter()
+                    .map(|p| json!({"id": p.get("id"), "pattern": p.get("pattern")}))
+                    .collect::<Vec<_>>()),
+                Ok(other) => json!({"unavailable": other.get("message")}),
+                Err(e) => json!({"unavailable": e.to_string()}),
+            };
+            json!({
+                "repo": format!("{owner}/{repo}"),
+                "legacy": legacy,
+                "rulesets": tag_rulesets(token, owner, repo)
+            })
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"repos": results}))))
+}
+
+/// Protects tags matching `pattern` on one repo or every repo in `repos`.
+/// `method` "ruleset" (default) creates a tag ruleset; "legacy" uses the
+/// older tag protection API.
+pub(crate) fn create_tag_protection(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let pattern = args.get("pattern").and_then(|v| v.as_str()).unwrap_or("");
+    let method = args.get("method").and_then(|v| v.as_str()).unwrap_or("ruleset");
+    let repos = repos_arg(args);
+    if pattern.is_empty() || repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "pattern and owner/repo (or repos) are required"}),
+        )));
+    }
+    if method != "ruleset" && method != "legacy" {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "method must be ruleset or legacy"}),
+        )));
+    }
+    let mut rules = str_list_arg(args, "rules");
+    if rules.is_empty() {
+        rules = DEFAULT_TAG_RULES.iter().map(|r| r.to_string()).collect();
+    }
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Protect tags {pattern}"));
+    let enforcement = args.get("enforcement").and_then(|v| v.as_str()).unwrap_or("active");
+
+    let results: Vec<Value> = repos
+        .iter()
+        .map(|(owner, repo)| {
+            let created = if method == "legacy" {
+                github_post(
+                    token,
+                    &format!("/repos/{owner}/{repo}/tags/protection"),
+                    &json!({"pattern": pattern}),
+                )
+            } else {
+                let body = json!({
+                    "name": name,
+                    "target": "tag",
+                    "enforcement": enforcement,
+                    "conditions": {"ref_name": {"include": [tag_ref(pattern)], "exclude": []}},
+                    "rules": rules.iter().map(|r| json!({"type": r})).collect::<Vec<_>>()
+                });
+                github_post(token, &format!("/repos/{owner}/{repo}/rulesets"), &body)
+            };
+            match created {
+                Ok(data) if data.get("id").is_some() => {
+                    json!({"repo": format!("{owner}/{repo}"), "id": data.get("id"), "created": true})
+                }
+                Ok(data) => json!({"repo": format!("{owner}/{repo}"), "created": false, "error": data.get("message")}),
+                Err(e) => json!({"repo": format!("{owner}/{repo}"), "created": false, "error": e.to_string()}),
+            }
+        })
+        .collect();
+    Ok(Json(DataType::from_json(
+        json!({"method": method, "pattern": pattern, "results": results}),
+    )))
+}
+
+pub(crate) fn delete_tag_protection(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let id = args.get("id").and_then(|v| v.to_json().as_u64()).unwrap_or(0);
+    let method = args.get("method").and_then(|v| v.as_str()).unwrap_or("ruleset");
+    if owner.is_empty() || repo.is_empty() || id == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and id are required"}),
+        )));
+    }
+    let path = match method {
+        "ruleset" => format!("/repos/{owner}/{repo}/rulesets/{id}"),
+        "legacy" => format!("/repos/{owner}/{repo}/tags/protection/{id}"),
+        _ => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "method must be ruleset or legacy"}),
+            )))
+        }
+    };
+    let data = github_delete(token, &path)?;
+    Ok(Json(DataType::from_json(data)))
+}
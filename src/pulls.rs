@@ -0,0 +1,411 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::composite::get_all;
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{
+    bool_arg, branches, commits, github_list, github_patch, github_post, github_put, github_request, json_body,
+    media_type, state, str_list_arg, templates, u64_arg,
+};
+
+const REVIEW_EVENTS: &[&str] = &["APPROVE", "REQUEST_CHANGES", "COMMENT"];
+const MERGE_METHODS: &[&str] = &["merge", "squash", "rebase"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Paths a PR touches (including the old side of renames). Cached per PR and
+// reused while its head SHA is unchanged, so repeated queue views only fetch
+// file lists for PRs that moved. Failed or cut-off fetches are not cached;
+// the flag says whether the list stopped at the page cap.
+fn changed_paths(token: &str, owner: &str, repo: &str, pr: &Value) -> Result<(Vec<String>, bool), Value> {
+    let number = pr.get("number").and_then(|n| n.as_u64()).unwrap_or(0);
+    let head = pr.pointer("/head/sha").and_then(|s| s.as_str()).unwrap_or("");
+    let key = format!("pr_files/{owner}/{repo}/{number}");
+    if let Some(cached) = state::load(&key) {
+        if cached.get("head").and_then(|h| h.as_str()) == Some(head) {
+            return Ok((str_items(cached.get("files")), false));
+        }
+    }
+    let listing = get_all(token, &format!("/repos/{owner}/{repo}/pulls/{number}/files"))?;
+    let mut files = Vec::new();
+    for file in &listing.items {
+        for field in ["filename", "previous_filename"] {
+            if let Some(name) = file.get(field).and_then(|f| f.as_str()) {
+                files.push(name.to_string());
+            }
+        }
+    }
+    if !listing.truncated() {
+        let _ = state::save(&key, &json!({"head": head, "files": files}));
+    }
+    Ok((files, listing.truncated()))
+}
+
+fn str_items(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+// A prefix matches itself and anything below it ("svc/api" matches
+// "svc/api/main.rs" but not "svc/api2/x").
+fn under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_matches('/');
+    prefix.is_empty() || path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Opens a pull request. With `template`, the body is taken from the named
+/// PR template ("default" for the single-file one) and `{{placeholders}}` are
+/// filled from `template_vars` plus `title`, `head`, and `base`; an explicit
+/// `body` is appended below the template.
+pub(crate) fn create_pr(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let title = args.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    let head = args.get("head").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || title.is_empty() || head.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, title, and head are required"}),
+        )));
+    }
+    let base = match args.get("base").and_then(|v| v.as_str()) {
+        Some(base) if !base.is_empty() => base.to_string(),
+        _ => match branches::default_branch(token, owner, repo)? {
+            Some(base) => base,
+            None => {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": "could not determine base branch"}),
+                )))
+            }
+        },
+    };
+    let mut body = args.get("body").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    let mut unfilled = Vec::new();
+    if let Some(name) = args.get("template").and_then(|v| v.as_str()).filter(|n| !n.is_empty()) {
+        let available = templates::pr_templates(token, owner, repo);
+        let Some(template) = available.iter().find(|t| t.name == name) else {
+            let names: Vec<&str> = available.iter().map(|t| t.name.as_str()).collect();
+            return Ok(Json(DataType::from_json(
+                json!({"error": format!("unknown PR template: {name}"), "available": names}),
+            )));
+        };
+        let mut vars = match args.get("template_vars").map(|v| v.to_json()) {
+            Some(Value::Object(vars)) => vars,
+            _ => Map::new(),
+        };
+        for (key, value) in [("title", title), ("head", head), ("base", base.as_str())] {
+            vars.entry(key).or_insert_with(|| json!(value));
+        }
+        let (filled, missing) = templates::fill_placeholders(&template.text, &vars);
+        unfilled = missing;
+        body = if body.is_empty() {
+            filled
+        } else {
+            format!("{filled}\n\n{body}")
+        };
+    }
+
+    let payload = json!({
+        "title": title,
+        "head": head,
+        "base": base,
+        "body": body,
+        "draft": bool_arg(args, "draft")
+    });
+    let mut data = github_post(token, &format!("/repos/{owner}/{repo}/pulls"), &payload)?;
+    if !unfilled.is_empty() {
+        if let Value::Object(obj) = &mut data {
+            obj.insert("unfilled_placeholders".into(), json!(unfilled));
+        }
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Open PRs touching any of `paths` (directory prefixes), for per-team views
+/// of a monorepo's queue. Each match lists the paths that put it there.
+pub(crate) fn list_prs_by_path(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let prefixes = str_list_arg(args, "paths");
+    if owner.is_empty() || repo.is_empty() || prefixes.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and paths are required"}),
+        )));
+    }
+    let pulls = match get_all(token, &format!("/repos/{owner}/{repo}/pulls?state=open")) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let mut matched = Vec::new();
+    let mut unreadable = Vec::new();
+    let mut partial = Vec::new();
+    for pr in &pulls.items {
+        let paths = match changed_paths(token, owner, repo, pr) {
+            Ok((paths, truncated)) => {
+                if truncated {
+                    partial.push(pr.get("number").cloned().unwrap_or(Value::Null));
+                }
+                paths
+            }
+            Err(error) => {
+                unreadable.push(json!({"number": pr.get("number"), "error": error}));
+                continue;
+            }
+        };
+        let touched: Vec<String> = paths
+            .into_iter()
+            .filter(|path| prefixes.iter().any(|prefix| under(path, prefix)))
+            .collect();
+        if touched.is_empty() {
+            continue;
+        }
+        matched.push(json!({
+            "number": pr.get("number"),
+            "title": pr.get("title"),
+            "author": pr.pointer("/user/login"),
+            "draft": pr.get("draft"),
+            "updated_at": pr.get("updated_at"),
+            "html_url": pr.get("html_url"),
+            "matched_files": touched
+        }));
+    }
+    Ok(Json(DataType::from_json(json!({
+        "paths": prefixes,
+        "open_prs_scanned": pulls.items.len(),
+        "count": matched.len(),
+        "pull_requests": matched,
+        // Open PRs past the page cap, or PRs whose file list was cut off
+        // (listed in `files_truncated`), may be missing above.
+        "truncated": pulls.truncated() || !partial.is_empty(),
+        "files_truncated": partial,
+        // PRs whose file list could not be read, so they may be missing above.
+        "unreadable": unreadable
+    }))))
+}
+
+pub(crate) fn list_reviews(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/pulls/{number}/reviews");
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Files a PR changes with their patches (GitHub lists at most 3000).
+pub(crate) fn get_pr_files(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/pulls/{number}/files");
+    let mut data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    if let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) {
+        for file in items.iter_mut() {
+            commits::annotate_patch(file);
+        }
+    }
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// The PR as a unified diff (`format`: diff, or patch for mbox-style
+/// per-commit patches). GitHub refuses diffs over its size limit with 406;
+/// that case points at get_pr_files, which pages through the files instead.
+pub(crate) fn get_pr_diff(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("diff");
+    if !matches!(format, "diff" | "patch") {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "format must be diff or patch"}),
+        )));
+    }
+    let path = format!("/repos/{owner}/{repo}/pulls/{number}");
+    let resp = github_request(token, "GET", &path, None, media_type(format))?;
+    if resp.status == 406 {
+        return Ok(Json(DataType::from_json(json!({
+            "error": "the diff exceeds GitHub's size limit; use get_pr_files to page through per-file patches",
+            "status": 406
+        }))));
+    }
+    if resp.status >= 400 {
+        return Ok(Json(DataType::from_json(json_body(&resp, &Fields::default())?)));
+    }
+    let text = String::from_utf8_lossy(&resp.body).into_owned();
+    let files = text.lines().filter(|l| l.starts_with("diff --git ")).count();
+    Ok(Json(DataType::from_json(json!({
+        "format": format,
+        "files_changed": files,
+        "bytes": text.len(),
+        "content": text
+    }))))
+}
+
+/// Inline review comments on a PR, or only those of one review when
+/// `review_id` is given.
+pub(crate) fn list_review_comments(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let path = match u64_arg(args, "review_id") {
+        Some(review) => format!("/repos/{owner}/{repo}/pulls/{number}/reviews/{review}/comments"),
+        None => format!("/repos/{owner}/{repo}/pulls/{number}/comments"),
+    };
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Submits a review. `comments` are inline comments, each with `path`,
+/// `body`, and a `line` (plus optional `side`, `start_line`, `start_side`
+/// for multi-line ranges) or a legacy diff `position`.
+pub(crate) fn submit_review(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    let event = args.get("event").and_then(|v| v.as_str()).unwrap_or("").to_uppercase();
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    if !REVIEW_EVENTS.contains(&event.as_str()) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "event must be APPROVE, REQUEST_CHANGES, or COMMENT"}),
+        )));
+    }
+    let body = args.get("body").and_then(|v| v.as_str()).unwrap_or("");
+    let comments = match args.get("comments").map(|v| v.to_json()) {
+        Some(Value::Array(comments)) => comments,
+        _ => Vec::new(),
+    };
+    if event != "APPROVE" && body.is_empty() && comments.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("{event} reviews need a body or inline comments")}),
+        )));
+    }
+
+    let mut inline = Vec::new();
+    for (i, comment) in comments.iter().enumerate() {
+        let has_text = |key: &str| comment.get(key).and_then(|v| v.as_str()).is_some_and(|s| !s.is_empty());
+        let anchored = comment.get("line").is_some() || comment.get("position").is_some();
+        if !has_text("path") || !has_text("body") || !anchored {
+            return Ok(Json(DataType::from_json(
+                json!({"error": format!("comments[{i}] needs path, body, and line (or position)")}),
+            )));
+        }
+        let mut entry = json!({});
+        for key in ["path", "body", "line", "side", "start_line", "start_side", "position"] {
+            if let Some(value) = comment.get(key) {
+                entry[key] = value.clone();
+            }
+        }
+        inline.push(entry);
+    }
+
+    let mut payload = json!({"event": event, "comments": inline});
+    if !body.is_empty() {
+        payload["body"] = json!(body);
+    }
+    if let Some(commit) = args.get("commit_id").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        payload["commit_id"] = json!(commit);
+    }
+    let data = github_post(
+        token,
+        &format!("/repos/{owner}/{repo}/pulls/{number}/reviews"),
+        &payload,
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Edits a PR's `title`, `body`, `state` (open or closed), or `base`; only
+/// the fields given are sent.
+pub(crate) fn update_pr(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let mut payload = json!({});
+    for key in ["title", "body", "state", "base"] {
+        if let Some(value) = args.get(key).and_then(|v| v.as_str()) {
+            payload[key] = json!(value);
+        }
+    }
+    if let Some(state) = payload.get("state").and_then(|s| s.as_str()) {
+        if state != "open" && state != "closed" {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "state must be open or closed"}),
+            )));
+        }
+    }
+    if args.get("maintainer_can_modify").is_some() {
+        payload["maintainer_can_modify"] = json!(bool_arg(args, "maintainer_can_modify"));
+    }
+    if payload.as_object().is_some_and(|p| p.is_empty()) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "nothing to update: give title, body, state, base, or maintainer_can_modify"}),
+        )));
+    }
+    let data = github_patch(token, &format!("/repos/{owner}/{repo}/pulls/{number}"), &payload)?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Merges a PR with `merge_method` merge, squash, or rebase. `sha` pins the
+/// head the caller reviewed: GitHub refuses the merge if the branch moved.
+pub(crate) fn merge_pr(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    let method = args.get("merge_method").and_then(|v| v.as_str()).unwrap_or("merge");
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    if !MERGE_METHODS.contains(&method) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "merge_method must be merge, squash, or rebase"}),
+        )));
+    }
+    let mut payload = json!({"merge_method": method});
+    for key in ["commit_title", "commit_message", "sha"] {
+        if let Some(value) = args.get(key).and_then(|v| v.as_str()).filter(|v| !v.is_empty()) {
+            payload[key] = json!(value);
+        }
+    }
+    let data = github_put(token, &format!("/repos/{owner}/{repo}/pulls/{number}/merge"), &payload)?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,126 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::{github_get, time, Response};
+
+pub(crate) const DEFAULT_MAX_RETRIES: u64 = 3;
+
+// Longest single wait; a primary limit resetting later than this is reported
+// rather than slept through.
+const MAX_WAIT_SECS: u64 = 60;
+
+// Secondary limits without Retry-After: GitHub asks for at least a minute,
+// but short bursts usually clear sooner, so start lower and double.
+const BASE_BACKOFF_SECS: u64 = 5;
+
+thread_local! {
+    static MAX_RETRIES: Cell<u64> = const { Cell::new(DEFAULT_MAX_RETRIES) };
+}
+
+// =============================================================================
+// Retry policy
+// =============================================================================
+
+pub(crate) fn configure(config: &DataType) {
+    let retries = config
+        .get("max_retries")
+        .and_then(|v| v.to_json().as_u64())
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+    MAX_RETRIES.with(|m| m.set(retries));
+}
+
+fn header_u64(resp: &Response, name: &str) -> Option<u64> {
+    resp.header(name).and_then(|v| v.trim().parse().ok())
+}
+
+// 429 always; 403 only when the quota is exhausted or the body names a rate
+// limit (a plain 403 is a permissions problem and retrying won't help).
+fn is_rate_limited(resp: &Response) -> bool {
+    match resp.status {
+        429 => true,
+        403 => {
+            header_u64(resp, "x-ratelimit-remaining") == Some(0)
+                || resp.header("retry-after").is_some()
+                || String::from_utf8_lossy(&resp.body)
+                    .to_ascii_lowercase()
+                    .contains("rate limit")
+        }
+        _ => false,
+    }
+}
+
+/// How long to wait before retrying `resp`, or `None` when it isn't a rate
+/// limit response, retries are used up, or the wait would exceed a minute.
+/// Prefers `Retry-After`, then `X-RateLimit-Reset` when the quota is spent,
+/// then exponential backoff.
+pub(crate) fn backoff(resp: &Response, attempt: u64) -> Option<Duration> {
+    if !is_rate_limited(resp) || attempt >= MAX_RETRIES.with(|m| m.get()) {
+        return None;
+    }
+    let wait = if let Some(secs) = header_u64(resp, "retry-after") {
+        secs
+    } else if header_u64(resp, "x-ratelimit-remaining") == Some(0) {
+        let reset = header_u64(resp, "x-ratelimit-reset")?;
+        // One extra second for clock skew between us and GitHub.
+        reset.saturating_sub(time::now().max(0) as u64) + 1
+    } else {
+        BASE_BACKOFF_SECS << attempt.min(4)
+    };
+    (wait <= MAX_WAIT_SECS).then(|| Duration::from_secs(wait))
+}
+
+/// Rate limit hints copied into an error body so callers know when to come
+/// back instead of retrying immediately.
+pub(crate) fn annotate(resp: &Response, obj: &mut Map<String, Value>) {
+    if !is_rate_limited(resp) {
+        return;
+    }
+    if let Some(secs) = header_u64(resp, "retry-after") {
+        obj.insert("retry_after".into(), json!(secs));
+    }
+    if let Some(reset) = header_u64(resp, "x-ratelimit-reset") {
+        obj.insert("rate_limit_reset".into(), json!(time::format(reset as i64)));
+    }
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Remaining quota per resource (core, search, graphql, ...). Querying
+/// `/rate_limit` does not itself count against the limit.
+pub(crate) fn get_rate_limit(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let data = github_get(token, "/rate_limit")?;
+    let Some(resources) = data.get("resources").and_then(|r| r.as_object()) else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let only = args.get("resource").and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let now = time::now();
+    let mut out = Map::new();
+    for (name, limit) in resources {
+        if only.is_some_and(|r| r != name.as_str()) {
+            continue;
+        }
+        let reset = limit.get("reset").and_then(|r| r.as_i64()).unwrap_or(0);
+        out.insert(
+            name.clone(),
+            json!({
+                "limit": limit.get("limit"),
+                "remaining": limit.get("remaining"),
+                "used": limit.get("used"),
+                "reset_at": time::format(reset),
+                "reset_in_secs": (reset - now).max(0)
+            }),
+        );
+    }
+    if let Some(resource) = only.filter(|_| out.is_empty()) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("unknown rate limit resource: {resource}")}),
+        )));
+    }
+    Ok(Json(DataType::from_json(json!({"resources": out}))))
+}
@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+const TOKEN_PREFIXES: &[&str] = &["github_pat_", "ghp_", "gho_", "ghu_", "ghs_", "ghr_"];
+
+thread_local! {
+    static SECRET: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+// =============================================================================
+// Secret redaction
+// =============================================================================
+
+pub(crate) fn set_secret(token: &str) {
+    SECRET.with(|s| *s.borrow_mut() = token.to_string());
+}
+
+/// Masks the configured token, anything shaped like a GitHub token, and
+/// bearer credentials.
+pub(crate) fn redact(text: &str) -> String {
+    let secret = SECRET.with(|s| s.borrow().clone());
+    let mut out = if secret.len() >= 8 {
+        text.replace(&secret, REDACTED)
+    } else {
+        text.to_string()
+    };
+    for prefix in TOKEN_PREFIXES {
+        out = mask_after(&out, prefix, true);
+    }
+    for scheme in ["Bearer ", "bearer "] {
+        out = mask_after(&out, scheme, false);
+    }
+    out
+}
+
+// Replaces the credential-like run of characters following `marker`. With
+// `include_marker` the marker itself is part of the secret (token prefixes).
+fn mask_after(text: &str, marker: &str, include_marker: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(marker) {
+        let boundary = !matches!(rest[..pos].chars().next_back(), Some(c) if c.is_ascii_alphanumeric());
+        let after = &rest[pos + marker.len()..];
+        let run = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'))
+            .unwrap_or(after.len());
+        if boundary && run >= 8 {
+            out.push_str(&rest[..pos]);
+            if !include_marker {
+                out.push_str(marker);
+            }
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(&rest[..pos + marker.len()]);
+            rest = after;
+            continue;
+        }
+        rest = &after[run..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Redacts every string in a tool result, object keys included.
+pub(crate) fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            let clean = redact(s);
+            if clean != *s {
+                *s = clean;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::Object(obj) => {
+            let entries = std::mem::take(obj);
+            for (key, mut v) in entries {
+                redact_value(&mut v);
+                obj.insert(redact(&key), v);
+            }
+        }
+        _ => {}
+    }
+}
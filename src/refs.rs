@@ -0,0 +1,106 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::confirm::{self, Gate};
+use crate::{bool_arg, github_get, github_patch};
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Accepts "heads/main", "refs/heads/main", or "tags/v1.0"; the API wants the
+// form without the leading "refs/".
+fn ref_arg(args: &DataType) -> String {
+    let raw = args.get("ref").and_then(|v| v.as_str()).unwrap_or("");
+    raw.strip_prefix("refs/").unwrap_or(raw).to_string()
+}
+
+fn force_preview(token: &str, owner: &str, repo: &str, git_ref: &str, sha: &str) -> Result<Value, Error> {
+    let current = github_get(token, &format!("/repos/{owner}/{repo}/git/ref/{git_ref}"))?;
+    let Some(current_sha) = current.pointer("/object/sha").and_then(|v| v.as_str()) else {
+        return Ok(json!({"ref": git_ref, "current": current}));
+    };
+    // Commits reachable from the current tip but not from the new one are
+    // what the force update drops.
+    let compare = github_get(token, &format!("/repos/{owner}/{repo}/compare/{sha}...{current_sha}"))?;
+    let dropped: Vec<Value> = compare
+        .get("commits")
+        .and_then(|c| c.as_array())
+        .map(|commits| {
+            commits
+                .iter()
+                .map(|c| {
+                    json!({
+                        "sha": c.get("sha"),
+                        "message": c.pointer("/commit/message").and_then(|m| m.as_str()).and_then(|m| m.lines().next())
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(json!({
+        "ref": git_ref,
+        "current_sha": current_sha,
+        "new_sha": sha,
+        "fast_forward": compare.get("ahead_by").and_then(|v| v.as_u64()) == Some(0),
+        "commits_dropped": compare.get("ahead_by"),
+        "dropped": dropped
+    }))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn get_ref(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let git_ref = ref_arg(args);
+    if owner.is_empty() || repo.is_empty() || git_ref.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and ref are required"}),
+        )));
+    }
+    let data = github_get(token, &format!("/repos/{owner}/{repo}/git/ref/{git_ref}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Points a ref at `sha`. Non-forced updates must fast-forward; `force`
+/// goes through the confirmation handshake with a preview of the commits
+/// the ref would lose.
+pub(crate) fn update_ref(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let git_ref = ref_arg(args);
+    let sha = args.get("sha").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || git_ref.is_empty() || sha.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, ref, and sha are required"}),
+        )));
+    }
+    let force = bool_arg(args, "force");
+
+    if force {
+        match confirm::gate(&format!("update_ref:{owner}/{repo}:{git_ref}:{sha}"), args) {
+            Gate::Proceed => {}
+            Gate::Invalid(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+            Gate::Pending(confirm_token) => {
+                let preview = force_preview(token, owner, repo, &git_ref, sha)?;
+                return Ok(Json(DataType::from_json(json!({
+                    "confirmation_required": true,
+                    "confirm_token": confirm_token,
+                    "preview": preview,
+                    "message": "Review the preview, then call again with confirm_token to force-update the ref"
+                }))));
+            }
+        }
+    }
+
+    let data = github_patch(
+        token,
+        &format!("/repos/{owner}/{repo}/git/refs/{git_ref}"),
+        &json!({"sha": sha, "force": force}),
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
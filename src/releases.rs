@@ -0,0 +1,378 @@
+use std::cmp::Ordering;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::branches::resolve_sha;
+use crate::composite::{ci_status, get_found, get_opt};
+use crate::templates::fetch_text;
+use crate::{github_get, url_encode};
+
+const DEFAULT_BLOCKER_LABEL: &str = "release-blocker";
+const CHANGELOG_FILES: &[&str] = &["CHANGELOG.md", "CHANGELOG", "CHANGES.md", "HISTORY.md"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn summary(release: &Value) -> Value {
+    json!({
+        "tag_name": release.get("tag_name"),
+        "name": release.get("name"),
+        "published_at": release.get("published_at"),
+        "prerelease": release.get("prerelease"),
+        "html_url": release.get("html_url")
+    })
+}
+
+// The release published just before `tag`, ignoring drafts.
+fn previous_release(token: &str, owner: &str, repo: &str, tag: &str) -> Result<Option<Value>, Error> {
+    let data = github_get(token, &format!("/repos/{owner}/{repo}/releases?per_page=100"))?;
+    let Value::Array(releases) = data else {
+        return Ok(None);
+    };
+    let published: Vec<&Value> = releases
+        .iter()
+        .filter(|r| r.get("draft") != Some(&json!(true)))
+        .collect();
+    let idx = published
+        .iter()
+        .position(|r| r.get("tag_name").and_then(|t| t.as_str()) == Some(tag));
+    Ok(idx.and_then(|i| published.get(i + 1)).map(|r| (*r).clone()))
+}
+
+// PR number a commit message points at: merge commits ("Merge pull request
+// #12 from ...") and squash merges ("Title (#12)").
+fn pr_number(message: &str) -> Option<u64> {
+    let first = message.lines().next().unwrap_or("");
+    if let Some(rest) = first.strip_prefix("Merge pull request #") {
+        return rest.split_whitespace().next()?.parse().ok();
+    }
+    let start = first.rfind("(#")?;
+    first[start + 2..].strip_suffix(')')?.parse().ok()
+}
+
+fn check(name: &str, status: &str, detail: String) -> Value {
+    json!({"check": name, "status": status, "detail": detail})
+}
+
+// Message of a GitHub error body or of the uniform error envelope.
+fn error_message(error: &Value) -> String {
+    error
+        .get("message")
+        .or_else(|| error.pointer("/error/message"))
+        .and_then(|m| m.as_str())
+        .unwrap_or("request failed")
+        .to_string()
+}
+
+// `version = "..."` from the first of `sections` that sets it.
+fn toml_version(text: &str, sections: &[&str]) -> Option<String> {
+    let mut section = "";
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim();
+            continue;
+        }
+        if !sections.contains(&section) {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            if key.trim() == "version" && !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+// The version declared at `sha` by the first manifest found, and its path.
+fn manifest_version(token: &str, owner: &str, repo: &str, sha: &str) -> Option<(String, &'static str)> {
+    let fetch = |path: &str| fetch_text(token, owner, repo, path, Some(sha));
+    if let Some(v) = fetch("Cargo.toml").and_then(|t| toml_version(&t, &["package", "workspace.package"])) {
+        return Some((v, "Cargo.toml"));
+    }
+    if let Some(text) = fetch("package.json") {
+        let manifest: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+        if let Some(v) = manifest.get("version").and_then(|v| v.as_str()) {
+            return Some((v.to_string(), "package.json"));
+        }
+    }
+    if let Some(v) = fetch("pyproject.toml").and_then(|t| toml_version(&t, &["project", "tool.poetry"])) {
+        return Some((v, "pyproject.toml"));
+    }
+    let v = fetch("VERSION")?.trim().to_string();
+    (!v.is_empty()).then_some((v, "VERSION"))
+}
+
+// Numeric core (padded to three parts) and whether it's a pre-release.
+fn version_key(version: &str) -> Option<(Vec<u64>, bool)> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let core_end = version.find(['-', '+']).unwrap_or(version.len());
+    let mut core: Vec<u64> = version[..core_end]
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    core.resize(core.len().max(3), 0);
+    Some((core, version[core_end..].starts_with('-')))
+}
+
+// Semver-ish ordering: numeric parts, then a release above its pre-releases.
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = version_key(a)?;
+    let (b_core, b_pre) = version_key(b)?;
+    Some(a_core.cmp(&b_core).then(b_pre.cmp(&a_pre)))
+}
+
+// Whether `text` names `version` as a whole token, so 1.2.3 doesn't match
+// 11.2.3 or 1.2.30.
+fn mentions_version(text: &str, version: &str) -> bool {
+    let bytes = text.as_bytes();
+    text.match_indices(version).any(|(i, _)| {
+        let end = i + version.len();
+        let before = i.checked_sub(1).map(|j| bytes[j]);
+        let after = bytes.get(end).copied();
+        let after_digit = bytes.get(end + 1).is_some_and(|c| c.is_ascii_digit());
+        !before.is_some_and(|c| c.is_ascii_digit() || c == b'.')
+            && !after.is_some_and(|c| c.is_ascii_digit() || (c == b'.' && after_digit))
+    })
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Commits and merged PRs between two release tags. `from` defaults to the
+/// release published before `to`.
+pub(crate) fn compare_releases(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let to = args.get("to").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || to.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and to are required"}),
+        )));
+    }
+    let Some(to_release) = get_opt(
+        token,
+        &format!("/repos/{owner}/{repo}/releases/tags/{}", url_encode(to)),
+    ) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("no release tagged {to}")}),
+        )));
+    };
+    let from_release = match args.get("from").and_then(|v| v.as_str()).filter(|f| !f.is_empty()) {
+        Some(from) => get_opt(
+            token,
+            &format!("/repos/{owner}/{repo}/releases/tags/{}", url_encode(from)),
+        ),
+        None => previous_release(token, owner, repo, to)?,
+    };
+    let Some(from_release) = from_release else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("could not resolve the release to compare {to} against")}),
+        )));
+    };
+    let from = from_release.get("tag_name").and_then(|t| t.as_str()).unwrap_or("");
+
+    let compare = github_get(
+        token,
+        &format!(
+            "/repos/{owner}/{repo}/compare/{}...{}",
+            url_encode(from),
+            url_encode(to)
+        ),
+    )?;
+    let Some(commits) = compare.get("commits").and_then(|c| c.as_array()) else {
+        return Ok(Json(DataType::from_json(compare)));
+    };
+
+    let mut pulls: Vec<Value> = Vec::new();
+    let entries: Vec<Value> = commits
+        .iter()
+        .map(|c| {
+            let message = c.pointer("/commit/message").and_then(|m| m.as_str()).unwrap_or("");
+            let title = message.lines().next().unwrap_or("");
+            if let Some(number) = pr_number(message) {
+                if !pulls.iter().any(|p| p["number"] == number) {
+                    // Merge commits carry the PR title on the line after the blank one.
+                    let pr_title = if title.starts_with("Merge pull request #") {
+                        message.lines().nth(2).unwrap_or(title)
+                    } else {
+                        title
+                    };
+                    pulls.push(json!({"number": number, "title": pr_title, "merge_commit": c.get("sha")}));
+                }
+            }
+            json!({
+                "sha": c.get("sha"),
+                "message": title,
+                "author": c.pointer("/author/login").or_else(|| c.pointer("/commit/author/name"))
+            })
+        })
+        .collect();
+    let total = compare
+        .get("total_commits")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(entries.len() as u64);
+
+    Ok(Json(DataType::from_json(json!({
+        "from": summary(&from_release),
+        "to": summary(&to_release),
+        "status": compare.get("status"),
+        "total_commits": total,
+        // Compare returns at most 250 commits.
+        "complete": total as usize == entries.len(),
+        "merged_prs": pulls,
+        "commits": entries,
+        "files_changed": compare.get("files").and_then(|f| f.as_array()).map(|f| f.len()),
+        "html_url": compare.get("html_url")
+    }))))
+}
+
+/// Pass/fail checklist for releasing `ref` (default branch by default): CI
+/// green, no open issues labeled `blocker_label`, a changelog entry for the
+/// version, and the version bumped past the latest release tag. `version`
+/// overrides the one read from Cargo.toml, package.json, pyproject.toml, or
+/// VERSION. Checks that don't apply report `skip`.
+pub(crate) fn release_readiness(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let git_ref = match args.get("ref").and_then(|v| v.as_str()).filter(|r| !r.is_empty()) {
+        Some(r) => r.to_string(),
+        None => {
+            let meta = github_get(token, &format!("/repos/{owner}/{repo}"))?;
+            match meta.get("default_branch").and_then(|b| b.as_str()) {
+                Some(branch) => branch.to_string(),
+                None => return Ok(Json(DataType::from_json(meta))),
+            }
+        }
+    };
+    let Some(sha) = resolve_sha(token, owner, repo, &git_ref)? else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("could not resolve ref {git_ref}")}),
+        )));
+    };
+    let mut checks = Vec::new();
+
+    let ci = ci_status(token, owner, repo, &sha);
+    checks.push(match ci["state"].as_str().unwrap_or("none") {
+        "success" => check("ci", "pass", format!("{} checks passed", ci["success"])),
+        "failure" => check("ci", "fail", format!("{} checks failed", ci["failure"])),
+        "pending" => check("ci", "fail", format!("{} checks still running", ci["pending"])),
+        "unknown" if ci["truncated"] == true => check("ci", "fail", "too many check runs to read them all".to_string()),
+        "unknown" => check(
+            "ci",
+            "fail",
+            format!(
+                "could not read CI status: {}",
+                ci["errors"]
+                    .as_array()
+                    .and_then(|e| e.first())
+                    .map(error_message)
+                    .unwrap_or_default()
+            ),
+        ),
+        _ => check("ci", "skip", "no statuses or check runs reported".to_string()),
+    });
+
+    let label = args
+        .get("blocker_label")
+        .and_then(|v| v.as_str())
+        .filter(|l| !l.is_empty())
+        .unwrap_or(DEFAULT_BLOCKER_LABEL);
+    let blockers = get_opt(
+        token,
+        &format!(
+            "/repos/{owner}/{repo}/issues?state=open&labels={}&per_page=100",
+            url_encode(label)
+        ),
+    );
+    let blockers: Option<Vec<Value>> = blockers.and_then(|b| b.as_array().cloned()).map(|issues| {
+        issues
+            .iter()
+            .map(|i| json!({"number": i.get("number"), "title": i.get("title"), "html_url": i.get("html_url")}))
+            .collect()
+    });
+    checks.push(match &blockers {
+        Some(open) if open.is_empty() => check("blockers", "pass", format!("no open `{label}` issues")),
+        Some(open) => check("blockers", "fail", format!("{} open `{label}` issues", open.len())),
+        None => check("blockers", "fail", format!("could not list `{label}` issues")),
+    });
+
+    let version = match args.get("version").and_then(|v| v.as_str()).filter(|v| !v.is_empty()) {
+        Some(v) => Some((v.to_string(), "args")),
+        None => manifest_version(token, owner, repo, &sha),
+    };
+    // Only a 404 means there is no release yet.
+    let latest = get_found(token, &format!("/repos/{owner}/{repo}/releases/latest"));
+    let latest_tag = latest
+        .as_ref()
+        .ok()
+        .flatten()
+        .and_then(|r| r.get("tag_name"))
+        .and_then(|t| t.as_str())
+        .map(str::to_string);
+
+    let changelog_paths: Vec<&str> = match args.get("changelog").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        Some(path) => vec![path],
+        None => CHANGELOG_FILES.to_vec(),
+    };
+    let changelog = changelog_paths
+        .iter()
+        .find_map(|path| fetch_text(token, owner, repo, path, Some(&sha)).map(|text| (*path, text)));
+    checks.push(match (&changelog, &version) {
+        (None, _) => check(
+            "changelog",
+            "fail",
+            format!("no changelog found (looked for {})", changelog_paths.join(", ")),
+        ),
+        (Some((path, text)), Some((v, _))) if mentions_version(text, v) => {
+            check("changelog", "pass", format!("{path} has an entry for {v}"))
+        }
+        (Some((path, _)), Some((v, _))) => check("changelog", "fail", format!("{path} has no entry for {v}")),
+        (Some((path, _)), None) => check(
+            "changelog",
+            "fail",
+            format!("{path} found, but the version is unknown; pass `version`"),
+        ),
+    });
+
+    checks.push(match (&version, &latest_tag) {
+        _ if latest.is_err() => {
+            let error = latest.as_ref().err().map(error_message).unwrap_or_default();
+            check("version", "fail", format!("could not read the latest release: {error}"))
+        }
+        (None, _) => check(
+            "version",
+            "fail",
+            "could not read a version from Cargo.toml, package.json, pyproject.toml, or VERSION; pass `version`"
+                .to_string(),
+        ),
+        (Some((v, _)), None) => check("version", "pass", format!("{v} (no previous release)")),
+        (Some((v, source)), Some(tag)) => match compare_versions(v, tag) {
+            Some(Ordering::Greater) => check("version", "pass", format!("{v} ({source}) is newer than {tag}")),
+            Some(_) => check("version", "fail", format!("{v} ({source}) is not newer than {tag}")),
+            None => check("version", "fail", format!("can't compare {v} with {tag}")),
+        },
+    });
+
+    let ready = checks.iter().all(|c| c["status"] != "fail");
+    Ok(Json(DataType::from_json(json!({
+        "ref": git_ref,
+        "sha": sha,
+        "ready": ready,
+        "version": version.as_ref().map(|(v, _)| v),
+        "latest_release": latest_tag,
+        "checks": checks,
+        "blockers": blockers,
+        "ci": ci
+    }))))
+}
@@ -0,0 +1,116 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::confirm::{self, Gate};
+use crate::{github_get, github_patch};
+
+const VISIBILITIES: &[&str] = &["public", "private", "internal"];
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Changes repository visibility behind the confirmation handshake. The
+/// first call only reports what the change would expose or break.
+pub(crate) fn set_repo_visibility(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let visibility = args.get("visibility").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || !VISIBILITIES.contains(&visibility) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and visibility (public, private, or internal) are required"}),
+        )));
+    }
+
+    match confirm::gate(&format!("set_repo_visibility:{owner}/{repo}:{visibility}"), args) {
+        Gate::Proceed => {}
+        Gate::Invalid(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+        Gate::Pending(confirm_token) => {
+            let preview = visibility_preview(token, owner, repo, visibility)?;
+            return Ok(Json(DataType::from_json(json!({
+                "confirmation_required": true,
+                "confirm_token": confirm_token,
+                "preview": preview,
+                "message": "Review the preview, then call again with confirm_token to apply the change"
+            }))));
+        }
+    }
+
+    let data = github_patch(
+        token,
+        &format!("/repos/{owner}/{repo}"),
+        &json!({"visibility": visibility}),
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+fn visibility_preview(token: &str, owner: &str, repo: &str, target: &str) -> Result<Value, Error> {
+    let meta = github_get(token, &format!("/repos/{owner}/{repo}"))?;
+    let current = meta
+        .get("visibility")
+        .and_then(|v| v.as_str())
+        .unwrap_or(if meta.get("private").and_then(|v| v.as_bool()) == Some(true) {
+            "private"
+        } else {
+            "public"
+        })
+        .to_string();
+
+    let mut warnings = Vec::new();
+    let mut secret_alerts = Value::Null;
+    if current != "public" && target == "public" {
+        warnings.push(
+            "All branches, full commit history, issues, releases, and Actions logs become world-readable".to_string(),
+        );
+        warnings.push("Private forks are detached into standalone private repositories".to_string());
+        let alerts = github_get(
+            token,
+            &format!("/repos/{owner}/{repo}/secret-scanning/alerts?state=open&per_page=100"),
+        )?;
+        secret_alerts = match alerts.as_array() {
+            Some(alerts) => {
+                if !alerts.is_empty() {
+                    warnings.push(format!(
+                        "{} open secret scanning alert(s): those secrets will be publicly visible in history",
+                        alerts.len()
+                    ));
+                }
+                json!({
+                    "open": alerts.len(),
+                    "secret_types": alerts
+                        .iter()
+                        .filter_map(|a| a.get("secret_type_display_name").or_else(|| a.get("secret_type")).cloned())
+                        .collect::<Vec<_>>()
+                })
+            }
+            // Secret scanning disabled or not visible to this token.
+            None => json!({"unavailable": alerts.get("message")}),
+        };
+        if secret_alerts.get("unavailable").is_some() {
+            warnings.push(
+                "Secret scanning results are unavailable; history has not been checked for leaked secrets".to_string(),
+            );
+        }
+    }
+    if current == "public" && target != "public" {
+        warnings.push(
+            "Stars and watchers are removed; existing public forks stay public in a separate network".to_string(),
+        );
+    }
+    if target == "internal" && meta.pointer("/owner/type").and_then(|v| v.as_str()) != Some("Organization") {
+        warnings.push("internal visibility is only available to organization repositories on Enterprise".to_string());
+    }
+
+    Ok(json!({
+        "repository": meta.get("full_name"),
+        "current_visibility": current,
+        "target_visibility": target,
+        "no_change": current == target,
+        "forks_count": meta.get("forks_count"),
+        "has_pages": meta.get("has_pages"),
+        "has_wiki": meta.get("has_wiki"),
+        "secret_scanning": secret_alerts,
+        "warnings": warnings
+    }))
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::codeowners::CodeOwners;
+use crate::composite::{get_all, get_opt, search_count};
+use crate::{u64_arg, url_encode};
+
+const DEFAULT_MAX_FILES: u64 = 30;
+const DEFAULT_LIMIT: u64 = 5;
+const HISTORY_DEPTH: u32 = 10;
+
+#[derive(Default)]
+struct Candidate {
+    owned_files: u64,
+    recent_commits: u64,
+    teams: bool,
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Ranks reviewers for a PR. Each changed file gives its CODEOWNERS three
+/// points and each recent author of the file (the REST stand-in for blame)
+/// one point per commit; scores are then divided by `1 + open_reviews / 5`
+/// so people already buried in review requests sink.
+pub(crate) fn suggest_reviewers(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let max_files = u64_arg(args, "max_files").unwrap_or(DEFAULT_MAX_FILES) as usize;
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_LIMIT) as usize;
+
+    let Some(pr) = get_opt(token, &format!("/repos/{owner}/{repo}/pulls/{number}")) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("pull request #{number} not found")}),
+        )));
+    };
+    let author = pr
+        .pointer("/user/login")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let base = pr.pointer("/base/ref").and_then(|v| v.as_str());
+    let requested: Vec<String> = pr
+        .get("requested_reviewers")
+        .and_then(|r| r.as_array())
+        .map(|r| {
+            r.iter()
+                .filter_map(|u| u.get("login").and_then(|l| l.as_str()).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let files = match get_all(token, &format!("/repos/{owner}/{repo}/pulls/{number}/files")) {
+        Ok(listing) => listing.items,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let files: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.get("filename").and_then(|v| v.as_str()).map(str::to_string))
+        .collect();
+    let codeowners = CodeOwners::fetch(token, owner, repo, base);
+
+    let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+    for file in files.iter().take(max_files) {
+        if let Some(co) = &codeowners {
+            for name in co.owners_of(file) {
+                let entry = candidates.entry(name.trim_start_matches('@').to_string()).or_default();
+                entry.owned_files += 1;
+                entry.teams = name.contains('/');
+            }
+        }
+        let history = get_opt(
+            token,
+            &format!(
+                "/repos/{owner}/{repo}/commits?path={}&per_page={HISTORY_DEPTH}",
+                url_encode(file)
+            ),
+        );
+        for commit in history.as_ref().and_then(|h| h.as_array()).into_iter().flatten() {
+            if let Some(login) = commit.pointer("/author/login").and_then(|v| v.as_str()) {
+                candidates.entry(login.to_string()).or_default().recent_commits += 1;
+            }
+        }
+    }
+    candidates.remove(&author);
+
+    let mut ranked: Vec<(String, Candidate, f64)> = candidates
+        .into_iter()
+        .filter(|(name, _)| !name.ends_with("[bot]"))
+        .map(|(name, c)| {
+            let score = (c.owned_files * 3 + c.recent_commits) as f64;
+            (name, c, score)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    // Review load costs a search per person, so only check the front runners.
+    let mut candidates: Vec<Value> = ranked
+        .into_iter()
+        .take(limit * 2)
+        .map(|(name, c, score)| {
+            let load = if c.teams {
+                None
+            } else {
+                search_count(token, &format!("type:pr+state:open+review-requested:{name}"))
+            };
+            let adjusted = score / (1.0 + load.unwrap_or(0) as f64 / 5.0);
+            json!({
+                "login": if c.teams { Value::Null } else { json!(name) },
+                "team": if c.teams { json!(name) } else { Value::Null },
+                "score": (adjusted * 100.0).round() / 100.0,
+                "owned_files": c.owned_files,
+                "recent_commits": c.recent_commits,
+                "open_review_requests": load,
+                "already_requested": requested.contains(&name)
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b["score"]
+            .as_f64()
+            .unwrap_or(0.0)
+            .total_cmp(&a["score"].as_f64().unwrap_or(0.0))
+    });
+    candidates.truncate(limit);
+
+    Ok(Json(DataType::from_json(json!({
+        "pr": number,
+        "author": author,
+        "files_considered": files.len().min(max_files),
+        "files_total": files.len(),
+        "codeowners": codeowners.as_ref().map(|c| c.path.clone()),
+        "candidates": candidates
+    }))))
+}
@@ -0,0 +1,333 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{bool_arg, contents, github_get, github_list, u64_arg, url_encode};
+
+// Keywords that introduce a named definition across the common languages.
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "trait",
+    "type",
+    "const",
+    "static",
+    "mod",
+    "union",
+    "macro_rules!",
+    "def",
+    "class",
+    "module",
+    "func",
+    "function",
+    "interface",
+    "record",
+    "fun",
+    "object",
+    "let",
+    "var",
+    "val",
+    "#define",
+    "typedef",
+];
+
+// Leading modifiers skipped before the keyword (`pub(crate) async fn`).
+const MODIFIERS: &[&str] = &[
+    "pub",
+    "export",
+    "default",
+    "async",
+    "unsafe",
+    "extern",
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "abstract",
+    "final",
+    "sealed",
+    "open",
+    "override",
+    "data",
+    "inline",
+    "declare",
+];
+
+const MAX_DEFINITION_FILES: u64 = 30;
+
+const ISSUE_SORTS: &[&str] = &[
+    "comments",
+    "reactions",
+    "reactions-+1",
+    "reactions--1",
+    "reactions-smile",
+    "reactions-thinking_face",
+    "reactions-heart",
+    "reactions-tada",
+    "interactions",
+    "created",
+    "updated",
+];
+const REPO_SORTS: &[&str] = &["stars", "forks", "help-wanted-issues", "updated"];
+const USER_SORTS: &[&str] = &["followers", "repositories", "joined"];
+const CODE_SORTS: &[&str] = &["indexed"];
+
+// =============================================================================
+// Client-side ranking and deduplication of search results
+// =============================================================================
+
+// Repository an item belongs to: code results carry `repository`, issue
+// results only `repository_url`, and repository results are the repo.
+fn repo_of(item: &Value) -> Option<&str> {
+    item.pointer("/repository/full_name")
+        .and_then(|v| v.as_str())
+        .or_else(|| {
+            item.get("repository_url")
+                .and_then(|v| v.as_str())
+                .and_then(|url| url.split("/repos/").nth(1))
+        })
+        .or_else(|| item.get("full_name").and_then(|v| v.as_str()))
+}
+
+// Numbers compare numerically, strings lexically (ISO timestamps sort
+// correctly that way), and missing keys sort last either way.
+fn compare_keys(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => {
+            x.as_f64().partial_cmp(&y.as_f64()).unwrap_or(Ordering::Equal)
+        }
+        (Some(Value::String(x)), Some(Value::String(y))) => x.cmp(y),
+        (Some(Value::Bool(x)), Some(Value::Bool(y))) => x.cmp(y),
+        _ => Ordering::Equal,
+    }
+}
+
+/// Applies `sort_by` (dotted key path, e.g. `repository.stargazers_count`)
+/// with `order` (desc by default), then `max_per_repo` (or `dedupe_by_repo`,
+/// which means one per repo) to the `items` of a search envelope. Sorting
+/// runs first so each repo keeps its best results. Only the fetched page is
+/// affected.
+pub(crate) fn refine(data: &mut Value, args: &DataType) {
+    let sort_by = args.get("sort_by").and_then(|v| v.as_str()).unwrap_or("");
+    let per_repo = match u64_arg(args, "max_per_repo") {
+        Some(n) if n > 0 => Some(n as usize),
+        _ if bool_arg(args, "dedupe_by_repo") => Some(1),
+        _ => None,
+    };
+    if sort_by.is_empty() && per_repo.is_none() {
+        return;
+    }
+    let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) else {
+        return;
+    };
+
+    if !sort_by.is_empty() {
+        let pointer = format!("/{}", sort_by.replace('.', "/"));
+        let ascending = args.get("order").and_then(|v| v.as_str()) == Some("asc");
+        items.sort_by(|a, b| {
+            let (ka, kb) = (a.pointer(&pointer), b.pointer(&pointer));
+            match (ka.is_some(), kb.is_some()) {
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                _ if ascending => compare_keys(ka, kb),
+                _ => compare_keys(kb, ka),
+            }
+        });
+    }
+
+    let before = items.len();
+    if let Some(cap) = per_repo {
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        items.retain(|item| {
+            let Some(repo) = repo_of(item) else {
+                return true;
+            };
+            let count = seen.entry(repo.to_string()).or_insert(0);
+            *count += 1;
+            *count <= cap
+        });
+    }
+    let dropped = before - items.len();
+    data["refined"] = json!({"sort_by": sort_by, "max_per_repo": per_repo, "dropped": dropped});
+}
+
+// =============================================================================
+// Search endpoints
+// =============================================================================
+
+/// Runs `query` against `/search/{kind}`. The query is percent-encoded as a
+/// whole, so qualifiers with quotes, colons, `#`, or `+` survive intact.
+/// `sort` (one of `sorts`) and `order` are applied server-side across all
+/// results; `sort_by` and friends then refine the fetched page.
+pub(crate) fn search(
+    token: &str,
+    args: &DataType,
+    kind: &str,
+    sorts: &[&str],
+    default_per_page: u64,
+) -> FnResult<Json<DataType>> {
+    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("").trim();
+    if query.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "query is required"}))));
+    }
+    let mut path = format!("/search/{kind}?q={}", url_encode(query));
+    if let Some(sort) = args.get("sort").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        if !sorts.contains(&sort) {
+            return Ok(Json(DataType::from_json(
+                json!({"error": format!("sort must be one of: {}", sorts.join(", "))}),
+            )));
+        }
+        path.push_str(&format!("&sort={}", url_encode(sort)));
+        match args.get("order").and_then(|v| v.as_str()).unwrap_or("desc") {
+            order @ ("asc" | "desc") => path.push_str(&format!("&order={order}")),
+            _ => return Ok(Json(DataType::from_json(json!({"error": "order must be asc or desc"})))),
+        }
+    }
+    let mut data = github_list(
+        token,
+        &path,
+        &Paging::from_args(args, default_per_page),
+        &Fields::from_args(args),
+    )?;
+    refine(&mut data, args);
+    Ok(Json(DataType::from_json(data)))
+}
+
+// =============================================================================
+// Definition matching
+// =============================================================================
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// The keyword when `line` defines `symbol` (`fn symbol`, `class symbol`,
+/// `func (r *T) symbol`, ...), matched on whole identifiers only.
+fn definition_kind(line: &str, symbol: &str) -> Option<&'static str> {
+    let mut rest = line.trim_start();
+    loop {
+        let word = rest.split(|c: char| c.is_whitespace()).next().unwrap_or("");
+        let bare = word.split('(').next().unwrap_or(word);
+        if !word.is_empty() && MODIFIERS.contains(&bare) {
+            rest = rest[word.len()..].trim_start();
+            // `extern "C"` carries an ABI string.
+            if rest.starts_with('"') {
+                rest = rest[1..].split_once('"').map_or("", |(_, r)| r).trim_start();
+            }
+            continue;
+        }
+        break;
+    }
+    let keyword = DEFINITION_KEYWORDS.iter().find(|kw| {
+        rest.strip_prefix(**kw)
+            .is_some_and(|r| r.starts_with(char::is_whitespace))
+    })?;
+    let mut name = rest[keyword.len()..].trim_start();
+    // Go methods: `func (r *Receiver) Name(`.
+    if *keyword == "func" && name.starts_with('(') {
+        name = name.split_once(')').map_or("", |(_, r)| r).trim_start();
+    }
+    let after = name.strip_prefix(symbol)?;
+    (!after.starts_with(is_ident)).then_some(*keyword)
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn search_code(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    search(token, args, "code", CODE_SORTS, 20)
+}
+
+/// Issues and PRs (narrow with `is:issue` / `is:pr` in the query).
+pub(crate) fn search_issues(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    search(token, args, "issues", ISSUE_SORTS, 30)
+}
+
+pub(crate) fn search_repos(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    search(token, args, "repositories", REPO_SORTS, 30)
+}
+
+pub(crate) fn search_users(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    search(token, args, "users", USER_SORTS, 30)
+}
+
+/// Candidate definition sites of `symbol` in a repo (`owner`/`repo`) or an
+/// org. REST code search has no `symbol:` qualifier, so files mentioning the
+/// symbol are searched (narrowed by `language`) and then scanned for lines
+/// that define it, returned with `context_lines` of surrounding code.
+pub(crate) fn find_definition(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let symbol = args.get("symbol").and_then(|v| v.as_str()).unwrap_or("").trim();
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let scope = match (owner.is_empty() || repo.is_empty(), org.is_empty()) {
+        (false, _) => format!("repo:{owner}/{repo}"),
+        (true, false) => format!("org:{org}"),
+        (true, true) => String::new(),
+    };
+    if symbol.is_empty() || scope.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "symbol and either owner/repo or org are required"}),
+        )));
+    }
+    if !symbol.chars().all(is_ident) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "symbol must be a single identifier"}),
+        )));
+    }
+    let mut query = format!("{symbol} {scope}");
+    if let Some(language) = args.get("language").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        query.push_str(&format!(" language:{language}"));
+    }
+    let max_files = u64_arg(args, "max_files").unwrap_or(10).clamp(1, MAX_DEFINITION_FILES);
+    let context = u64_arg(args, "context_lines").unwrap_or(3).min(20) as usize;
+
+    let path = format!("/search/code?q={}", url_encode(&query));
+    let found = github_list(token, &path, &Paging::single(1, max_files), &Fields::default())?;
+    let Some(items) = found.get("items").and_then(|i| i.as_array()) else {
+        return Ok(Json(DataType::from_json(found)));
+    };
+
+    let mut definitions = Vec::new();
+    for item in items {
+        let Some(url) = item.get("url").and_then(|u| u.as_str()) else {
+            continue;
+        };
+        let file = github_get(token, url)?;
+        let Some(text) = contents::decode(&file).and_then(|b| String::from_utf8(b).ok()) else {
+            continue;
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            let Some(kind) = definition_kind(line, symbol) else {
+                continue;
+            };
+            let start = i.saturating_sub(context);
+            let end = (i + context + 1).min(lines.len());
+            let html_url = item.get("html_url").and_then(|u| u.as_str()).unwrap_or("");
+            definitions.push(json!({
+                "repository": item.pointer("/repository/full_name"),
+                "path": item.get("path"),
+                "line": i + 1,
+                "kind": kind,
+                "text": line.trim(),
+                "html_url": format!("{html_url}#L{}", i + 1),
+                "context": {"start_line": start + 1, "lines": lines[start..end]}
+            }));
+        }
+    }
+    Ok(Json(DataType::from_json(json!({
+        "symbol": symbol,
+        "query": query,
+        "files_searched": items.len(),
+        "total_matching_files": found.pointer("/page_info/total_hint"),
+        "definitions": definitions
+    }))))
+}
@@ -0,0 +1,317 @@
+use base64::Engine;
+use crypto_box::aead::OsRng;
+use crypto_box::PublicKey;
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_get, github_list, github_patch, github_post, github_put, str_list_arg, url_encode};
+
+const VISIBILITIES: &[&str] = &["all", "private", "selected"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Encrypts `value` as a libsodium sealed box for the scope's public key
+/// (`{base}/public-key`), as GitHub requires for every secret write.
+/// Returns `(key_id, encrypted_value)`.
+pub(crate) fn seal(token: &str, base: &str, value: &str) -> Result<(String, String), String> {
+    let key = github_get(token, &format!("{base}/public-key")).map_err(|e| e.to_string())?;
+    let (Some(key_id), Some(encoded)) = (
+        key.get("key_id").and_then(|v| v.as_str()),
+        key.get("key").and_then(|v| v.as_str()),
+    ) else {
+        let message = key
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("no public key returned");
+        return Err(format!("could not fetch public key: {message}"));
+    };
+    let engine = base64::engine::general_purpose::STANDARD;
+    let bytes: [u8; 32] = engine
+        .decode(encoded)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("malformed public key")?;
+    let sealed = PublicKey::from(bytes)
+        .seal(&mut OsRng, value.as_bytes())
+        .map_err(|e| format!("encryption failed: {e}"))?;
+    Ok((key_id.to_string(), engine.encode(sealed)))
+}
+
+// Selected repositories are given by name (resolved in `org`) or by id.
+fn repository_ids(token: &str, org: &str, repos: &[String]) -> Result<Vec<u64>, String> {
+    repos
+        .iter()
+        .map(|repo| match repo.parse::<u64>() {
+            Ok(id) => Ok(id),
+            Err(_) => github_get(token, &format!("/repos/{org}/{repo}"))
+                .ok()
+                .and_then(|r| r.get("id").and_then(|v| v.as_u64()))
+                .ok_or(format!("repository not found: {org}/{repo}")),
+        })
+        .collect()
+}
+
+fn org_and_name(args: &DataType) -> (&str, &str) {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    (org, name)
+}
+
+// Environment secrets have their own endpoints and public key.
+fn environment_base(args: &DataType) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let environment = args.get("environment").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || environment.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "/repos/{owner}/{repo}/environments/{}/secrets",
+        url_encode(environment)
+    ))
+}
+
+fn kind_path(kind: &str) -> Option<&'static str> {
+    match kind {
+        "secret" => Some("secrets"),
+        "variable" => Some("variables"),
+        _ => None,
+    }
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn list_org_secrets(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, _) = org_and_name(args);
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let path = format!("/orgs/{org}/actions/secrets");
+    let data = github_list(token, &path, &Paging::from_args(args, 100), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Creates or updates an org secret. `visibility` is all, private, or
+/// selected; with selected, `selected_repos` (names or ids) sets access.
+pub(crate) fn set_org_secret(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, name) = org_and_name(args);
+    let value = args.get("value").and_then(|v| v.as_str()).unwrap_or("");
+    let visibility = args.get("visibility").and_then(|v| v.as_str()).unwrap_or("private");
+    if org.is_empty() || name.is_empty() || value.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "org, name, and value are required"}),
+        )));
+    }
+    if !VISIBILITIES.contains(&visibility) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "visibility must be all, private, or selected"}),
+        )));
+    }
+    let base = format!("/orgs/{org}/actions/secrets");
+    let (key_id, encrypted) = match seal(token, &base, value) {
+        Ok(sealed) => sealed,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let mut body = json!({"encrypted_value": encrypted, "key_id": key_id, "visibility": visibility});
+    if visibility == "selected" {
+        match repository_ids(token, org, &str_list_arg(args, "selected_repos")) {
+            Ok(ids) => body["selected_repository_ids"] = json!(ids),
+            Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+        }
+    }
+    let data = github_put(token, &format!("{base}/{name}"), &body)?;
+    if data.get("message").is_some() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    // 201 on create, 204 on update; neither echoes the secret.
+    Ok(Json(DataType::from_json(
+        json!({"name": name, "visibility": visibility, "saved": true}),
+    )))
+}
+
+pub(crate) fn list_org_variables(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, _) = org_and_name(args);
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let path = format!("/orgs/{org}/actions/variables");
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Creates an org variable, or updates it in place if it already exists.
+pub(crate) fn set_org_variable(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, name) = org_and_name(args);
+    let value = args.get("value").and_then(|v| v.as_str()).unwrap_or("");
+    let visibility = args.get("visibility").and_then(|v| v.as_str()).unwrap_or("private");
+    if org.is_empty() || name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org and name are required"}))));
+    }
+    if !VISIBILITIES.contains(&visibility) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "visibility must be all, private, or selected"}),
+        )));
+    }
+    let mut body = json!({"name": name, "value": value, "visibility": visibility});
+    if visibility == "selected" {
+        match repository_ids(token, org, &str_list_arg(args, "selected_repos")) {
+            Ok(ids) => body["selected_repository_ids"] = json!(ids),
+            Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+        }
+    }
+    let base = format!("/orgs/{org}/actions/variables");
+    let exists = github_get(token, &format!("{base}/{name}")).is_ok_and(|v| v.get("value").is_some());
+    let data = if exists {
+        github_patch(token, &format!("{base}/{name}"), &body)?
+    } else {
+        github_post(token, &base, &body)?
+    };
+    if data.get("message").is_some() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    Ok(Json(DataType::from_json(
+        json!({"name": name, "visibility": visibility, "created": !exists, "saved": true}),
+    )))
+}
+
+pub(crate) fn get_org_selected_repos(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, name) = org_and_name(args);
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("secret");
+    let Some(segment) = kind_path(kind) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "kind must be secret or variable"}),
+        )));
+    };
+    if org.is_empty() || name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org and name are required"}))));
+    }
+    let data = github_get(
+        token,
+        &format!("/orgs/{org}/actions/{segment}/{name}/repositories?per_page=100"),
+    )?;
+    let Some(repos) = data.get("repositories").and_then(|r| r.as_array()) else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let repos: Vec<Value> = repos
+        .iter()
+        .map(|r| json!({"id": r.get("id"), "name": r.get("name")}))
+        .collect();
+    Ok(Json(DataType::from_json(
+        json!({"kind": kind, "name": name, "repositories": repos}),
+    )))
+}
+
+/// Replaces the repositories that can use a `selected`-visibility org
+/// secret or variable. `add`/`remove` edit the list one repo at a time.
+pub(crate) fn set_org_selected_repos(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let (org, name) = org_and_name(args);
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("secret");
+    let Some(segment) = kind_path(kind) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "kind must be secret or variable"}),
+        )));
+    };
+    if org.is_empty() || name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org and name are required"}))));
+    }
+    let base = format!("/orgs/{org}/actions/{segment}/{name}/repositories");
+
+    if args.get("repos").is_some() {
+        let ids = match repository_ids(token, org, &str_list_arg(args, "repos")) {
+            Ok(ids) => ids,
+            Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+        };
+        let data = github_put(token, &base, &json!({"selected_repository_ids": ids}))?;
+        return Ok(Json(DataType::from_json(data)));
+    }
+
+    let mut results = Vec::new();
+    for (key, method) in [("add", "PUT"), ("remove", "DELETE")] {
+        let ids = match repository_ids(token, org, &str_list_arg(args, key)) {
+            Ok(ids) => ids,
+            Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+        };
+        for id in ids {
+            let path = format!("{base}/{id}");
+            let resp = if method == "PUT" {
+                github_put(token, &path, &json!({}))
+            } else {
+                github_delete(token, &path)
+            };
+            let ok = matches!(&resp, Ok(d) if d.get("message").is_none());
+            results.push(json!({"repository_id": id, "action": key, "ok": ok}));
+        }
+    }
+    if results.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "repos, add, or remove is required"}),
+        )));
+    }
+    Ok(Json(DataType::from_json(
+        json!({"kind": kind, "name": name, "results": results}),
+    )))
+}
+
+pub(crate) fn list_environment_secrets(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(base) = environment_base(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and environment are required"}),
+        )));
+    };
+    let data = github_list(token, &base, &Paging::from_args(args, 100), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Creates or rotates a secret in one deployment environment, encrypted with
+/// that environment's own public key.
+pub(crate) fn set_environment_secret(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(base) = environment_base(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and environment are required"}),
+        )));
+    };
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let value = args.get("value").and_then(|v| v.as_str()).unwrap_or("");
+    if name.is_empty() || value.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "name and value are required"}),
+        )));
+    }
+    let (key_id, encrypted) = match seal(token, &base, value) {
+        Ok(sealed) => sealed,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let data = github_put(
+        token,
+        &format!("{base}/{name}"),
+        &json!({"encrypted_value": encrypted, "key_id": key_id}),
+    )?;
+    if data.get("message").is_some() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    let environment = args.get("environment").and_then(|v| v.as_str()).unwrap_or("");
+    Ok(Json(DataType::from_json(
+        json!({"environment": environment, "name": name, "saved": true}),
+    )))
+}
+
+pub(crate) fn delete_environment_secret(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(base) = environment_base(args) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and environment are required"}),
+        )));
+    };
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    if name.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "name is required"}))));
+    }
+    let data = github_delete(token, &format!("{base}/{name}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,247 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_get, github_list, github_patch, github_put, repos_arg, str_list_arg, u64_arg};
+
+const QUERY_SUITES: &[&str] = &["default", "extended"];
+const BYPASS_STATUSES: &[&str] = &[
+    "pending",
+    "approved",
+    "denied",
+    "expired",
+    "completed",
+    "cancelled",
+    "open",
+    "all",
+];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn flag(args: &DataType, key: &str) -> Option<bool> {
+    match args.get(key).map(|v| v.to_json()) {
+        Some(Value::Bool(b)) => Some(b),
+        Some(Value::String(s)) if s == "true" || s == "false" => Some(s == "true"),
+        _ => None,
+    }
+}
+
+fn outcome(result: Result<Value, Error>) -> Value {
+    match result {
+        Ok(data) if data.get("message").is_none() => json!({"ok": true}),
+        Ok(data) => json!({"ok": false, "error": data.get("message")}),
+        Err(e) => json!({"ok": false, "error": e.to_string()}),
+    }
+}
+
+// Vulnerability alerts and Dependabot security updates are on/off endpoints.
+fn toggle(token: &str, path: &str, enable: bool) -> Value {
+    if enable {
+        outcome(github_put(token, path, &json!({})))
+    } else {
+        outcome(github_delete(token, path))
+    }
+}
+
+fn status(enable: bool) -> Value {
+    json!({"status": if enable { "enabled" } else { "disabled" }})
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+pub(crate) fn get_code_scanning_setup(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let repos = repos_arg(args);
+    if repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or repos, are required"}),
+        )));
+    }
+    let results: Vec<Value> = repos
+        .iter()
+        .map(|(owner, repo)| {
+            let setup = github_get(token, &format!("/repos/{owner}/{repo}/code-scanning/default-setup"))
+                .unwrap_or_else(|e| json!({"message": e.to_string()}));
+            json!({
+                "repo": format!("{owner}/{repo}"),
+                "state": setup.get("state"),
+                "languages": setup.get("languages"),
+                "query_suite": setup.get("query_suite"),
+                "schedule": setup.get("schedule"),
+                "runner_type": setup.get("runner_type"),
+                "updated_at": setup.get("updated_at"),
+                "error": setup.get("message")
+            })
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"repos": results}))))
+}
+
+/// Switches repos to (or off) CodeQL default setup. `languages` defaults to
+/// whatever GitHub detects; the schedule is GitHub-managed (weekly) and
+/// cannot be changed through the API.
+pub(crate) fn update_code_scanning_setup(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let repos = repos_arg(args);
+    let state = args.get("state").and_then(|v| v.as_str()).unwrap_or("configured");
+    if repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or repos, are required"}),
+        )));
+    }
+    if state != "configured" && state != "not-configured" {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "state must be configured or not-configured"}),
+        )));
+    }
+    let mut body = json!({"state": state});
+    if let Some(suite) = args.get("query_suite").and_then(|v| v.as_str()) {
+        if !QUERY_SUITES.contains(&suite) {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "query_suite must be default or extended"}),
+            )));
+        }
+        body["query_suite"] = json!(suite);
+    }
+    let languages = str_list_arg(args, "languages");
+    if !languages.is_empty() {
+        body["languages"] = json!(languages);
+    }
+    if let Some(runner) = args.get("runner_type").and_then(|v| v.as_str()) {
+        body["runner_type"] = json!(runner);
+    }
+
+    let results: Vec<Value> = repos
+        .iter()
+        .map(|(owner, repo)| {
+            let path = format!("/repos/{owner}/{repo}/code-scanning/default-setup");
+            match github_patch(token, &path, &body) {
+                // 202 carries the run that applies the new configuration.
+                Ok(data) if data.get("message").is_none() => json!({
+                    "repo": format!("{owner}/{repo}"),
+                    "updated": true,
+                    "run_id": data.get("run_id"),
+                    "run_url": data.get("run_url")
+                }),
+                Ok(data) => json!({"repo": format!("{owner}/{repo}"), "updated": false, "error": data.get("message")}),
+                Err(e) => json!({"repo": format!("{owner}/{repo}"), "updated": false, "error": e.to_string()}),
+            }
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"requested": body, "repos": results}))))
+}
+
+/// Applies a security baseline to one repo or many: `vulnerability_alerts`,
+/// `dependabot_security_updates`, `secret_scanning`, and `push_protection`
+/// are each set when given and left alone otherwise. Dependabot updates
+/// depend on vulnerability alerts, so alerts are enabled first and disabled
+/// last.
+pub(crate) fn set_security_features(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let repos = repos_arg(args);
+    if repos.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or repos, are required"}),
+        )));
+    }
+    let alerts = flag(args, "vulnerability_alerts");
+    let updates = flag(args, "dependabot_security_updates");
+    let scanning = flag(args, "secret_scanning");
+    let push_protection = flag(args, "push_protection");
+    if alerts.is_none() && updates.is_none() && scanning.is_none() && push_protection.is_none() {
+        return Ok(Json(DataType::from_json(json!({
+            "error": "set at least one of vulnerability_alerts, dependabot_security_updates, secret_scanning, push_protection"
+        }))));
+    }
+
+    let results: Vec<Value> = repos
+        .iter()
+        .map(|(owner, repo)| {
+            let base = format!("/repos/{owner}/{repo}");
+            let mut result = json!({"repo": format!("{owner}/{repo}")});
+            if alerts == Some(true) {
+                result["vulnerability_alerts"] = toggle(token, &format!("{base}/vulnerability-alerts"), true);
+            }
+            if let Some(enable) = updates {
+                result["dependabot_security_updates"] =
+                    toggle(token, &format!("{base}/automated-security-fixes"), enable);
+            }
+            if alerts == Some(false) {
+                result["vulnerability_alerts"] = toggle(token, &format!("{base}/vulnerability-alerts"), false);
+            }
+            let mut analysis = json!({});
+            if let Some(enable) = scanning {
+                analysis["secret_scanning"] = status(enable);
+            }
+            if let Some(enable) = push_protection {
+                analysis["secret_scanning_push_protection"] = status(enable);
+            }
+            if analysis.as_object().is_some_and(|a| !a.is_empty()) {
+                let applied = outcome(github_patch(token, &base, &json!({"security_and_analysis": analysis})));
+                if scanning.is_some() {
+                    result["secret_scanning"] = applied.clone();
+                }
+                if push_protection.is_some() {
+                    result["push_protection"] = applied;
+                }
+            }
+            result
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({"repos": results}))))
+}
+
+/// Secret scanning push protection bypass requests for a repo or an org,
+/// open (awaiting review) ones by default.
+pub(crate) fn list_bypass_requests(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let status = args.get("status").and_then(|v| v.as_str()).unwrap_or("open");
+    if !BYPASS_STATUSES.contains(&status) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": format!("status must be one of: {}", BYPASS_STATUSES.join(", "))}),
+        )));
+    }
+    let base = if !owner.is_empty() && !repo.is_empty() {
+        format!("/repos/{owner}/{repo}/bypass-requests/secret-scanning")
+    } else if !org.is_empty() {
+        format!("/orgs/{org}/bypass-requests/secret-scanning")
+    } else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo, or org, are required"}),
+        )));
+    };
+    let path = format!("{base}?request_status={status}");
+    let data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::from_args(args))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Approves or denies a pending push protection bypass request.
+pub(crate) fn review_bypass_request(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let number = u64_arg(args, "number").unwrap_or(0);
+    let decision = args.get("decision").and_then(|v| v.as_str()).unwrap_or("");
+    let message = args.get("message").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() || number == 0 {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner, repo, and number are required"}),
+        )));
+    }
+    let status = match decision {
+        "approve" | "approved" => "approve",
+        "deny" | "denied" => "deny",
+        _ => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "decision must be approve or deny"}),
+            )))
+        }
+    };
+    let path = format!("/repos/{owner}/{repo}/bypass-requests/secret-scanning/{number}");
+    let data = github_patch(token, &path, &json!({"status": status, "message": message}))?;
+    Ok(Json(DataType::from_json(data)))
+}
@@ -0,0 +1,92 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_list, time, u64_arg, url_encode};
+
+const DEFAULT_STALE_DAYS: u64 = 90;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn days_since(ts: Option<&Value>, now: i64) -> Option<i64> {
+    let secs = time::parse(ts?.as_str()?)?;
+    Some((now - secs) / time::DAY)
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// SAML SSO authorized credentials (PATs, SSH keys) for an org, each flagged
+/// `stale` when unused for `stale_days` and `expired` past its expiry, with
+/// per-type and per-flag counts over the fetched page(s).
+pub(crate) fn list_credential_authorizations(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    if org.is_empty() {
+        return Ok(Json(DataType::from_json(json!({"error": "org is required"}))));
+    }
+    let mut path = format!("/orgs/{org}/credential-authorizations");
+    if let Some(login) = args.get("login").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) {
+        path.push_str(&format!("?login={}", url_encode(login)));
+    }
+    let stale_days = u64_arg(args, "stale_days").unwrap_or(DEFAULT_STALE_DAYS) as i64;
+    let mut data = github_list(token, &path, &Paging::from_args(args, 30), &Fields::default())?;
+    let Some(items) = data.get_mut("items").and_then(|i| i.as_array_mut()) else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+
+    let now = time::now();
+    let mut by_type = Map::new();
+    let (mut stale, mut expired, mut no_expiry) = (0u64, 0u64, 0u64);
+    for item in items.iter_mut() {
+        let kind = item
+            .get("credential_type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let count = by_type.get(&kind).and_then(|c| c.as_u64()).unwrap_or(0);
+        by_type.insert(kind, json!(count + 1));
+        let idle = days_since(item.get("credential_accessed_at"), now);
+        // Never used since it was authorized counts as stale too.
+        let is_stale = match idle {
+            Some(days) => days >= stale_days,
+            None => true,
+        };
+        let expires = item
+            .get("authorized_credential_expires_at")
+            .and_then(|e| e.as_str())
+            .and_then(time::parse);
+        let is_expired = expires.is_some_and(|e| e < now);
+        stale += is_stale as u64;
+        expired += is_expired as u64;
+        no_expiry += expires.is_none() as u64;
+        item["days_since_access"] = json!(idle);
+        item["stale"] = json!(is_stale);
+        item["expired"] = json!(is_expired);
+    }
+    data["summary"] = json!({
+        "by_type": by_type,
+        "stale": stale,
+        "expired": expired,
+        "no_expiry": no_expiry,
+        "stale_days": stale_days
+    });
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Revokes one SSO authorization; the credential itself stays valid outside
+/// the org until its owner deletes it.
+pub(crate) fn revoke_credential_authorization(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(credential_id) = u64_arg(args, "credential_id").filter(|_| !org.is_empty()) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "org and credential_id are required"}),
+        )));
+    };
+    let data = github_delete(token, &format!("/orgs/{org}/credential-authorizations/{credential_id}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
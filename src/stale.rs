@@ -0,0 +1,103 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::get_pages;
+use crate::paging::Paging;
+use crate::{bool_arg, github_post, str_list_arg, time, u64_arg, url_encode};
+
+const DEFAULT_DAYS: u64 = 30;
+const DEFAULT_LIMIT: u64 = 100;
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+/// Finds open issues/PRs with no activity for `days`, optionally labelling
+/// and/or commenting on each one (skipped under `dry_run`).
+pub(crate) fn find_stale(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let days = u64_arg(args, "days").unwrap_or(DEFAULT_DAYS);
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("all");
+    let label = args.get("label").and_then(|v| v.as_str()).unwrap_or("");
+    let comment = args.get("comment").and_then(|v| v.as_str()).unwrap_or("");
+    let dry_run = bool_arg(args, "dry_run");
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_LIMIT).clamp(1, 1000) as usize;
+
+    let now = time::now();
+    let cutoff = time::format_date(now - days as i64 * time::DAY);
+    let mut query = format!("repo:{owner}/{repo} is:open updated:<{cutoff}");
+    match kind {
+        "issues" => query.push_str(" is:issue"),
+        "prs" => query.push_str(" is:pr"),
+        _ => {}
+    }
+    for excluded in str_list_arg(args, "exclude_labels") {
+        query.push_str(&format!(" -label:\"{excluded}\""));
+    }
+
+    let paging = Paging {
+        page: 1,
+        per_page: 100,
+        max_pages: (limit as u64).div_ceil(100),
+    };
+    let path = format!("/search/issues?q={}&sort=updated&order=asc", url_encode(&query));
+    let listing = match get_pages(token, &path, &paging) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let total = listing.total;
+    let mut found = listing.items;
+    found.truncate(limit);
+
+    let mut results = Vec::new();
+    for item in &found {
+        let number = item.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+        let updated = item.get("updated_at").and_then(|v| v.as_str()).unwrap_or("");
+        let mut actions = Vec::new();
+        if !dry_run && !label.is_empty() {
+            let path = format!("/repos/{owner}/{repo}/issues/{number}/labels");
+            actions.push(action("label", github_post(token, &path, &json!({"labels": [label]}))));
+        }
+        if !dry_run && !comment.is_empty() {
+            let path = format!("/repos/{owner}/{repo}/issues/{number}/comments");
+            actions.push(action("comment", github_post(token, &path, &json!({"body": comment}))));
+        }
+        results.push(json!({
+            "number": number,
+            "title": item.get("title"),
+            "kind": if item.get("pull_request").is_some() { "pr" } else { "issue" },
+            "updated_at": updated,
+            "days_inactive": time::parse(updated).map(|t| (now - t) / time::DAY),
+            "html_url": item.get("html_url"),
+            "labels": item
+                .get("labels")
+                .and_then(|l| l.as_array())
+                .map(|l| l.iter().filter_map(|x| x.get("name").cloned()).collect::<Vec<_>>()),
+            "actions": actions
+        }));
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "query": query,
+        "dry_run": dry_run,
+        "count": results.len(),
+        "total": total,
+        "truncated": total.is_some_and(|t| t > results.len() as u64),
+        "items": results
+    }))))
+}
+
+fn action(name: &str, result: Result<Value, Error>) -> Value {
+    match result {
+        Ok(resp) if resp.get("message").is_none() => json!({"action": name, "ok": true}),
+        Ok(resp) => json!({"action": name, "ok": false, "error": resp.get("message")}),
+        Err(e) => json!({"action": name, "ok": false, "error": e.to_string()}),
+    }
+}
@@ -0,0 +1,25 @@
+use extism_pdk::*;
+use serde_json::Value;
+
+// =============================================================================
+// Plugin state (persisted across calls in Extism vars)
+// =============================================================================
+
+pub(crate) fn load(key: &str) -> Option<Value> {
+    var::get::<Json<Value>>(key).ok().flatten().map(|Json(v)| v)
+}
+
+pub(crate) fn save(key: &str, value: &Value) -> Result<(), Error> {
+    var::set(key, Json(value.clone()))
+}
+
+pub(crate) fn remove(key: &str) -> Result<(), Error> {
+    var::remove(key)
+}
+
+/// Returns the next value of a named monotonic counter.
+pub(crate) fn next_id(counter: &str) -> u64 {
+    let next = load(counter).and_then(|v| v.as_u64()).unwrap_or(0) + 1;
+    let _ = save(counter, &Value::from(next));
+    next
+}
@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::{get_all, get_opt, search_count, Listing};
+use crate::stream::Fields;
+use crate::{github_request, json_body, time, u64_arg, JSON_MEDIA_TYPE};
+
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+const DEFAULT_LIMIT: u64 = 20;
+const DEFAULT_STATS_ATTEMPTS: u64 = 4;
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const BUSIEST_SLOTS: usize = 5;
+// Review-size thresholds beyond which a PR is flagged as oversized.
+const DEFAULT_MAX_LINES: u64 = 400;
+const DEFAULT_MAX_FILES: u64 = 20;
+const DEFAULT_PR_LIMIT: u64 = 30;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// Search serves at most 1000 results per query; past that the listing is
+// truncated.
+fn search_all(token: &str, query: &str) -> Result<Listing, Value> {
+    get_all(token, &format!("/search/issues?q={query}"))
+}
+
+/// Fetches a `/stats/*` endpoint. GitHub answers 202 while it computes the
+/// stats in the background, so retry with doubling waits (1s, 2s, ...) for up
+/// to `attempts` tries. `None` means the stats are still being computed.
+fn computed_stats(token: &str, path: &str, attempts: u64) -> Result<Option<Value>, Error> {
+    let mut wait = 1;
+    for attempt in 1..=attempts.max(1) {
+        let resp = github_request(token, "GET", path, None, JSON_MEDIA_TYPE)?;
+        if resp.status != 202 {
+            return json_body(&resp, &Fields::default()).map(Some);
+        }
+        if attempt < attempts {
+            std::thread::sleep(std::time::Duration::from_secs(wait));
+            wait *= 2;
+        }
+    }
+    Ok(None)
+}
+
+fn stats_path(args: &DataType, stat: &str) -> Option<String> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some(format!("/repos/{owner}/{repo}/stats/{stat}"))
+}
+
+fn pending() -> FnResult<Json<DataType>> {
+    Ok(Json(DataType::from_json(json!({
+        "pending": true,
+        "message": "GitHub is still computing these statistics; try again shortly"
+    }))))
+}
+
+fn week_date(week: Option<&Value>) -> Value {
+    week.and_then(|w| w.as_i64())
+        .map(|w| json!(time::format_date(w)))
+        .unwrap_or(Value::Null)
+}
+
+// Keeps the most recent `weeks` entries when the arg is given.
+fn last_weeks(mut items: Vec<Value>, args: &DataType) -> Vec<Value> {
+    if let Some(weeks) = u64_arg(args, "weeks") {
+        let keep = weeks as usize;
+        if items.len() > keep {
+            items.drain(..items.len() - keep);
+        }
+    }
+    items
+}
+
+// Size and spread of one PR, or None if it cannot be read.
+fn pr_size(token: &str, owner: &str, repo: &str, number: u64, max_lines: u64, max_files: u64) -> Option<Value> {
+    let pr = get_opt(token, &format!("/repos/{owner}/{repo}/pulls/{number}"))?;
+    let files = get_all(token, &format!("/repos/{owner}/{repo}/pulls/{number}/files"))
+        .ok()?
+        .items;
+    let additions = pr.get("additions").and_then(|v| v.as_u64()).unwrap_or(0);
+    let deletions = pr.get("deletions").and_then(|v| v.as_u64()).unwrap_or(0);
+    let changed_files = pr
+        .get("changed_files")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(files.len() as u64);
+    let lines = additions + deletions;
+
+    let mut directories: Vec<&str> = files
+        .iter()
+        .filter_map(|f| f.get("filename").and_then(|v| v.as_str()))
+        .map(|name| name.rsplit_once('/').map_or("", |(dir, _)| dir))
+        .collect();
+    directories.sort_unstable();
+    directories.dedup();
+    let largest = files
+        .iter()
+        .max_by_key(|f| f.get("changes").and_then(|v| v.as_u64()).unwrap_or(0));
+    let largest_changes = largest
+        .and_then(|f| f.get("changes"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    let mut flags = Vec::new();
+    if lines > max_lines {
+        flags.push("too_many_lines");
+    }
+    if changed_files > max_files {
+        flags.push("too_many_files");
+    }
+    Some(json!({
+        "number": number,
+        "title": pr.get("title"),
+        "author": pr.pointer("/user/login"),
+        "additions": additions,
+        "deletions": deletions,
+        "lines_changed": lines,
+        "files_changed": changed_files,
+        "directories": directories.len(),
+        "largest_file": largest.and_then(|f| f.get("filename")),
+        "largest_file_share": (lines > 0).then(|| (largest_changes as f64 / lines as f64 * 1000.0).round() / 1000.0),
+        "oversized": !flags.is_empty(),
+        "flags": flags
+    }))
+}
+
+#[derive(Default)]
+struct Activity {
+    commits: u64,
+    prs_opened: u64,
+    prs_merged: u64,
+    prs_reviewed: Option<u64>,
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Per-user commits, PRs opened, PRs merged, and PRs reviewed in a repo
+/// between `since` and `until` (dates; default the last 30 days). Commits
+/// come from the commits endpoint on the default branch; PR counts from
+/// search. Reviews are counted for the top `limit` users only, as PRs they
+/// reviewed (not their own) that were updated in the window. Totals that hit
+/// the page cap are flagged in `truncated`.
+pub(crate) fn contributor_activity(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let now = time::now();
+    let until = args
+        .get("until")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| time::format_date(now));
+    let since = args
+        .get("since")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| time::format_date(now - DEFAULT_WINDOW_DAYS * time::DAY));
+    // Both go into URLs and search qualifiers verbatim, so anything but a
+    // plain date (a time, `&`, `+`) would change the query.
+    let is_date = |d: &str| time::parse(d).map(time::format_date).as_deref() == Some(d);
+    if !is_date(&since) || !is_date(&until) {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "since and until must be YYYY-MM-DD dates"}),
+        )));
+    }
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_LIMIT) as usize;
+    let range = format!("{since}..{until}");
+
+    let mut users: BTreeMap<String, Activity> = BTreeMap::new();
+    let commits = match get_all(
+        token,
+        &format!("/repos/{owner}/{repo}/commits?since={since}T00:00:00Z&until={until}T23:59:59Z"),
+    ) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    for commit in &commits.items {
+        if let Some(login) = commit.pointer("/author/login").and_then(|v| v.as_str()) {
+            users.entry(login.to_string()).or_default().commits += 1;
+        }
+    }
+    let opened = match search_all(token, &format!("repo:{owner}/{repo}+type:pr+created:{range}")) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    for pr in &opened.items {
+        if let Some(login) = pr.pointer("/user/login").and_then(|v| v.as_str()) {
+            users.entry(login.to_string()).or_default().prs_opened += 1;
+        }
+    }
+    let merged = match search_all(token, &format!("repo:{owner}/{repo}+type:pr+is:merged+merged:{range}")) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    for pr in &merged.items {
+        if let Some(login) = pr.pointer("/user/login").and_then(|v| v.as_str()) {
+            users.entry(login.to_string()).or_default().prs_merged += 1;
+        }
+    }
+    users.retain(|login, _| !login.ends_with("[bot]"));
+
+    let mut ranked: Vec<(String, Activity)> = users.into_iter().collect();
+    ranked.sort_by_key(|(_, a)| std::cmp::Reverse(a.commits + a.prs_opened * 3 + a.prs_merged * 3));
+    ranked.truncate(limit);
+    for (login, activity) in &mut ranked {
+        activity.prs_reviewed = search_count(
+            token,
+            &format!("repo:{owner}/{repo}+type:pr+reviewed-by:{login}+-author:{login}+updated:{range}"),
+        );
+    }
+
+    let leaderboard: Vec<Value> = ranked
+        .into_iter()
+        .map(|(login, a)| {
+            json!({
+                "login": login,
+                "commits": a.commits,
+                "prs_opened": a.prs_opened,
+                "prs_merged": a.prs_merged,
+                "prs_reviewed": a.prs_reviewed
+            })
+        })
+        .collect();
+    Ok(Json(DataType::from_json(json!({
+        "repository": format!("{owner}/{repo}"),
+        "since": since,
+        "until": until,
+        "totals": {"commits": commits.items.len(), "prs_opened": opened.items.len(), "prs_merged": merged.items.len()},
+        // Which totals stopped at the page cap and are lower bounds.
+        "truncated": {
+            "commits": commits.truncated(),
+            "prs_opened": opened.truncated(),
+            "prs_merged": merged.truncated()
+        },
+        "contributors": leaderboard
+    }))))
+}
+
+/// Weekly commit counts for the last year, with per-day breakdowns
+/// (Sunday first).
+pub(crate) fn commit_activity(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = stats_path(args, "commit_activity") else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    };
+    let attempts = u64_arg(args, "max_attempts").unwrap_or(DEFAULT_STATS_ATTEMPTS);
+    let Some(data) = computed_stats(token, &path, attempts)? else {
+        return pending();
+    };
+    let Value::Array(weeks) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    let weeks: Vec<Value> = weeks
+        .iter()
+        .map(|w| json!({"week": week_date(w.get("week")), "total": w.get("total"), "days": w.get("days")}))
+        .collect();
+    let weeks = last_weeks(weeks, args);
+    let total: u64 = weeks.iter().filter_map(|w| w["total"].as_u64()).sum();
+    Ok(Json(DataType::from_json(json!({"total": total, "weeks": weeks}))))
+}
+
+/// Weekly additions and deletions. GitHub only computes this for repos with
+/// fewer than 10,000 commits.
+pub(crate) fn code_frequency(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = stats_path(args, "code_frequency") else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    };
+    let attempts = u64_arg(args, "max_attempts").unwrap_or(DEFAULT_STATS_ATTEMPTS);
+    let Some(data) = computed_stats(token, &path, attempts)? else {
+        return pending();
+    };
+    let Value::Array(rows) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    // Rows are [week, additions, deletions] with deletions negative.
+    let weeks: Vec<Value> = rows
+        .iter()
+        .filter_map(|row| {
+            let row = row.as_array()?;
+            Some(json!({
+                "week": week_date(row.first()),
+                "additions": row.get(1).and_then(|v| v.as_i64()).unwrap_or(0),
+                "deletions": row.get(2).and_then(|v| v.as_i64()).unwrap_or(0).abs()
+            }))
+        })
+        .collect();
+    let weeks = last_weeks(weeks, args);
+    let additions: i64 = weeks.iter().filter_map(|w| w["additions"].as_i64()).sum();
+    let deletions: i64 = weeks.iter().filter_map(|w| w["deletions"].as_i64()).sum();
+    Ok(Json(DataType::from_json(
+        json!({"additions": additions, "deletions": deletions, "weeks": weeks}),
+    )))
+}
+
+/// Commits by weekday and hour (in each commit's own time zone), with
+/// weekday and hour totals and the busiest slots.
+pub(crate) fn punch_card(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = stats_path(args, "punch_card") else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    };
+    let attempts = u64_arg(args, "max_attempts").unwrap_or(DEFAULT_STATS_ATTEMPTS);
+    let Some(data) = computed_stats(token, &path, attempts)? else {
+        return pending();
+    };
+    let Value::Array(rows) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+
+    let mut grid = [[0u64; 24]; 7];
+    for row in &rows {
+        let cells: Vec<u64> = row
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_u64())
+            .collect();
+        if let [day, hour, commits] = cells[..] {
+            if day < 7 && hour < 24 {
+                grid[day as usize][hour as usize] = commits;
+            }
+        }
+    }
+    let by_weekday: Vec<Value> = grid
+        .iter()
+        .zip(WEEKDAYS)
+        .map(|(hours, day)| json!({"day": day, "commits": hours.iter().sum::<u64>()}))
+        .collect();
+    let by_hour: Vec<u64> = (0..24).map(|h| grid.iter().map(|hours| hours[h]).sum()).collect();
+    let mut slots: Vec<(usize, usize, u64)> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(d, hours)| hours.iter().enumerate().map(move |(h, c)| (d, h, *c)))
+        .filter(|(_, _, c)| *c > 0)
+        .collect();
+    slots.sort_by_key(|(_, _, c)| std::cmp::Reverse(*c));
+    let busiest: Vec<Value> = slots
+        .iter()
+        .take(BUSIEST_SLOTS)
+        .map(|(d, h, c)| json!({"day": WEEKDAYS[*d], "hour": h, "commits": c}))
+        .collect();
+
+    Ok(Json(DataType::from_json(json!({
+        "total": by_hour.iter().sum::<u64>(),
+        "by_weekday": by_weekday,
+        "by_hour": by_hour,
+        "busiest": busiest,
+        "grid": grid
+    }))))
+}
+
+/// Weekly commit counts for the last 52 weeks split into the repo owner and
+/// everyone else, oldest first, with the community share of commits.
+pub(crate) fn participation(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let Some(path) = stats_path(args, "participation") else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    };
+    let attempts = u64_arg(args, "max_attempts").unwrap_or(DEFAULT_STATS_ATTEMPTS);
+    let Some(data) = computed_stats(token, &path, attempts)? else {
+        return pending();
+    };
+    let counts = |key: &str| -> Vec<u64> {
+        data.get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_u64())
+            .collect()
+    };
+    let (all, owner) = (counts("all"), counts("owner"));
+    if all.is_empty() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+
+    // The last entry is the current week; label each by its starting Sunday.
+    let today = time::now().div_euclid(time::DAY);
+    let this_week = today - (today + 4).rem_euclid(7);
+    let weeks: Vec<Value> = all
+        .iter()
+        .enumerate()
+        .map(|(i, total)| {
+            let owner_commits = owner.get(i).copied().unwrap_or(0);
+            let start = this_week - 7 * (all.len() - 1 - i) as i64;
+            json!({
+                "week": time::format_date(start * time::DAY),
+                "all": total,
+                "owner": owner_commits,
+                "community": total.saturating_sub(owner_commits)
+            })
+        })
+        .collect();
+    let weeks = last_weeks(weeks, args);
+    let total: u64 = weeks.iter().filter_map(|w| w["all"].as_u64()).sum();
+    let community: u64 = weeks.iter().filter_map(|w| w["community"].as_u64()).sum();
+    let share = (total > 0).then(|| (community as f64 / total as f64 * 1000.0).round() / 1000.0);
+
+    Ok(Json(DataType::from_json(json!({
+        "total": total,
+        "community": community,
+        "owner": total - community,
+        "community_share": share,
+        "weeks": weeks
+    }))))
+}
+
+/// Size and complexity of one PR (`number`), or of PRs merged between
+/// `since` and `until` (up to `limit`). PRs over `max_lines` changed lines
+/// or `max_files` files are flagged as oversized.
+pub(crate) fn pr_metrics(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let max_lines = u64_arg(args, "max_lines").unwrap_or(DEFAULT_MAX_LINES);
+    let max_files = u64_arg(args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+    if let Some(number) = u64_arg(args, "number") {
+        return match pr_size(token, owner, repo, number, max_lines, max_files) {
+            Some(metrics) => Ok(Json(DataType::from_json(metrics))),
+            None => Ok(Json(DataType::from_json(
+                json!({"error": format!("pull request #{number} not found")}),
+            ))),
+        };
+    }
+
+    let now = time::now();
+    let until = args
+        .get("until")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| time::format_date(now));
+    let since = args
+        .get("since")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| time::format_date(now - DEFAULT_WINDOW_DAYS * time::DAY));
+    let limit = u64_arg(args, "limit").unwrap_or(DEFAULT_PR_LIMIT) as usize;
+    let merged = match search_all(
+        token,
+        &format!("repo:{owner}/{repo}+type:pr+is:merged+merged:{since}..{until}"),
+    ) {
+        Ok(listing) => listing,
+        Err(error) => return Ok(Json(DataType::from_json(error))),
+    };
+    let numbers: Vec<u64> = merged
+        .items
+        .iter()
+        .filter_map(|pr| pr.get("number").and_then(|v| v.as_u64()))
+        .take(limit)
+        .collect();
+    let prs: Vec<Value> = numbers
+        .iter()
+        .filter_map(|n| pr_size(token, owner, repo, *n, max_lines, max_files))
+        .collect();
+
+    let sizes: Vec<u64> = {
+        let mut sizes: Vec<u64> = prs.iter().filter_map(|p| p["lines_changed"].as_u64()).collect();
+        sizes.sort_unstable();
+        sizes
+    };
+    let median = sizes.get(sizes.len() / 2).copied();
+    let oversized: Vec<&Value> = prs
+        .iter()
+        .filter(|p| p["oversized"] == true)
+        .map(|p| &p["number"])
+        .collect();
+
+    Ok(Json(DataType::from_json(json!({
+        "since": since,
+        "until": until,
+        "count": prs.len(),
+        "median_lines_changed": median,
+        "max_lines_changed": sizes.last(),
+        "oversized": oversized,
+        "pull_requests": prs
+    }))))
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use magi_pdk::DataType;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+// =============================================================================
+// Field projection
+// =============================================================================
+
+/// A set of (dot-separated) field paths to keep. Empty means keep everything.
+#[derive(Default)]
+pub(crate) struct Fields(BTreeMap<String, Fields>);
+
+impl Fields {
+    pub(crate) fn parse<S: AsRef<str>>(paths: &[S]) -> Fields {
+        let mut root = Fields::default();
+        for path in paths {
+            let mut node = &mut root;
+            for part in path.as_ref().split('.').map(str::trim).filter(|p| !p.is_empty()) {
+                node = node.0.entry(part.to_string()).or_default();
+            }
+        }
+        root
+    }
+
+    /// Reads the `fields` arg, given either as an array or a comma-separated string.
+    pub(crate) fn from_args(args: &DataType) -> Fields {
+        match args.get("fields").map(|v| v.to_json()) {
+            Some(Value::Array(items)) => {
+                let paths: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+                Fields::parse(&paths)
+            }
+            Some(Value::String(s)) => Fields::parse(&s.split(',').collect::<Vec<_>>()),
+            _ => Fields::default(),
+        }
+    }
+
+    fn is_all(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Parses a response body, projecting every listed item while it is being
+/// deserialized so dropped fields are never materialized. Items are the
+/// elements of a top-level array, or of the array fields of a top-level
+/// object (e.g. `workflow_runs`, `artifacts`, search `items`).
+pub(crate) fn parse(body: &[u8], fields: &Fields) -> Result<Value, serde_json::Error> {
+    if fields.is_all() {
+        return serde_json::from_slice(body);
+    }
+    let mut de = serde_json::Deserializer::from_slice(body);
+    let value = Project {
+        fields,
+        mode: Mode::Listing,
+    }
+    .deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+// =============================================================================
+// Deserializer plumbing
+// =============================================================================
+
+#[derive(Clone, Copy)]
+enum Mode {
+    // Top level: arrays are item lists, object values may be item lists.
+    Listing,
+    // Value of a top-level field: arrays are item lists, objects kept whole.
+    Container,
+    // Inside an item: keep only the selected keys.
+    Item,
+}
+
+struct Project<'a> {
+    fields: &'a Fields,
+    mode: Mode,
+}
+
+impl<'de> DeserializeSeed<'de> for Project<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        if matches!(self.mode, Mode::Item) && self.fields.is_all() {
+            return Value::deserialize(d);
+        }
+        d.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Project<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(Project {
+            fields: self.fields,
+            mode: Mode::Item,
+        })? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut out = Map::new();
+        match self.mode {
+            Mode::Container => return Value::deserialize(MapAccessDeserializer::new(map)),
+            Mode::Listing => {
+                while let Some(key) = map.next_key::<String>()? {
+                    let value = map.next_value_seed(Project {
+                        fields: self.fields,
+                        mode: Mode::Container,
+                    })?;
+                    out.insert(key, value);
+                }
+            }
+            Mode::Item => {
+                while let Some(key) = map.next_key::<String>()? {
+                    match self.fields.0.get(&key) {
+                        Some(sub) => {
+                            let value = map.next_value_seed(Project {
+                                fields: sub,
+                                mode: Mode::Item,
+                            })?;
+                            out.insert(key, value);
+                        }
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Value::Object(out))
+    }
+}
@@ -0,0 +1,120 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::paging::Paging;
+use crate::{bool_arg, github_get, state, u64_arg};
+
+#[derive(Clone, Copy)]
+pub(crate) enum SyncKind {
+    Issues,
+    Prs,
+}
+
+impl SyncKind {
+    fn name(self) -> &'static str {
+        match self {
+            SyncKind::Issues => "issues",
+            SyncKind::Prs => "prs",
+        }
+    }
+
+    // The issues endpoint returns both; PRs carry a `pull_request` key.
+    fn matches(self, item: &Value) -> bool {
+        item.get("pull_request").is_some() == matches!(self, SyncKind::Prs)
+    }
+}
+
+// =============================================================================
+// Incremental sync
+// =============================================================================
+
+/// Returns issues or PRs updated since the previous sync of the same repo.
+/// Items are walked oldest-first, each batch re-queried with `since` set to the
+/// newest `updated_at` so far (page numbers would shift as items are updated
+/// mid-walk), so a capped run can resume where it stopped; the cursor keeps
+/// the numbers already seen at its timestamp because `since` is inclusive.
+pub(crate) fn sync(token: &str, args: &DataType, kind: SyncKind) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let cap = Paging::all().max_pages;
+    let max_pages = u64_arg(args, "max_pages").unwrap_or(cap).clamp(1, cap);
+    let key = format!("sync/{}/{owner}/{repo}", kind.name());
+    let previous = if bool_arg(args, "reset") {
+        None
+    } else {
+        state::load(&key)
+    };
+
+    let since = previous
+        .as_ref()
+        .and_then(|c| c.get("since"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let seen: Vec<u64> = previous
+        .as_ref()
+        .and_then(|c| c.get("seen_at_cursor"))
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|n| n.as_u64()).collect())
+        .unwrap_or_default();
+
+    let mut items = Vec::new();
+    let mut cursor = since.clone();
+    let mut cursor_numbers = seen;
+    let mut more = false;
+    // Only advances past 1 when a whole page shares the cursor's timestamp.
+    let mut page = 1;
+    for batch_no in 1..=max_pages {
+        let (batch_since, batch_seen) = (cursor.clone(), cursor_numbers.clone());
+        let mut path =
+            format!("/repos/{owner}/{repo}/issues?state=all&sort=updated&direction=asc&per_page=100&page={page}");
+        if !batch_since.is_empty() {
+            path.push_str(&format!("&since={batch_since}"));
+        }
+        let batch = match github_get(token, &path)? {
+            Value::Array(batch) => batch,
+            other => return Ok(Json(DataType::from_json(other))),
+        };
+        let full_page = batch.len() == 100;
+        for item in batch {
+            let updated = item
+                .get("updated_at")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let number = item.get("number").and_then(|v| v.as_u64()).unwrap_or(0);
+            if updated == batch_since && batch_seen.contains(&number) {
+                continue;
+            }
+            if updated > cursor {
+                cursor = updated;
+                cursor_numbers.clear();
+            }
+            if updated == cursor {
+                cursor_numbers.push(number);
+            }
+            if kind.matches(&item) {
+                items.push(item);
+            }
+        }
+        if !full_page {
+            break;
+        }
+        page = if cursor == batch_since { page + 1 } else { 1 };
+        more = batch_no == max_pages;
+    }
+
+    state::save(&key, &json!({"since": cursor, "seen_at_cursor": cursor_numbers}))?;
+    Ok(Json(DataType::from_json(json!({
+        "items": items,
+        "previous_cursor": if since.is_empty() { Value::Null } else { json!(since) },
+        "cursor": cursor,
+        "more": more
+    }))))
+}
@@ -0,0 +1,242 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::{contents, github_get};
+
+const ISSUE_TEMPLATE_DIR: &str = ".github/ISSUE_TEMPLATE";
+
+// GitHub looks for PR templates in these directories, in this order.
+const PR_TEMPLATE_DIRS: [&str; 3] = [".github", "", "docs"];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+pub(crate) fn fetch_text(token: &str, owner: &str, repo: &str, path: &str, git_ref: Option<&str>) -> Option<String> {
+    let mut url = format!("/repos/{owner}/{repo}/contents/{path}");
+    if let Some(r) = git_ref {
+        url.push_str(&format!("?ref={r}"));
+    }
+    let file = github_get(token, &url).ok()?;
+    String::from_utf8(contents::decode(&file)?).ok()
+}
+
+pub(crate) fn list_dir(token: &str, owner: &str, repo: &str, path: &str) -> Vec<Value> {
+    match github_get(token, &format!("/repos/{owner}/{repo}/contents/{path}")) {
+        Ok(Value::Array(entries)) => entries,
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) struct PrTemplate {
+    pub name: String,
+    pub path: String,
+    pub text: String,
+}
+
+/// Collects `pull_request_template.*` files and `PULL_REQUEST_TEMPLATE/`
+/// directories from every location GitHub recognizes. Single-file templates
+/// are named "default"; directory templates keep their file name.
+pub(crate) fn pr_templates(token: &str, owner: &str, repo: &str) -> Vec<PrTemplate> {
+    let mut found = Vec::new();
+    for dir in PR_TEMPLATE_DIRS {
+        for entry in list_dir(token, owner, repo, dir) {
+            let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or(name);
+            let lower = name.to_ascii_lowercase();
+            match entry.get("type").and_then(|v| v.as_str()) {
+                Some("file") if lower.starts_with("pull_request_template.") => {
+                    if let Some(text) = fetch_text(token, owner, repo, path, None) {
+                        found.push(PrTemplate {
+                            name: "default".into(),
+                            path: path.into(),
+                            text,
+                        });
+                    }
+                }
+                Some("dir") if lower == "pull_request_template" => {
+                    for file in list_dir(token, owner, repo, path) {
+                        let file_name = file.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                        let file_path = file.get("path").and_then(|v| v.as_str()).unwrap_or(file_name);
+                        if !file_name.ends_with(".md") && !file_name.ends_with(".txt") {
+                            continue;
+                        }
+                        if let Some(text) = fetch_text(token, owner, repo, file_path, None) {
+                            found.push(PrTemplate {
+                                name: file_name.into(),
+                                path: file_path.into(),
+                                text,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    found
+}
+
+/// Replaces `{{key}}` placeholders with values from `vars`; unknown
+/// placeholders are left in place and reported.
+pub(crate) fn fill_placeholders(text: &str, vars: &Map<String, Value>) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(text.len());
+    let mut unfilled = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match vars.get(key) {
+            Some(Value::String(v)) => out.push_str(v),
+            Some(v) if !v.is_null() => out.push_str(&v.to_string()),
+            _ => {
+                out.push_str(&rest[start..start + len + 4]);
+                if !unfilled.iter().any(|k| k == key) {
+                    unfilled.push(key.to_string());
+                }
+            }
+        }
+        rest = &rest[start + len + 4..];
+    }
+    out.push_str(rest);
+    (out, unfilled)
+}
+
+fn str_field(v: &Value, key: &str) -> Value {
+    v.get(key).cloned().unwrap_or(Value::Null)
+}
+
+// Normalizes one `body` element of an issue form.
+fn form_field(element: &Value) -> Value {
+    let kind = element.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    let attrs = element.get("attributes").cloned().unwrap_or(json!({}));
+    let required = element
+        .pointer("/validations/required")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    // Dropdown options are strings; checkbox options are {label, required}.
+    let options = attrs.get("options").and_then(|o| o.as_array()).map(|opts| {
+        opts.iter()
+            .map(|o| match o {
+                Value::Object(_) => json!({"label": o.get("label"), "required": o.get("required").and_then(|r| r.as_bool()).unwrap_or(false)}),
+                other => other.clone(),
+            })
+            .collect::<Vec<_>>()
+    });
+    json!({
+        "id": str_field(element, "id"),
+        "type": kind,
+        "label": str_field(&attrs, "label"),
+        "description": str_field(&attrs, "description"),
+        "placeholder": str_field(&attrs, "placeholder"),
+        "value": str_field(&attrs, "value"),
+        "render": str_field(&attrs, "render"),
+        "multiple": attrs.get("multiple").and_then(|v| v.as_bool()).unwrap_or(false),
+        "default": str_field(&attrs, "default"),
+        "options": options,
+        "required": required,
+        // Markdown elements are display-only and never appear in the body.
+        "input": kind != "markdown"
+    })
+}
+
+fn body_template(fields: &[Value]) -> String {
+    fields
+        .iter()
+        .filter(|f| f["input"] == true)
+        .map(|f| {
+            format!(
+                "### {}\n\n<{}>",
+                f["label"].as_str().unwrap_or(""),
+                f["id"].as_str().unwrap_or("value")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Parses YAML issue forms into field schemas. Submitted form bodies are
+/// `### <label>` sections in field order, which `body_template` mirrors.
+pub(crate) fn get_issue_forms(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let only = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+
+    let mut forms = Vec::new();
+    let mut errors = Vec::new();
+    for entry in list_dir(token, owner, repo, ISSUE_TEMPLATE_DIR) {
+        let file = entry.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let is_yaml = file.ends_with(".yml") || file.ends_with(".yaml");
+        if !is_yaml || file.starts_with("config.") {
+            continue;
+        }
+        let Some(text) = fetch_text(token, owner, repo, &format!("{ISSUE_TEMPLATE_DIR}/{file}"), None) else {
+            errors.push(json!({"file": file, "error": "could not fetch"}));
+            continue;
+        };
+        let form: Value = match serde_yaml::from_str(&text) {
+            Ok(form) => form,
+            Err(e) => {
+                errors.push(json!({"file": file, "error": e.to_string()}));
+                continue;
+            }
+        };
+        let name = form.get("name").and_then(|v| v.as_str()).unwrap_or(file);
+        if !only.is_empty() && only != name && only != file {
+            continue;
+        }
+        let fields: Vec<Value> = form
+            .get("body")
+            .and_then(|b| b.as_array())
+            .map(|body| body.iter().map(form_field).collect())
+            .unwrap_or_default();
+        forms.push(json!({
+            "file": file,
+            "name": name,
+            "description": str_field(&form, "description"),
+            "title": str_field(&form, "title"),
+            "labels": str_field(&form, "labels"),
+            "assignees": str_field(&form, "assignees"),
+            "projects": str_field(&form, "projects"),
+            "required_fields": fields
+                .iter()
+                .filter(|f| f["required"] == true)
+                .map(|f| f["id"].as_str().or(f["label"].as_str()).unwrap_or("").to_string())
+                .collect::<Vec<_>>(),
+            "body_template": body_template(&fields),
+            "fields": fields
+        }));
+    }
+
+    Ok(Json(DataType::from_json(json!({"forms": forms, "errors": errors}))))
+}
+
+pub(crate) fn get_pr_templates(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let only = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let templates: Vec<Value> = pr_templates(token, owner, repo)
+        .into_iter()
+        .filter(|t| only.is_empty() || t.name == only)
+        .map(|t| json!({"name": t.name, "path": t.path, "body": t.text}))
+        .collect();
+    Ok(Json(DataType::from_json(json!({"templates": templates}))))
+}
@@ -0,0 +1,65 @@
+// =============================================================================
+// Timestamps (UTC, as used by the GitHub API)
+// =============================================================================
+
+pub(crate) const DAY: i64 = 86_400;
+
+pub(crate) fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Parses `YYYY-MM-DDTHH:MM:SSZ` (or a bare date) into Unix seconds.
+pub(crate) fn parse(ts: &str) -> Option<i64> {
+    let date = ts.get(..10)?;
+    let mut parts = date.split('-');
+    let y: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let d: i64 = parts.next()?.parse().ok()?;
+    let mut secs = days_from_civil(y, m, d) * DAY;
+    if let Some(time) = ts.get(11..19) {
+        let mut hms = time.split(':').map(|p| p.parse::<i64>().ok());
+        secs += hms.next()?? * 3600 + hms.next()?? * 60 + hms.next()??;
+    }
+    Some(secs)
+}
+
+pub(crate) fn format_date(secs: i64) -> String {
+    let (y, m, d) = civil_from_days(secs.div_euclid(DAY));
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+pub(crate) fn format(secs: i64) -> String {
+    let rem = secs.rem_euclid(DAY);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_date(secs),
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// Howard Hinnant's days-from-civil algorithms.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
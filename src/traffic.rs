@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::composite::get_opt;
+use crate::{bool_arg, state, time};
+
+const KINDS: [&str; 2] = ["views", "clones"];
+// About three years of daily points per kind.
+const MAX_DAYS: usize = 1100;
+
+fn state_key(owner: &str, repo: &str) -> String {
+    format!("traffic/{owner}/{repo}")
+}
+
+// Monday of the week containing `date` (YYYY-MM-DD).
+fn week_of(date: &str) -> Option<String> {
+    let days = time::parse(date)?.div_euclid(time::DAY);
+    let monday = days - (days + 3).rem_euclid(7);
+    Some(time::format_date(monday * time::DAY))
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Fetches the last 14 days of daily views and clones and merges them into
+/// plugin state keyed by date, so history outlives GitHub's retention. A day
+/// is overwritten on later snapshots since today's counts are still partial.
+/// The merged points are also returned for hosts that keep their own store.
+pub(crate) fn snapshot_traffic(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let key = state_key(owner, repo);
+    let mut stored = state::load(&key).unwrap_or(json!({}));
+    let mut fetched = json!({});
+
+    for kind in KINDS {
+        let Some(data) = get_opt(token, &format!("/repos/{owner}/{repo}/traffic/{kind}?per=day")) else {
+            return Ok(Json(DataType::from_json(
+                json!({"error": format!("could not read {kind} traffic (push access is required)")}),
+            )));
+        };
+        let points: Vec<(String, Value)> = data
+            .get(kind)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| {
+                let date = p.get("timestamp")?.as_str()?.get(..10)?.to_string();
+                Some((date, json!({"count": p.get("count"), "uniques": p.get("uniques")})))
+            })
+            .collect();
+        let history = stored[kind].as_object().cloned().unwrap_or_default();
+        let mut merged: BTreeMap<String, Value> = history.into_iter().collect();
+        for (date, point) in &points {
+            merged.insert(date.clone(), point.clone());
+        }
+        while merged.len() > MAX_DAYS {
+            merged.pop_first();
+        }
+        stored[kind] = Value::Object(merged.into_iter().collect::<Map<_, _>>());
+        fetched[kind] = json!(points
+            .into_iter()
+            .map(|(date, mut p)| {
+                p["date"] = json!(date);
+                p
+            })
+            .collect::<Vec<_>>());
+    }
+    stored["last_snapshot"] = json!(time::format(time::now()));
+    if !bool_arg(args, "emit_only") {
+        state::save(&key, &stored)?;
+    }
+
+    Ok(Json(DataType::from_json(json!({
+        "repository": format!("{owner}/{repo}"),
+        "stored": !bool_arg(args, "emit_only"),
+        "days_stored": stored["views"].as_object().map_or(0, Map::len),
+        "points": fetched
+    }))))
+}
+
+/// Reads accumulated traffic back, daily or summed per ISO week (`per`),
+/// optionally from `since` (YYYY-MM-DD).
+pub(crate) fn traffic_history(args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let per = args.get("per").and_then(|v| v.as_str()).unwrap_or("week");
+    if per != "day" && per != "week" {
+        return Ok(Json(DataType::from_json(json!({"error": "per must be day or week"}))));
+    }
+    let since = args.get("since").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(stored) = state::load(&state_key(owner, repo)) else {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "no traffic snapshots stored for this repository; run snapshot_traffic first"}),
+        )));
+    };
+
+    let mut out =
+        json!({"repository": format!("{owner}/{repo}"), "per": per, "last_snapshot": stored.get("last_snapshot")});
+    for kind in KINDS {
+        let days = stored[kind].as_object().cloned().unwrap_or_default();
+        let mut buckets: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+        for (date, point) in days.iter().filter(|(date, _)| date.as_str() >= since) {
+            let bucket = if per == "week" {
+                week_of(date).unwrap_or_else(|| date.clone())
+            } else {
+                date.clone()
+            };
+            let entry = buckets.entry(bucket).or_default();
+            entry.0 += point["count"].as_u64().unwrap_or(0);
+            entry.1 += point["uniques"].as_u64().unwrap_or(0);
+        }
+        let total: u64 = buckets.values().map(|(count, _)| count).sum();
+        out[kind] = json!({
+            "total": total,
+            // Weekly uniques are summed daily uniques, an upper bound on distinct visitors.
+            "series": buckets
+                .into_iter()
+                .map(|(period, (count, uniques))| json!({"period": period, "count": count, "uniques": uniques}))
+                .collect::<Vec<_>>()
+        });
+    }
+    Ok(Json(DataType::from_json(out)))
+}
@@ -0,0 +1,119 @@
+use magi_pdk::DataType;
+use serde_json::Value;
+
+use crate::str_list_arg;
+
+// =============================================================================
+// Commit message trailers (Co-authored-by, Signed-off-by, issue refs)
+// =============================================================================
+
+pub(crate) type Trailer = (String, String);
+
+fn is_trailer_line(line: &str) -> bool {
+    match line.split_once(": ") {
+        Some((key, value)) => {
+            !key.is_empty() && !value.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+        None => false,
+    }
+}
+
+fn is_identity(value: &str) -> bool {
+    let Some((name, rest)) = value.split_once(" <") else {
+        return false;
+    };
+    !name.trim().is_empty() && rest.ends_with('>') && rest.contains('@')
+}
+
+/// Collects trailers from `trailers` (an object of key -> value or list of
+/// values, or a list of `{key, value}`) plus the shorthands `co_authors`,
+/// `signed_off_by`, and `closes` (issue numbers or refs like `org/repo#3`).
+pub(crate) fn from_args(args: &DataType) -> Result<Vec<Trailer>, String> {
+    let mut out: Vec<Trailer> = Vec::new();
+    match args.get("trailers").map(|v| v.to_json()) {
+        Some(Value::Object(map)) => {
+            for (key, value) in map {
+                match value {
+                    Value::Array(values) => out.extend(
+                        values
+                            .iter()
+                            .filter_map(|v| v.as_str())
+                            .map(|v| (key.clone(), v.to_string())),
+                    ),
+                    Value::String(v) => out.push((key, v)),
+                    _ => return Err(format!("trailer {key} must be a string or list of strings")),
+                }
+            }
+        }
+        Some(Value::Array(items)) => {
+            for item in items {
+                let key = item.get("key").and_then(|k| k.as_str()).unwrap_or("");
+                let value = item.get("value").and_then(|v| v.as_str()).unwrap_or("");
+                out.push((key.to_string(), value.to_string()));
+            }
+        }
+        _ => {}
+    }
+    for author in str_list_arg(args, "co_authors") {
+        out.push(("Co-authored-by".into(), author));
+    }
+    if let Some(signer) = args
+        .get("signed_off_by")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+    {
+        out.push(("Signed-off-by".into(), signer.to_string()));
+    }
+    let closes = match args.get("closes").map(|v| v.to_json()) {
+        Some(Value::Array(refs)) => refs
+            .iter()
+            .map(|r| r.as_str().map(str::to_string).unwrap_or_else(|| r.to_string()))
+            .collect(),
+        Some(Value::Number(n)) => vec![n.to_string()],
+        _ => str_list_arg(args, "closes"),
+    };
+    for issue in closes {
+        let issue = issue.trim().to_string();
+        let reference = if issue.contains('#') {
+            issue
+        } else {
+            format!("#{issue}")
+        };
+        out.push(("Closes".into(), reference));
+    }
+
+    for (key, value) in &out {
+        if !is_trailer_line(&format!("{key}: {value}")) || value.contains('\n') {
+            return Err(format!("invalid trailer: {key}: {value}"));
+        }
+        let identity_key = key.eq_ignore_ascii_case("Co-authored-by") || key.eq_ignore_ascii_case("Signed-off-by");
+        if identity_key && !is_identity(value) {
+            return Err(format!("{key} must be \"Name <email>\": {value}"));
+        }
+    }
+    Ok(out)
+}
+
+/// Appends trailers to `message` the way `git interpret-trailers` does: into
+/// an existing trailer block when the message ends with one, otherwise after
+/// a blank line. Trailers already present are not repeated.
+pub(crate) fn apply(message: &str, trailers: &[Trailer]) -> String {
+    let message = message.trim_end();
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or("");
+    let has_block = message.contains("\n\n") && last_paragraph.lines().all(is_trailer_line);
+    let new: Vec<String> = trailers
+        .iter()
+        .map(|(k, v)| format!("{k}: {v}"))
+        .filter(|line| !message.lines().any(|l| l.trim() == line))
+        .fold(Vec::new(), |mut acc, line| {
+            if !acc.contains(&line) {
+                acc.push(line);
+            }
+            acc
+        });
+    if new.is_empty() {
+        return message.to_string();
+    }
+    let separator = if has_block { "\n" } else { "\n\n" };
+    format!("{message}{separator}{}", new.join("\n"))
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::json;
+
+use crate::stream::Fields;
+use crate::{github_get_fields, u64_arg};
+
+const DEFAULT_TOP: u64 = 10;
+
+#[derive(Default)]
+struct Bucket {
+    files: u64,
+    bytes: u64,
+}
+
+fn language_for(ext: &str) -> &'static str {
+    match ext {
+        "rs" => "Rust",
+        "go" => "Go",
+        "py" | "pyi" => "Python",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "scala" => "Scala",
+        "sh" | "bash" | "zsh" => "Shell",
+        "md" | "markdown" | "rst" | "txt" => "Docs",
+        "json" | "yaml" | "yml" | "toml" | "ini" | "xml" => "Config",
+        "html" | "htm" | "css" | "scss" | "sass" | "less" | "vue" | "svelte" => "Web",
+        "sql" => "SQL",
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "ico" | "webp" => "Image",
+        _ => "Other",
+    }
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+/// Structural map of a repo from one recursive tree call: per-directory
+/// counts and sizes (to `depth` levels), extension and language breakdowns,
+/// and the largest files.
+pub(crate) fn summarize_tree(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let git_ref = args.get("ref").and_then(|v| v.as_str()).unwrap_or("HEAD");
+    let depth = u64_arg(args, "depth").unwrap_or(1).max(1) as usize;
+    let top = u64_arg(args, "top").unwrap_or(DEFAULT_TOP) as usize;
+
+    let path = format!("/repos/{owner}/{repo}/git/trees/{git_ref}?recursive=1");
+    let data = github_get_fields(token, &path, &Fields::parse(&["path", "type", "size"]))?;
+    let Some(entries) = data.get("tree").and_then(|t| t.as_array()) else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+
+    let mut dirs: BTreeMap<String, Bucket> = BTreeMap::new();
+    let mut extensions: BTreeMap<String, Bucket> = BTreeMap::new();
+    let mut languages: BTreeMap<&str, Bucket> = BTreeMap::new();
+    let mut files: Vec<(&str, u64)> = Vec::new();
+    let (mut total_files, mut total_bytes) = (0u64, 0u64);
+
+    for entry in entries {
+        if entry.get("type").and_then(|v| v.as_str()) != Some("blob") {
+            continue;
+        }
+        let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
+        let size = entry.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+        total_files += 1;
+        total_bytes += size;
+        files.push((path, size));
+
+        let segments: Vec<&str> = path.split('/').collect();
+        let dir = if segments.len() > 1 {
+            segments[..(segments.len() - 1).min(depth)].join("/")
+        } else {
+            ".".to_string()
+        };
+        let name = segments.last().copied().unwrap_or("");
+        let ext = match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => ext.to_ascii_lowercase(),
+            _ => String::new(),
+        };
+        for bucket in [
+            dirs.entry(dir).or_default(),
+            languages.entry(language_for(&ext)).or_default(),
+            extensions
+                .entry(if ext.is_empty() { "(none)".to_string() } else { ext })
+                .or_default(),
+        ] {
+            bucket.files += 1;
+            bucket.bytes += size;
+        }
+    }
+
+    files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let largest: Vec<_> = files
+        .iter()
+        .take(top)
+        .map(|(p, s)| json!({"path": p, "size": s}))
+        .collect();
+    let by_size = |mut rows: Vec<(String, Bucket)>| {
+        rows.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(&b.0)));
+        rows.into_iter()
+            .map(|(name, b)| json!({"name": name, "files": b.files, "bytes": b.bytes}))
+            .collect::<Vec<_>>()
+    };
+
+    Ok(Json(DataType::from_json(json!({
+        "ref": git_ref,
+        "truncated": data.get("truncated").and_then(|v| v.as_bool()).unwrap_or(false),
+        "total_files": total_files,
+        "total_bytes": total_bytes,
+        "directories": by_size(dirs.into_iter().collect()),
+        "languages": by_size(languages.into_iter().map(|(k, v)| (k.to_string(), v)).collect()),
+        "extensions": by_size(extensions.into_iter().collect()),
+        "largest_files": largest
+    }))))
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::{bool_arg, state, time, Response};
+
+const USAGE_KEY: &str = "usage";
+
+// Counters for the tool call in progress; folded into plugin state once the
+// call finishes so each call costs a single state write.
+#[derive(Default)]
+struct Pending {
+    tool: String,
+    requests: u64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    rate_limit_consumed: u64,
+    rate_limits: Map<String, Value>,
+}
+
+thread_local! {
+    static PENDING: RefCell<Pending> = RefCell::new(Pending::default());
+}
+
+// =============================================================================
+// Recording
+// =============================================================================
+
+pub(crate) fn begin(tool: &str) {
+    PENDING.with(|p| {
+        *p.borrow_mut() = Pending {
+            tool: tool.to_string(),
+            ..Pending::default()
+        }
+    });
+}
+
+pub(crate) fn record_request(bytes_sent: usize, resp: &Response) {
+    let header_u64 = |name: &str| resp.header(name).and_then(|v| v.trim().parse::<u64>().ok());
+    PENDING.with(|p| {
+        let mut p = p.borrow_mut();
+        p.requests += 1;
+        p.bytes_sent += bytes_sent as u64;
+        p.bytes_received += resp.body.len() as u64;
+        if let Some(remaining) = header_u64("x-ratelimit-remaining") {
+            // Conditional requests answered with 304 are free.
+            if resp.status != 304 {
+                p.rate_limit_consumed += 1;
+            }
+            let resource = resp.header("x-ratelimit-resource").unwrap_or("core").to_string();
+            p.rate_limits.insert(
+                resource,
+                json!({
+                    "limit": header_u64("x-ratelimit-limit"),
+                    "remaining": remaining,
+                    "used": header_u64("x-ratelimit-used"),
+                    "reset": header_u64("x-ratelimit-reset")
+                }),
+            );
+        }
+    });
+}
+
+pub(crate) fn finish(failed: bool) {
+    let pending = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+    if pending.tool.is_empty() {
+        return;
+    }
+    let mut usage = state::load(USAGE_KEY).unwrap_or_else(empty_usage);
+    let tool = &mut usage["tools"][pending.tool.as_str()];
+    for (field, delta) in [
+        ("calls", 1),
+        ("errors", failed as u64),
+        ("api_requests", pending.requests),
+        ("bytes_sent", pending.bytes_sent),
+        ("bytes_received", pending.bytes_received),
+        ("rate_limit_consumed", pending.rate_limit_consumed),
+    ] {
+        tool[field] = json!(tool[field].as_u64().unwrap_or(0) + delta);
+    }
+    for (resource, snapshot) in pending.rate_limits {
+        usage["rate_limits"][resource.as_str()] = snapshot;
+    }
+    if let Err(e) = state::save(USAGE_KEY, &usage) {
+        magi_pdk::log_info(&format!("failed to store usage stats: {e}"));
+    }
+}
+
+fn empty_usage() -> Value {
+    json!({"since": time::format(time::now()), "tools": {}, "rate_limits": {}})
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+pub(crate) fn usage_stats(args: &DataType) -> FnResult<Json<DataType>> {
+    let usage = state::load(USAGE_KEY).unwrap_or_else(empty_usage);
+    let mut totals = json!({});
+    if let Some(tools) = usage["tools"].as_object() {
+        for stats in tools.values() {
+            for (field, value) in stats.as_object().into_iter().flatten() {
+                totals[field.as_str()] =
+                    json!(totals[field.as_str()].as_u64().unwrap_or(0) + value.as_u64().unwrap_or(0));
+            }
+        }
+    }
+    if bool_arg(args, "reset") {
+        state::remove(USAGE_KEY)?;
+    }
+    Ok(Json(DataType::from_json(json!({
+        "since": usage["since"],
+        "totals": totals,
+        "tools": usage["tools"],
+        "rate_limits": usage["rate_limits"]
+    }))))
+}
@@ -0,0 +1,55 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::json;
+
+use crate::paging::Paging;
+use crate::stream::Fields;
+use crate::{github_delete, github_list, github_patch, u64_arg};
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Pending repository invitations for the token's user.
+pub(crate) fn list_my_invitations(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let fields = Fields::parse(&[
+        "id",
+        "repository.full_name",
+        "inviter.login",
+        "permissions",
+        "created_at",
+        "expired",
+    ]);
+    let data = github_list(
+        token,
+        "/user/repository_invitations",
+        &Paging::from_args(args, 100),
+        &fields,
+    )?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+/// Accepts or declines a repository invitation by `id`.
+pub(crate) fn respond_to_invitation(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let id = u64_arg(args, "id").unwrap_or(0);
+    let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+    if id == 0 {
+        return Ok(Json(DataType::from_json(json!({"error": "id is required"}))));
+    }
+    let path = format!("/user/repository_invitations/{id}");
+    let data = match action {
+        "accept" => github_patch(token, &path, &json!({}))?,
+        "decline" => github_delete(token, &path)?,
+        _ => {
+            return Ok(Json(DataType::from_json(
+                json!({"error": "action must be accept or decline"}),
+            )))
+        }
+    };
+    if data.get("message").is_some() {
+        return Ok(Json(DataType::from_json(data)));
+    }
+    Ok(Json(DataType::from_json(
+        json!({"id": id, "action": action, "success": true}),
+    )))
+}
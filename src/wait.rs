@@ -0,0 +1,123 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::composite::{ci_status, get_opt};
+use crate::{time, u64_arg};
+
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+// =============================================================================
+// Pollable resources
+// =============================================================================
+
+/// One observation of the resource: whether it has reached a terminal state,
+/// the state to report, and the raw detail.
+struct Poll {
+    done: bool,
+    state: Value,
+    detail: Value,
+}
+
+fn poll_workflow_run(token: &str, owner: &str, repo: &str, run_id: u64) -> Poll {
+    let run = get_opt(token, &format!("/repos/{owner}/{repo}/actions/runs/{run_id}")).unwrap_or(Value::Null);
+    let status = run.get("status").and_then(|s| s.as_str()).unwrap_or("unknown");
+    Poll {
+        done: status == "completed",
+        state: run
+            .get("conclusion")
+            .filter(|c| !c.is_null())
+            .cloned()
+            .unwrap_or(json!(status)),
+        detail: json!({
+            "id": run.get("id"),
+            "name": run.get("name"),
+            "status": status,
+            "conclusion": run.get("conclusion"),
+            "html_url": run.get("html_url")
+        }),
+    }
+}
+
+// Forks are created asynchronously; the repo is usable once it has a branch.
+fn poll_fork(token: &str, owner: &str, repo: &str) -> Poll {
+    let branches = get_opt(token, &format!("/repos/{owner}/{repo}/branches?per_page=1"));
+    let ready = matches!(&branches, Some(Value::Array(b)) if !b.is_empty());
+    Poll {
+        done: ready,
+        state: json!(if ready { "ready" } else { "pending" }),
+        detail: json!({"repository": format!("{owner}/{repo}")}),
+    }
+}
+
+// Settled once nothing is pending and at least one status or check reported;
+// an `unknown` state (a failed lookup) is polled again.
+fn poll_statuses(token: &str, owner: &str, repo: &str, sha: &str) -> Poll {
+    let status = ci_status(token, owner, repo, sha);
+    let pending = status.get("pending").and_then(|p| p.as_u64()).unwrap_or(0);
+    let state = status.get("state").cloned().unwrap_or(Value::Null);
+    Poll {
+        done: pending == 0 && state != "none" && state != "unknown",
+        state,
+        detail: status,
+    }
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Polls a resource every `interval_secs` until it reaches a terminal state
+/// or `timeout_secs` (capped at 600) passes. `kind` is `workflow_run`
+/// (`run_id`), `fork` (the fork's `owner`/`repo`), or `statuses` (`sha`).
+pub(crate) fn wait_for(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("");
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    if owner.is_empty() || repo.is_empty() {
+        return Ok(Json(DataType::from_json(
+            json!({"error": "owner and repo are required"}),
+        )));
+    }
+    let run_id = u64_arg(args, "run_id").unwrap_or(0);
+    let sha = args.get("sha").and_then(|v| v.as_str()).unwrap_or("");
+    let missing = match kind {
+        "workflow_run" if run_id == 0 => Some("run_id is required for workflow_run"),
+        "statuses" if sha.is_empty() => Some("sha is required for statuses"),
+        "workflow_run" | "statuses" | "fork" => None,
+        _ => Some("kind must be workflow_run, fork, or statuses"),
+    };
+    if let Some(error) = missing {
+        return Ok(Json(DataType::from_json(json!({"error": error}))));
+    }
+    let interval = u64_arg(args, "interval_secs").unwrap_or(DEFAULT_INTERVAL_SECS).max(1);
+    let timeout = u64_arg(args, "timeout_secs")
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .min(MAX_TIMEOUT_SECS);
+
+    let started = time::now();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let poll = match kind {
+            "workflow_run" => poll_workflow_run(token, owner, repo, run_id),
+            "fork" => poll_fork(token, owner, repo),
+            _ => poll_statuses(token, owner, repo, sha),
+        };
+        let elapsed = (time::now() - started).max(0) as u64;
+        if poll.done || elapsed + interval > timeout {
+            return Ok(Json(DataType::from_json(json!({
+                "kind": kind,
+                "done": poll.done,
+                "timed_out": !poll.done,
+                "state": poll.state,
+                "attempts": attempts,
+                "elapsed_secs": elapsed,
+                "resource": poll.detail
+            }))));
+        }
+        std::thread::sleep(std::time::Duration::from_secs(interval));
+    }
+}
@@ -0,0 +1,95 @@
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Value};
+
+use crate::stream::Fields;
+use crate::{bool_arg, github_get, github_post, github_request, json_body, paging, u64_arg, JSON_MEDIA_TYPE};
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+// `/repos/{owner}/{repo}/hooks/{id}` or `/orgs/{org}/hooks/{id}`.
+fn hook_base(args: &DataType) -> Result<String, &'static str> {
+    let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+    let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+    let org = args.get("org").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(hook_id) = u64_arg(args, "hook_id") else {
+        return Err("hook_id is required");
+    };
+    if !owner.is_empty() && !repo.is_empty() {
+        Ok(format!("/repos/{owner}/{repo}/hooks/{hook_id}"))
+    } else if !org.is_empty() {
+        Ok(format!("/orgs/{org}/hooks/{hook_id}"))
+    } else {
+        Err("owner and repo, or org, are required")
+    }
+}
+
+// Deliveries page by opaque cursor rather than page number.
+fn next_cursor(link: Option<&str>) -> Option<String> {
+    let links = paging::parse_link(link?);
+    let (_, url) = links.iter().find(|(rel, _)| rel == "next")?;
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .find(|(k, _)| *k == "cursor")
+        .map(|(_, v)| v.to_string())
+}
+
+// =============================================================================
+// Tool implementations
+// =============================================================================
+
+/// Recent deliveries of a hook, newest first. `cursor` continues from the
+/// `next_cursor` of a previous call; `failed_only` keeps non-2xx responses.
+pub(crate) fn list_hook_deliveries(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let base = match hook_base(args) {
+        Ok(base) => base,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let per_page = u64_arg(args, "per_page").unwrap_or(30).clamp(1, 100);
+    let mut path = format!("{base}/deliveries?per_page={per_page}");
+    if let Some(cursor) = args.get("cursor").and_then(|v| v.as_str()).filter(|c| !c.is_empty()) {
+        // Passed back exactly as it appeared in the Link header.
+        path.push_str(&format!("&cursor={cursor}"));
+    }
+    let resp = github_request(token, "GET", &path, None, JSON_MEDIA_TYPE)?;
+    let data = json_body(&resp, &Fields::default())?;
+    let Value::Array(mut deliveries) = data else {
+        return Ok(Json(DataType::from_json(data)));
+    };
+    if bool_arg(args, "failed_only") {
+        deliveries.retain(|d| !matches!(d.get("status_code").and_then(|s| s.as_u64()), Some(200..=299)));
+    }
+    Ok(Json(DataType::from_json(json!({
+        "items": deliveries,
+        "page_info": {"next_cursor": next_cursor(resp.header("link"))}
+    }))))
+}
+
+/// One delivery with its full request (headers, payload) and response.
+pub(crate) fn get_hook_delivery(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let base = match hook_base(args) {
+        Ok(base) => base,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let Some(delivery_id) = u64_arg(args, "delivery_id") else {
+        return Ok(Json(DataType::from_json(json!({"error": "delivery_id is required"}))));
+    };
+    let data = github_get(token, &format!("{base}/deliveries/{delivery_id}"))?;
+    Ok(Json(DataType::from_json(data)))
+}
+
+pub(crate) fn redeliver_hook_delivery(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let base = match hook_base(args) {
+        Ok(base) => base,
+        Err(e) => return Ok(Json(DataType::from_json(json!({"error": e})))),
+    };
+    let Some(delivery_id) = u64_arg(args, "delivery_id") else {
+        return Ok(Json(DataType::from_json(json!({"error": "delivery_id is required"}))));
+    };
+    let data = github_post(token, &format!("{base}/deliveries/{delivery_id}/attempts"), &json!({}))?;
+    Ok(Json(DataType::from_json(data)))
+}
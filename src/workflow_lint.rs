@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashMap};
+
+use extism_pdk::*;
+use magi_pdk::DataType;
+use serde_json::{json, Map, Value};
+
+use crate::templates::fetch_text;
+
+const WORKFLOW_KEYS: &[&str] = &[
+    "name",
+    "run-name",
+    "on",
+    "permissions",
+    "env",
+    "defaults",
+    "concurrency",
+    "jobs",
+];
+const JOB_KEYS: &[&str] = &[
+    "name",
+    "needs",
+    "permissions",
+    "runs-on",
+    "environment",
+    "concurrency",
+    "outputs",
+    "env",
+    "defaults",
+    "if",
+    "steps",
+    "timeout-minutes",
+    "strategy",
+    "continue-on-error",
+    "container",
+    "services",
+    "uses",
+    "with",
+    "secrets",
+];
+const STEP_KEYS: &[&str] = &[
+    "id",
+    "if",
+    "name",
+    "uses",
+    "run",
+    "working-directory",
+    "shell",
+    "with",
+    "env",
+    "continue-on-error",
+    "timeout-minutes",
+];
+
+// Workflow commands replaced by environment files. set-env and add-path are
+// disabled outright and fail the step; the other two still run but warn.
+const DEPRECATED_COMMANDS: &[(&str, &str, &str)] = &[
+    ("::set-env", "error", "write to $GITHUB_ENV instead"),
+    ("::add-path", "error", "write to $GITHUB_PATH instead"),
+    ("::set-output", "warning", "write to $GITHUB_OUTPUT instead"),
+    ("::save-state", "warning", "write to $GITHUB_STATE instead"),
+];
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+#[derive(Default)]
+struct Report {
+    problems: Vec<Value>,
+}
+
+impl Report {
+    fn push(&mut self, severity: &str, location: &str, message: String) {
+        self.problems
+            .push(json!({"severity": severity, "location": location, "message": message}));
+    }
+
+    fn unknown_keys(&mut self, obj: &Map<String, Value>, allowed: &[&str], location: &str) {
+        for key in obj.keys().filter(|k| !allowed.contains(&k.as_str())) {
+            self.push("error", location, format!("unknown key `{key}`"));
+        }
+    }
+
+    fn count(&self, severity: &str) -> usize {
+        self.problems.iter().filter(|p| p["severity"] == severity).count()
+    }
+}
+
+// `needs` may be a single job id or a list of them.
+fn needs_of(job: &Value) -> Vec<String> {
+    match job.get("needs") {
+        Some(Value::String(need)) => vec![need.clone()],
+        Some(Value::Array(needs)) => needs.iter().filter_map(|n| n.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Each `needs` cycle once, as the job ids along it with the first repeated
+// at the end. BTreeMap keeps the output stable across runs.
+fn needs_cycles(graph: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    fn visit(
+        job: &str,
+        graph: &BTreeMap<String, Vec<String>>,
+        state: &mut HashMap<String, u8>,
+        stack: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        state.insert(job.to_string(), 1);
+        stack.push(job.to_string());
+        for need in graph.get(job).into_iter().flatten() {
+            match state.get(need.as_str()).copied().unwrap_or(0) {
+                0 if graph.contains_key(need) => visit(need, graph, state, stack, cycles),
+                1 => {
+                    let start = stack.iter().position(|j| j == need).unwrap_or(0);
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(need.clone());
+                    cycles.push(cycle);
+                }
+                _ => {}
+            }
+        }
+        stack.pop();
+        state.insert(job.to_string(), 2);
+    }
+
+    let mut state = HashMap::new();
+    let mut cycles = Vec::new();
+    for job in graph.keys() {
+        if !state.contains_key(job.as_str()) {
+            visit(job, graph, &mut state, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+fn check_step(report: &mut Report, step: &Value, location: &str) {
+    let Some(obj) = step.as_object() else {
+        report.push("error", location, "step must be a mapping".to_string());
+        return;
+    };
+    report.unknown_keys(obj, STEP_KEYS, location);
+    match (obj.contains_key("uses"), obj.contains_key("run")) {
+        (true, true) => report.push("error", location, "step has both `uses` and `run`".to_string()),
+        (false, false) => report.push("error", location, "step needs `uses` or `run`".to_string()),
+        _ => {}
+    }
+    let run = obj.get("run").and_then(|r| r.as_str()).unwrap_or("");
+    for (command, severity, fix) in DEPRECATED_COMMANDS {
+        if run.contains(command) {
+            report.push(severity, location, format!("`{command}` is deprecated; {fix}"));
+        }
+    }
+}
+
+fn check_job(report: &mut Report, id: &str, job: &Value, jobs: &Map<String, Value>) {
+    let location = format!("jobs.{id}");
+    let Some(obj) = job.as_object() else {
+        report.push("error", &location, "job must be a mapping".to_string());
+        return;
+    };
+    report.unknown_keys(obj, JOB_KEYS, &location);
+    for need in needs_of(job) {
+        if !jobs.contains_key(&need) {
+            report.push("error", &location, format!("needs unknown job `{need}`"));
+        }
+    }
+    // A reusable workflow call (`uses`) replaces runs-on and steps entirely.
+    if obj.contains_key("uses") {
+        if obj.contains_key("steps") || obj.contains_key("runs-on") {
+            report.push(
+                "error",
+                &location,
+                "a job calling a reusable workflow can't have `runs-on` or `steps`".to_string(),
+            );
+        }
+        return;
+    }
+    if !obj.contains_key("runs-on") {
+        report.push("error", &location, "missing `runs-on`".to_string());
+    }
+    match obj.get("steps") {
+        Some(Value::Array(steps)) if !steps.is_empty() => {
+            for (i, step) in steps.iter().enumerate() {
+                check_step(report, step, &format!("{location}.steps[{i}]"));
+            }
+        }
+        _ => report.push("error", &location, "missing or empty `steps`".to_string()),
+    }
+}
+
+fn validate(workflow: &Value) -> Report {
+    let mut report = Report::default();
+    let Some(root) = workflow.as_object() else {
+        report.push("error", "", "workflow must be a mapping".to_string());
+        return report;
+    };
+    report.unknown_keys(root, WORKFLOW_KEYS, "");
+    if !root.contains_key("on") {
+        report.push("error", "", "missing `on` trigger".to_string());
+    }
+    let Some(jobs) = root.get("jobs").and_then(|j| j.as_object()).filter(|j| !j.is_empty()) else {
+        report.push("error", "", "missing or empty `jobs`".to_string());
+        return report;
+    };
+    for (id, job) in jobs {
+        check_job(&mut report, id, job, jobs);
+    }
+    let graph: BTreeMap<String, Vec<String>> = jobs.iter().map(|(id, job)| (id.clone(), needs_of(job))).collect();
+    for cycle in needs_cycles(&graph) {
+        report.push(
+            "error",
+            &format!("jobs.{}", cycle[0]),
+            format!("`needs` cycle: {}", cycle.join(" -> ")),
+        );
+    }
+    report
+}
+
+// =============================================================================
+// Tool implementation
+// =============================================================================
+
+/// Statically checks a workflow: inline `content` (to pre-check an edit
+/// before committing it), or the file at `path` in owner/repo at `ref`.
+/// `valid` is false when any problem has severity error.
+pub(crate) fn validate_workflow(token: &str, args: &DataType) -> FnResult<Json<DataType>> {
+    let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let text = match args.get("content").and_then(|v| v.as_str()) {
+        Some(content) => content.to_string(),
+        None => {
+            let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+            let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+            if owner.is_empty() || repo.is_empty() || path.is_empty() {
+                return Ok(Json(DataType::from_json(
+                    json!({"error": "content, or owner, repo, and path are required"}),
+                )));
+            }
+            let git_ref = args.get("ref").and_then(|v| v.as_str()).filter(|r| !r.is_empty());
+            match fetch_text(token, owner, repo, path, git_ref) {
+                Some(text) => text,
+                None => {
+                    return Ok(Json(DataType::from_json(
+                        json!({"error": format!("could not fetch {path}")}),
+                    )))
+                }
+            }
+        }
+    };
+    let workflow: Value = match serde_yaml::from_str(&text) {
+        Ok(workflow) => workflow,
+        Err(e) => {
+            return Ok(Json(DataType::from_json(json!({
+                "path": path,
+                "valid": false,
+                "problems": [{"severity": "error", "location": "", "message": format!("invalid YAML: {e}")}]
+            }))))
+        }
+    };
+    let report = validate(&workflow);
+    Ok(Json(DataType::from_json(json!({
+        "path": path,
+        "valid": report.count("error") == 0,
+        "errors": report.count("error"),
+        "warnings": report.count("warning"),
+        "problems": report.problems
+    }))))
+}